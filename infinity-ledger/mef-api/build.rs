@@ -0,0 +1,8 @@
+/// Compile the gRPC protobuf definitions with the vendored `protoc`
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if std::env::var_os("PROTOC").is_none() {
+        std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    }
+    tonic_build::compile_protos("proto/mef.proto")?;
+    Ok(())
+}
@@ -0,0 +1,397 @@
+/// Bearer-token authentication with read, write and admin scopes
+///
+/// Each route group is wrapped with [`protect`], which either leaves the group
+/// open (auth disabled or the group is listed in `public_route_groups`) or
+/// requires `Authorization: Bearer <token>`. Safe methods and the queries in
+/// [`READ_ONLY_POST_ROUTES`] need the `read` scope, everything else `write`,
+/// and the routes in [`ADMIN_ROUTES`] `admin`.
+/// Tokens bound to a tenant are only accepted by that tenant's routes.
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{header, Method},
+    middleware::{self, Next},
+    response::Response,
+    Router,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+use crate::{config::ApiConfig, error::ApiError, Result};
+
+/// Access level granted to a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Scope {
+    /// Parse a scope name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "read" => Some(Scope::Read),
+            "write" => Some(Scope::Write),
+            "admin" => Some(Scope::Admin),
+            _ => None,
+        }
+    }
+
+    /// Whether this scope grants `required`; admin implies write implies read
+    pub fn grants(self, required: Scope) -> bool {
+        self >= required
+    }
+}
+
+/// A configured API token
+#[derive(Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiToken {
+    /// Label used in logs instead of the secret
+    #[serde(default)]
+    pub name: String,
+    pub token: String,
+    pub scopes: Vec<Scope>,
+    /// Tenant the token is limited to; unbound tokens reach every tenant
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+impl ApiToken {
+    /// Parse `token:scope+scope[@tenant]` as used in `MEF_API_TOKENS`
+    pub fn parse(spec: &str) -> Option<Self> {
+        let (token, scopes) = spec.trim().rsplit_once(':')?;
+        let (scopes, tenant) = match scopes.split_once('@') {
+            Some((scopes, tenant)) if !tenant.is_empty() => (scopes, Some(tenant.to_string())),
+            Some(_) => return None,
+            None => (scopes, None),
+        };
+        let scopes = scopes
+            .split('+')
+            .map(Scope::parse)
+            .collect::<Option<Vec<_>>>()?;
+        if token.is_empty() || scopes.is_empty() {
+            return None;
+        }
+        Some(Self {
+            name: String::new(),
+            token: token.to_string(),
+            scopes,
+            tenant,
+        })
+    }
+
+    fn grants(&self, required: Scope) -> bool {
+        self.scopes.iter().any(|scope| scope.grants(required))
+    }
+}
+
+impl std::fmt::Debug for ApiToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiToken")
+            .field("name", &self.name)
+            .field("token", &"<redacted>")
+            .field("scopes", &self.scopes)
+            .field("tenant", &self.tenant)
+            .finish()
+    }
+}
+
+/// Routes that need the admin scope regardless of method
+pub const ADMIN_ROUTES: &[(&str, &str)] = &[
+    ("POST", "/commit/rotate"),
+    ("DELETE", "/metatron/cache/clear"),
+    ("POST", "/gate/merkaba/calibrate"),
+    ("GET", "/admin/tenants"),
+    ("POST", "/admin/tenants"),
+    ("GET", "/admin/tenants/:name"),
+    ("DELETE", "/admin/tenants/:name"),
+];
+
+/// POST routes that only query state, so a read token may call them
+pub const READ_ONLY_POST_ROUTES: &[&str] =
+    &["/search", "/tic/query", "/proof/batch", "/memory/search"];
+
+/// Scope needed for a request to `path`
+pub fn required_scope(method: &Method, path: &str) -> Scope {
+    if ADMIN_ROUTES
+        .iter()
+        .any(|(m, p)| *m == method.as_str() && *p == path)
+    {
+        Scope::Admin
+    } else if matches!(*method, Method::GET | Method::HEAD | Method::OPTIONS)
+        || (*method == Method::POST && READ_ONLY_POST_ROUTES.contains(&path))
+    {
+        Scope::Read
+    } else {
+        Scope::Write
+    }
+}
+
+/// Compare two secrets without leaking the position of the first difference
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Configured token matching a presented secret
+pub(crate) fn find_token(config: &ApiConfig, presented: &str) -> Option<ApiToken> {
+    config
+        .all_tokens()
+        .find(|t| constant_time_eq(t.token.as_bytes(), presented.as_bytes()))
+}
+
+/// Check a presented token against the configured ones
+///
+/// A token bound to a tenant is rejected unless `config` serves that tenant.
+///
+/// # Arguments
+/// * `config` - API configuration holding the tokens
+/// * `presented` - Bearer token from the request, if any
+/// * `required` - Scope the route needs
+pub fn authorize(config: &ApiConfig, presented: Option<&str>, required: Scope) -> Result<()> {
+    let presented =
+        presented.ok_or_else(|| ApiError::Unauthorized("Missing bearer token".to_string()))?;
+
+    let token = find_token(config, presented)
+        .ok_or_else(|| ApiError::Unauthorized("Invalid bearer token".to_string()))?;
+
+    if token.tenant.is_some() && token.tenant != config.tenant {
+        Err(ApiError::Forbidden(format!(
+            "Token {:?} is limited to tenant {:?}",
+            token.name,
+            token.tenant.as_deref().unwrap_or_default()
+        )))
+    } else if token.grants(required) {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(format!(
+            "Token {:?} lacks the {:?} scope",
+            token.name, required
+        )))
+    }
+}
+
+/// Token from an `Authorization: Bearer <token>` header value
+pub(crate) fn parse_bearer(value: &str) -> Option<&str> {
+    let (scheme, token) = value.split_once(' ')?;
+    scheme.eq_ignore_ascii_case("bearer").then(|| token.trim())
+}
+
+pub(crate) fn bearer_token(request: &Request) -> Option<&str> {
+    parse_bearer(
+        request
+            .headers()
+            .get(header::AUTHORIZATION)?
+            .to_str()
+            .ok()?,
+    )
+}
+
+/// Whether requests to a route group must carry a token
+pub(crate) fn requires_token(config: &ApiConfig, group: &str) -> bool {
+    config.auth_required && !config.public_route_groups.iter().any(|g| g == group)
+}
+
+async fn require_token(
+    State(config): State<Arc<ApiConfig>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    // Routes are matched before the route layer runs
+    let path = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+
+    authorize(
+        &config,
+        bearer_token(&request),
+        required_scope(request.method(), &path),
+    )?;
+    Ok(next.run(request).await)
+}
+
+/// Require a bearer token for every route of a group
+///
+/// # Arguments
+/// * `router` - Routes of one group
+/// * `config` - API configuration with tokens and public groups
+/// * `group` - Group name matched against `public_route_groups`
+pub fn protect<S>(router: Router<S>, config: &Arc<ApiConfig>, group: &str) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    if !requires_token(config, group) {
+        return router;
+    }
+    router.route_layer(middleware::from_fn_with_state(
+        config.clone(),
+        require_token,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, routing::get};
+    use tower::ServiceExt;
+
+    fn config() -> ApiConfig {
+        ApiConfig {
+            api_token: "root".to_string(),
+            tokens: vec![
+                ApiToken::parse("reader:read").unwrap(),
+                ApiToken::parse("writer:write").unwrap(),
+            ],
+            ..ApiConfig::default()
+        }
+    }
+
+    async fn status(router: &Router, method: &str, path: &str, token: Option<&str>) -> StatusCode {
+        let mut request = Request::builder().method(method).uri(path);
+        if let Some(token) = token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", token));
+        }
+        let request = request.body(Body::empty()).unwrap();
+        router.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[test]
+    fn test_parse_token_specs() {
+        let token = ApiToken::parse("abc:read+write").unwrap();
+        assert_eq!(token.token, "abc");
+        assert_eq!(token.scopes, vec![Scope::Read, Scope::Write]);
+        assert_eq!(token.tenant, None);
+        assert!(ApiToken::parse("abc:root").is_none());
+        let bound = ApiToken::parse("abc:read@acme").unwrap();
+        assert_eq!(bound.scopes, vec![Scope::Read]);
+        assert_eq!(bound.tenant.as_deref(), Some("acme"));
+        assert!(ApiToken::parse("abc:read@").is_none());
+        assert!(ApiToken::parse(":admin").is_none());
+        assert!(Scope::Admin.grants(Scope::Read));
+        assert!(!Scope::Read.grants(Scope::Write));
+    }
+
+    #[tokio::test]
+    async fn test_scopes_are_enforced() {
+        let config = Arc::new(config());
+        let router: Router = protect(
+            Router::new()
+                .route("/items", get(|| async { "ok" }).post(|| async { "ok" }))
+                .route("/commit/rotate", axum::routing::post(|| async { "ok" })),
+            &config,
+            "items",
+        );
+
+        assert_eq!(
+            status(&router, "GET", "/items", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&router, "GET", "/items", Some("wrong")).await,
+            StatusCode::UNAUTHORIZED
+        );
+        assert_eq!(
+            status(&router, "GET", "/items", Some("reader")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&router, "POST", "/items", Some("reader")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(
+            status(&router, "POST", "/items", Some("writer")).await,
+            StatusCode::OK
+        );
+        assert_eq!(
+            status(&router, "POST", "/commit/rotate", Some("writer")).await,
+            StatusCode::FORBIDDEN
+        );
+        // The legacy single token keeps full access
+        assert_eq!(
+            status(&router, "POST", "/commit/rotate", Some("root")).await,
+            StatusCode::OK
+        );
+    }
+
+    #[tokio::test]
+    async fn test_public_groups_and_disabled_auth() {
+        let mut config = config();
+        config.public_route_groups = vec!["health".to_string()];
+        let routes = || Router::new().route("/healthz", get(|| async { "ok" }));
+
+        let public: Router = protect(routes(), &Arc::new(config.clone()), "health");
+        assert_eq!(
+            status(&public, "GET", "/healthz", None).await,
+            StatusCode::OK
+        );
+
+        let guarded: Router = protect(routes(), &Arc::new(config.clone()), "system");
+        assert_eq!(
+            status(&guarded, "GET", "/healthz", None).await,
+            StatusCode::UNAUTHORIZED
+        );
+
+        config.auth_required = false;
+        let open: Router = protect(routes(), &Arc::new(config), "system");
+        assert_eq!(status(&open, "GET", "/healthz", None).await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_posts_need_read_scope() {
+        let config = Arc::new(config());
+        let router: Router = protect(
+            Router::new()
+                .route("/search", axum::routing::post(|| async { "ok" }))
+                .route("/tic/query", axum::routing::post(|| async { "ok" }))
+                .route("/proof/batch", axum::routing::post(|| async { "ok" }))
+                .route("/ingest", axum::routing::post(|| async { "ok" })),
+            &config,
+            "items",
+        );
+
+        for path in ["/search", "/tic/query", "/proof/batch"] {
+            assert_eq!(
+                status(&router, "POST", path, Some("reader")).await,
+                StatusCode::OK,
+                "{}",
+                path
+            );
+        }
+        assert_eq!(
+            status(&router, "POST", "/ingest", Some("reader")).await,
+            StatusCode::FORBIDDEN
+        );
+        assert_eq!(required_scope(&Method::PUT, "/search"), Scope::Write);
+    }
+
+    #[test]
+    fn test_default_token_is_never_accepted() {
+        let config = ApiConfig::default();
+        assert_eq!(config.api_token, crate::config::DEFAULT_API_TOKEN);
+        assert!(config.all_tokens().next().is_none());
+        assert!(authorize(&config, Some(&config.api_token), Scope::Read).is_err());
+        assert!(config.check_auth().is_err());
+
+        let empty = ApiConfig {
+            api_token: String::new(),
+            ..ApiConfig::default()
+        };
+        assert!(empty.check_auth().is_err());
+        let open = ApiConfig {
+            auth_required: false,
+            ..ApiConfig::default()
+        };
+        assert!(open.check_auth().is_ok());
+        assert!(self::config().check_auth().is_ok());
+
+        // The admin secret never reaches the logs
+        let secret = ApiConfig {
+            api_token: "s3cret-admin-token".to_string(),
+            ..ApiConfig::default()
+        };
+        let debug = format!("{:?}", secret);
+        assert!(!debug.contains("s3cret-admin-token"), "{}", debug);
+    }
+}
@@ -5,17 +5,38 @@ use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+use crate::auth::{ApiToken, Scope};
+use crate::tenants::Quotas;
+use crate::{idempotency, jobs};
+use mef_core::gates::GateFsm;
+
+/// Placeholder `api_token`; never accepted as a credential
+pub const DEFAULT_API_TOKEN: &str = "infinity-ledger-token";
+
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ApiConfig {
     /// Server port
     pub port: u16,
 
-    /// API token for authentication
+    /// gRPC server port
+    #[serde(default = "default_grpc_port")]
+    pub grpc_port: u16,
+
+    /// API token for authentication (grants the admin scope); the built-in
+    /// [`DEFAULT_API_TOKEN`] is ignored
     pub api_token: String,
 
+    /// Additional tokens with individual scopes
+    #[serde(default)]
+    pub tokens: Vec<ApiToken>,
+
     /// Whether authentication is required
     pub auth_required: bool,
 
+    /// Route groups served without authentication
+    #[serde(default = "default_public_route_groups")]
+    pub public_route_groups: Vec<String>,
+
     /// Store directory path
     pub store_path: PathBuf,
 
@@ -39,6 +60,62 @@ pub struct ApiConfig {
 
     /// Metrics window size
     pub metrics_window: usize,
+
+    /// Seconds the gate FSM stays in cooldown after a decision
+    #[serde(default = "default_gate_cooldown_secs")]
+    pub gate_cooldown_secs: u64,
+
+    /// Background job worker tasks
+    #[serde(default = "default_job_workers")]
+    pub job_workers: usize,
+
+    /// Attempts per background job before it is marked failed
+    #[serde(default = "default_job_max_attempts")]
+    pub job_max_attempts: u32,
+
+    /// How long responses are kept for `Idempotency-Key` replays
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+
+    /// Seconds to wait for in-flight requests when shutting down
+    #[serde(default = "default_shutdown_timeout_secs")]
+    pub shutdown_timeout_secs: u64,
+
+    /// Limits on stored vectors and ledger blocks
+    #[serde(default)]
+    pub quotas: Quotas,
+
+    /// Tenant served with this configuration; `None` for the default namespace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tenant: Option<String>,
+}
+
+fn default_grpc_port() -> u16 {
+    50051
+}
+
+fn default_gate_cooldown_secs() -> u64 {
+    GateFsm::DEFAULT_COOLDOWN.as_secs()
+}
+
+fn default_job_workers() -> usize {
+    jobs::DEFAULT_WORKERS
+}
+
+fn default_job_max_attempts() -> u32 {
+    jobs::DEFAULT_MAX_ATTEMPTS
+}
+
+fn default_idempotency_ttl_secs() -> u64 {
+    idempotency::DEFAULT_TTL.as_secs()
+}
+
+fn default_shutdown_timeout_secs() -> u64 {
+    30
+}
+
+fn default_public_route_groups() -> Vec<String> {
+    vec!["health".to_string()]
 }
 
 impl Default for ApiConfig {
@@ -48,8 +125,11 @@ impl Default for ApiConfig {
 
         Self {
             port: 8000,
-            api_token: "infinity-ledger-token".to_string(),
+            grpc_port: default_grpc_port(),
+            api_token: DEFAULT_API_TOKEN.to_string(),
+            tokens: Vec::new(),
             auth_required: true,
+            public_route_groups: default_public_route_groups(),
             store_path: mef_home.join("store"),
             ledger_path: mef_home.join("ledger"),
             logs_path: mef_home.join("logs"),
@@ -58,10 +138,45 @@ impl Default for ApiConfig {
             quality_collection: "spiral".to_string(),
             quality_metric: "cosine".to_string(),
             metrics_window: 10000,
+            gate_cooldown_secs: default_gate_cooldown_secs(),
+            job_workers: default_job_workers(),
+            job_max_attempts: default_job_max_attempts(),
+            idempotency_ttl_secs: default_idempotency_ttl_secs(),
+            shutdown_timeout_secs: default_shutdown_timeout_secs(),
+            quotas: Quotas::default(),
+            tenant: None,
         }
     }
 }
 
+impl std::fmt::Debug for ApiConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ApiConfig")
+            .field("port", &self.port)
+            .field("grpc_port", &self.grpc_port)
+            .field("api_token", &"<redacted>")
+            .field("tokens", &self.tokens)
+            .field("auth_required", &self.auth_required)
+            .field("public_route_groups", &self.public_route_groups)
+            .field("store_path", &self.store_path)
+            .field("ledger_path", &self.ledger_path)
+            .field("logs_path", &self.logs_path)
+            .field("seed", &self.seed)
+            .field("eps_pi", &self.eps_pi)
+            .field("quality_collection", &self.quality_collection)
+            .field("quality_metric", &self.quality_metric)
+            .field("metrics_window", &self.metrics_window)
+            .field("gate_cooldown_secs", &self.gate_cooldown_secs)
+            .field("job_workers", &self.job_workers)
+            .field("job_max_attempts", &self.job_max_attempts)
+            .field("idempotency_ttl_secs", &self.idempotency_ttl_secs)
+            .field("shutdown_timeout_secs", &self.shutdown_timeout_secs)
+            .field("quotas", &self.quotas)
+            .field("tenant", &self.tenant)
+            .finish()
+    }
+}
+
 impl ApiConfig {
     /// Load configuration from environment variables and config file
     pub fn load() -> Result<Self> {
@@ -72,14 +187,26 @@ impl ApiConfig {
             config.port = port.parse().context("Invalid MEF_API_PORT")?;
         }
 
+        if let Ok(port) = env::var("MEF_GRPC_PORT") {
+            config.grpc_port = port.parse().context("Invalid MEF_GRPC_PORT")?;
+        }
+
         if let Ok(token) = env::var("MEF_API_TOKEN") {
             config.api_token = token;
         }
 
+        if let Ok(specs) = env::var("MEF_API_TOKENS") {
+            config.tokens = Self::parse_tokens(&specs)?;
+        }
+
         if let Ok(auth) = env::var("AUTH_TOKEN_REQUIRED") {
             config.auth_required = Self::parse_bool(&auth);
         }
 
+        if let Ok(groups) = env::var("MEF_AUTH_PUBLIC_GROUPS") {
+            config.public_route_groups = Self::parse_list(&groups);
+        }
+
         if let Ok(store) = env::var("MEF_STORE_DIR") {
             config.store_path = PathBuf::from(store);
         }
@@ -100,6 +227,36 @@ impl ApiConfig {
             config.eps_pi = eps.parse().context("Invalid MEF_EPS_PI")?;
         }
 
+        if let Ok(secs) = env::var("MEF_GATE_COOLDOWN_SECS") {
+            config.gate_cooldown_secs = secs.parse().context("Invalid MEF_GATE_COOLDOWN_SECS")?;
+        }
+
+        if let Ok(workers) = env::var("MEF_JOB_WORKERS") {
+            config.job_workers = workers.parse().context("Invalid MEF_JOB_WORKERS")?;
+        }
+
+        if let Ok(attempts) = env::var("MEF_JOB_MAX_ATTEMPTS") {
+            config.job_max_attempts = attempts.parse().context("Invalid MEF_JOB_MAX_ATTEMPTS")?;
+        }
+
+        if let Ok(ttl) = env::var("MEF_IDEMPOTENCY_TTL_SECS") {
+            config.idempotency_ttl_secs =
+                ttl.parse().context("Invalid MEF_IDEMPOTENCY_TTL_SECS")?;
+        }
+
+        if let Ok(secs) = env::var("MEF_SHUTDOWN_TIMEOUT_SECS") {
+            config.shutdown_timeout_secs =
+                secs.parse().context("Invalid MEF_SHUTDOWN_TIMEOUT_SECS")?;
+        }
+
+        if let Ok(max) = env::var("MEF_MAX_VECTORS") {
+            config.quotas.max_vectors = Some(max.parse().context("Invalid MEF_MAX_VECTORS")?);
+        }
+
+        if let Ok(max) = env::var("MEF_MAX_BLOCKS") {
+            config.quotas.max_blocks = Some(max.parse().context("Invalid MEF_MAX_BLOCKS")?);
+        }
+
         if let Ok(collection) = env::var("QUALITY_COLLECTION") {
             config.quality_collection = collection;
         }
@@ -118,6 +275,8 @@ impl ApiConfig {
             Self::load_from_file(&mut config, config_path)?;
         }
 
+        config.check_auth()?;
+
         // Ensure directories exist
         for path in [&config.store_path, &config.ledger_path, &config.logs_path] {
             fs::create_dir_all(path)
@@ -143,11 +302,114 @@ impl ApiConfig {
             if let Some(port) = yaml_map.get("port").and_then(|v| v.as_u64()) {
                 config.port = port as u16;
             }
+
+            if let Some(port) = yaml_map.get("grpc_port").and_then(|v| v.as_u64()) {
+                config.grpc_port = port as u16;
+            }
+
+            if let Some(secs) = yaml_map.get("gate_cooldown_secs").and_then(|v| v.as_u64()) {
+                config.gate_cooldown_secs = secs;
+            }
+
+            if let Some(workers) = yaml_map.get("job_workers").and_then(|v| v.as_u64()) {
+                config.job_workers = workers as usize;
+            }
+
+            if let Some(attempts) = yaml_map.get("job_max_attempts").and_then(|v| v.as_u64()) {
+                config.job_max_attempts = attempts as u32;
+            }
+
+            if let Some(ttl) = yaml_map
+                .get("idempotency_ttl_secs")
+                .and_then(|v| v.as_u64())
+            {
+                config.idempotency_ttl_secs = ttl;
+            }
+
+            if let Some(secs) = yaml_map
+                .get("shutdown_timeout_secs")
+                .and_then(|v| v.as_u64())
+            {
+                config.shutdown_timeout_secs = secs;
+            }
+
+            if let Some(quotas) = yaml_map.get("quotas") {
+                config.quotas = serde_yaml::from_value(quotas.clone())
+                    .with_context(|| format!("Invalid quotas in {:?}", path))?;
+            }
+
+            if let Some(tokens) = yaml_map.get("tokens") {
+                config.tokens = serde_yaml::from_value(tokens.clone())
+                    .with_context(|| format!("Invalid tokens in {:?}", path))?;
+            }
+
+            if let Some(groups) = yaml_map.get("public_route_groups") {
+                config.public_route_groups = serde_yaml::from_value(groups.clone())
+                    .with_context(|| format!("Invalid public_route_groups in {:?}", path))?;
+            }
         }
 
         Ok(())
     }
 
+    /// Refuse to serve with authentication on but no real token configured
+    ///
+    /// The default `api_token` is published with the source, so leaving it
+    /// in place must not silently open the server.
+    pub fn check_auth(&self) -> Result<()> {
+        if !self.auth_required {
+            return Ok(());
+        }
+        if self.api_token == DEFAULT_API_TOKEN {
+            anyhow::bail!(
+                "Authentication is required but MEF_API_TOKEN is unset; set it to a secret \
+                 (or set AUTH_TOKEN_REQUIRED=false)"
+            );
+        }
+        if self.api_token.is_empty() && self.tokens.is_empty() {
+            anyhow::bail!(
+                "Authentication is required but neither MEF_API_TOKEN nor MEF_API_TOKENS is set"
+            );
+        }
+        Ok(())
+    }
+
+    /// All accepted tokens, including `api_token` as an admin token unless it
+    /// is empty or the built-in default
+    pub fn all_tokens(&self) -> impl Iterator<Item = ApiToken> + '_ {
+        let configured = !self.api_token.is_empty() && self.api_token != DEFAULT_API_TOKEN;
+        let legacy = configured.then(|| ApiToken {
+            name: "default".to_string(),
+            token: self.api_token.clone(),
+            scopes: vec![Scope::Admin],
+            tenant: None,
+        });
+        legacy.into_iter().chain(self.tokens.iter().cloned())
+    }
+
+    /// Parse comma-separated `token:scope+scope[@tenant]` entries
+    fn parse_tokens(specs: &str) -> Result<Vec<ApiToken>> {
+        Self::parse_list(specs)
+            .iter()
+            .enumerate()
+            .map(|(i, spec)| {
+                let mut token = ApiToken::parse(spec)
+                    .with_context(|| format!("Invalid MEF_API_TOKENS entry #{}", i + 1))?;
+                token.name = format!("env-{}", i + 1);
+                Ok(token)
+            })
+            .collect()
+    }
+
+    fn parse_list(value: &str) -> Vec<String> {
+        value
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    }
+
     fn parse_bool(value: &str) -> bool {
         matches!(
             value.trim().to_lowercase().as_str(),
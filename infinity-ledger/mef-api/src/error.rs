@@ -1,6 +1,6 @@
 /// Error types for API server
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -19,6 +19,15 @@ pub enum ApiError {
     #[error("Unauthorized: {0}")]
     Unauthorized(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Unprocessable: {0}")]
+    Unprocessable(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 
@@ -35,28 +44,74 @@ pub enum ApiError {
     VectorDB(String),
 }
 
+impl ApiError {
+    /// HTTP status and client-facing message
+    pub fn parts(&self) -> (StatusCode, &str) {
+        match self {
+            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
+            ApiError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg),
+            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            ApiError::Unprocessable(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg),
+            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ApiError::Storage(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ApiError::Ledger(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ApiError::Processing(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+            ApiError::VectorDB(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
+        }
+    }
+
+    /// Rebuild an error from a status and message recorded by [`ApiError::parts`]
+    pub fn from_parts(status: u16, message: String) -> Self {
+        match status {
+            400 => ApiError::InvalidInput(message),
+            401 => ApiError::Unauthorized(message),
+            403 => ApiError::Forbidden(message),
+            404 => ApiError::NotFound(message),
+            409 => ApiError::Conflict(message),
+            422 => ApiError::Unprocessable(message),
+            _ => ApiError::Internal(message),
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
-            ApiError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
-            ApiError::InvalidInput(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
-            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
-            ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ApiError::Storage(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ApiError::Ledger(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ApiError::Processing(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-            ApiError::VectorDB(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
-        };
+        let (status, error_message) = self.parts();
 
         let body = Json(json!({
             "error": error_message,
             "status": status.as_u16(),
         }));
 
+        if status == StatusCode::UNAUTHORIZED {
+            return (
+                status,
+                [(header::WWW_AUTHENTICATE, "Bearer realm=\"mef\"")],
+                body,
+            )
+                .into_response();
+        }
+
         (status, body).into_response()
     }
 }
 
+impl From<ApiError> for tonic::Status {
+    fn from(err: ApiError) -> Self {
+        match err {
+            ApiError::NotFound(msg) => tonic::Status::not_found(msg),
+            ApiError::InvalidInput(msg) => tonic::Status::invalid_argument(msg),
+            ApiError::Unauthorized(msg) => tonic::Status::unauthenticated(msg),
+            ApiError::Forbidden(msg) => tonic::Status::permission_denied(msg),
+            ApiError::Conflict(msg) => tonic::Status::failed_precondition(msg),
+            ApiError::Unprocessable(msg) => tonic::Status::invalid_argument(msg),
+            other => tonic::Status::internal(other.to_string()),
+        }
+    }
+}
+
 // Convenience conversions
 impl From<anyhow::Error> for ApiError {
     fn from(err: anyhow::Error) -> Self {
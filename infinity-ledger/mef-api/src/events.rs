@@ -0,0 +1,278 @@
+/// Publish/subscribe bus for live pipeline events
+///
+/// Events get increasing IDs and the most recent ones are kept in memory, so
+/// a subscriber that reconnects with the last ID it saw receives everything
+/// it missed (as long as it is still retained) before live events. IDs are
+/// prefixed with the server start time, so they keep increasing across
+/// restarts; a resume ID beyond the current head replays the whole backlog.
+/// Published events are also recorded in the audit log, if one is attached.
+use chrono::Utc;
+use mef_audit::{EventSeverity, MEFAuditLogger};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{broadcast, watch};
+
+/// Events kept for resuming subscribers
+pub const DEFAULT_HISTORY: usize = 1024;
+
+/// Bits of an event ID counting events within one server run
+///
+/// The remaining bits hold the start time in seconds, which keeps IDs below
+/// 2^53 so JavaScript clients can compare them exactly.
+const RUN_ID_BITS: u32 = 20;
+
+/// Kind of a bus event
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    /// A block was appended to the ledger
+    BlockAppended,
+    /// A TIC was crystallized
+    TicCrystallized,
+    /// The Merkaba gate decided FIRE or HOLD
+    GateDecision,
+    /// A vector index was rebuilt
+    IndexRebuilt,
+}
+
+impl EventKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EventKind::BlockAppended => "block_appended",
+            EventKind::TicCrystallized => "tic_crystallized",
+            EventKind::GateDecision => "gate_decision",
+            EventKind::IndexRebuilt => "index_rebuilt",
+        }
+    }
+
+    /// Parse an event kind name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "block_appended" => Some(EventKind::BlockAppended),
+            "tic_crystallized" => Some(EventKind::TicCrystallized),
+            "gate_decision" => Some(EventKind::GateDecision),
+            "index_rebuilt" => Some(EventKind::IndexRebuilt),
+            _ => None,
+        }
+    }
+}
+
+/// An event published on the bus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Event {
+    pub id: u64,
+    pub kind: EventKind,
+    pub timestamp: String,
+    pub data: JsonValue,
+}
+
+struct History {
+    next_id: u64,
+    events: VecDeque<Event>,
+}
+
+/// In-process event bus shared through [`crate::AppState`]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+    history: Mutex<History>,
+    capacity: usize,
+    /// Set once live streams should end
+    closed: watch::Sender<bool>,
+    /// Audit log recording every published event
+    audit: Option<Arc<Mutex<MEFAuditLogger>>>,
+}
+
+/// Subscription returned by [`EventBus::subscribe`]
+pub struct Subscription {
+    /// Retained events after the requested ID, oldest first
+    pub backlog: Vec<Event>,
+    /// Events published after the backlog was taken
+    pub live: broadcast::Receiver<Event>,
+}
+
+impl EventBus {
+    /// Create a bus retaining up to `capacity` events
+    pub fn new(capacity: usize) -> Self {
+        let started = Utc::now().timestamp().max(0) as u64;
+        Self::starting_at(capacity, (started << RUN_ID_BITS) + 1)
+    }
+
+    /// Create a bus whose first event gets `first_id`
+    fn starting_at(capacity: usize, first_id: u64) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self {
+            sender,
+            history: Mutex::new(History {
+                next_id: first_id,
+                events: VecDeque::with_capacity(capacity),
+            }),
+            capacity,
+            closed: watch::channel(false).0,
+            audit: None,
+        }
+    }
+
+    /// Record every published event in `audit`
+    pub fn set_audit_log(&mut self, audit: Arc<Mutex<MEFAuditLogger>>) {
+        self.audit = Some(audit);
+    }
+
+    /// Publish an event to all current subscribers
+    ///
+    /// # Arguments
+    /// * `kind` - Event kind
+    /// * `data` - Event payload
+    pub fn publish(&self, kind: EventKind, data: JsonValue) -> Event {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let event = Event {
+            id: history.next_id,
+            kind,
+            timestamp: Utc::now().to_rfc3339(),
+            data,
+        };
+        history.next_id += 1;
+        if history.events.len() == self.capacity {
+            history.events.pop_front();
+        }
+        history.events.push_back(event.clone());
+
+        // Sent under the history lock so backlog and live events never overlap
+        let _ = self.sender.send(event.clone());
+        drop(history);
+
+        if let Some(audit) = &self.audit {
+            let mut audit = audit.lock().unwrap_or_else(|e| e.into_inner());
+            let event_type = kind.as_str().to_uppercase();
+            if let Err(e) = audit.log_event(
+                &event_type,
+                "mef-api",
+                event.data.clone(),
+                EventSeverity::Info,
+            ) {
+                tracing::warn!("Failed to audit event {}: {}", event.id, e);
+            }
+        }
+        event
+    }
+
+    /// End the live streams of all subscribers, e.g. before shutting down
+    pub fn close(&self) {
+        self.closed.send_replace(true);
+    }
+
+    /// Whether [`close`](Self::close) was called
+    pub fn is_closed(&self) -> bool {
+        *self.closed.borrow()
+    }
+
+    /// Resolves once the bus is closed
+    pub fn closed(&self) -> impl std::future::Future<Output = ()> + Send + 'static {
+        let mut closed = self.closed.subscribe();
+        async move {
+            let _ = closed.wait_for(|closed| *closed).await;
+        }
+    }
+
+    /// Subscribe to events published after `last_event_id`
+    ///
+    /// An ID this bus has not reached yet (e.g. from a server whose clock ran
+    /// ahead) cannot be placed, so the whole retained backlog is replayed.
+    ///
+    /// # Arguments
+    /// * `last_event_id` - Last ID the subscriber saw; `None` for live events only
+    pub fn subscribe(&self, last_event_id: Option<u64>) -> Subscription {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let head = history.next_id - 1;
+        let backlog = match last_event_id {
+            Some(last) if last > head => history.events.iter().cloned().collect(),
+            Some(last) => history
+                .events
+                .iter()
+                .filter(|event| event.id > last)
+                .cloned()
+                .collect(),
+            None => Vec::new(),
+        };
+        Subscription {
+            backlog,
+            live: self.sender.subscribe(),
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_live_events() {
+        let bus = EventBus::default();
+        let mut subscription = bus.subscribe(None);
+        assert!(subscription.backlog.is_empty());
+
+        let published = bus.publish(EventKind::GateDecision, json!({"decision": "FIRE"}));
+        let received = subscription.live.recv().await.unwrap();
+        assert_eq!(received.id, published.id);
+        assert_eq!(received.kind, EventKind::GateDecision);
+    }
+
+    #[test]
+    fn test_resume_replays_retained_events() {
+        let bus = EventBus::starting_at(3, 1);
+        for i in 0..5 {
+            bus.publish(EventKind::BlockAppended, json!({ "index": i }));
+        }
+
+        // IDs 1 and 2 were evicted
+        let ids: Vec<u64> = bus
+            .subscribe(Some(0))
+            .backlog
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec![3, 4, 5]);
+
+        let ids: Vec<u64> = bus
+            .subscribe(Some(4))
+            .backlog
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec![5]);
+        assert!(bus.subscribe(Some(5)).backlog.is_empty());
+    }
+
+    #[test]
+    fn test_ids_keep_increasing_across_restarts() {
+        // Last event of a run that started a second earlier
+        let earlier = (Utc::now().timestamp() as u64 - 1) << RUN_ID_BITS;
+        let last = earlier + 1000;
+
+        let after = EventBus::new(8);
+        let first = after.publish(EventKind::GateDecision, json!({})).id;
+        assert!(first > last);
+        assert!(first < 1 << 53);
+        let ids: Vec<u64> = after
+            .subscribe(Some(last))
+            .backlog
+            .iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, vec![first]);
+
+        // An ID from the future replays everything retained
+        let ahead = EventBus::starting_at(8, 1);
+        ahead.publish(EventKind::BlockAppended, json!({}));
+        ahead.publish(EventKind::BlockAppended, json!({}));
+        assert_eq!(ahead.subscribe(Some(first)).backlog.len(), 2);
+    }
+}
@@ -0,0 +1,358 @@
+/// gRPC interface mirroring the REST endpoints
+///
+/// The service shares [`AppState`] with the axum server and calls the same
+/// route handlers, so both interfaces return identical results. Requests are
+/// authorized like the REST route they mirror, with the bearer token taken
+/// from the `authorization` metadata; the `grpc` route group can be made
+/// public through `public_route_groups`.
+use axum::{extract::State, http::Method, Json};
+use serde_json::Value as JsonValue;
+use tokio::{net::TcpListener, sync::mpsc};
+use tokio_stream::{
+    wrappers::{ReceiverStream, TcpListenerStream},
+    StreamExt,
+};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::{auth, models, routes, AppState};
+use mef_ledger::LedgerSnapshot;
+
+/// Generated protobuf messages and service stubs (`proto/mef.proto`)
+pub mod proto {
+    tonic::include_proto!("mef.v1");
+}
+
+use proto::mef_server::{Mef, MefServer};
+
+/// Route group name used for `public_route_groups`
+pub const ROUTE_GROUP: &str = "grpc";
+
+/// Messages buffered per streaming call before the sender waits
+const STREAM_CAPACITY: usize = 16;
+
+/// Default number of search results, as for `POST /search`
+const DEFAULT_TOP_K: usize = 5;
+
+/// gRPC service backed by the shared application state
+#[derive(Clone)]
+pub struct MefService {
+    state: AppState,
+}
+
+impl MefService {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    /// Check the caller's token against the scope of the mirrored REST route
+    ///
+    /// # Arguments
+    /// * `request` - Incoming gRPC request
+    /// * `method` - HTTP method of the mirrored route
+    /// * `route` - Path of the mirrored route
+    fn authorize<T>(&self, request: &Request<T>, method: Method, route: &str) -> crate::Result<()> {
+        let config = &self.state.config;
+        if !auth::requires_token(config, ROUTE_GROUP) {
+            return Ok(());
+        }
+        let presented = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok())
+            .and_then(auth::parse_bearer);
+        auth::authorize(config, presented, auth::required_scope(&method, route))
+    }
+}
+
+/// Build the tonic service for the shared state
+pub fn service(state: AppState) -> MefServer<MefService> {
+    MefServer::new(MefService::new(state))
+}
+
+/// Serve the gRPC interface on an already bound listener
+///
+/// # Arguments
+/// * `state` - Application state shared with the REST server
+/// * `listener` - Listener for incoming HTTP/2 connections
+pub async fn serve(state: AppState, listener: TcpListener) -> anyhow::Result<()> {
+    serve_with_shutdown(state, listener, std::future::pending()).await
+}
+
+/// Serve the gRPC interface until `signal` resolves, then let in-flight
+/// calls finish
+///
+/// # Arguments
+/// * `state` - Application state shared with the REST server
+/// * `listener` - Listener for incoming HTTP/2 connections
+/// * `signal` - Resolves when the server should stop accepting calls
+pub async fn serve_with_shutdown(
+    state: AppState,
+    listener: TcpListener,
+    signal: impl std::future::Future<Output = ()>,
+) -> anyhow::Result<()> {
+    tonic::transport::Server::builder()
+        .add_service(service(state))
+        .serve_with_incoming_shutdown(TcpListenerStream::new(listener), signal)
+        .await?;
+    Ok(())
+}
+
+async fn ingest(
+    state: &AppState,
+    request: proto::IngestRequest,
+) -> Result<proto::IngestResponse, Status> {
+    let Json(response) = routes::ingest::ingest(
+        State(state.clone()),
+        Json(models::IngestRequest {
+            data: request.data,
+            data_type: request.data_type,
+            seed: request.seed,
+        }),
+    )
+    .await?;
+
+    Ok(proto::IngestResponse {
+        snapshot_id: response.snapshot_id,
+        phase: response.phase,
+        por: response.por,
+        hash: response.hash,
+        timestamp: response.timestamp,
+    })
+}
+
+/// Convert a block as serialized by the ledger into its message
+fn block_message(block: JsonValue) -> proto::Block {
+    let text = |key: &str| block[key].as_str().unwrap_or_default().to_string();
+    proto::Block {
+        index: block["index"].as_u64().unwrap_or_default(),
+        hash: text("hash"),
+        previous_hash: text("previous_hash"),
+        timestamp: text("timestamp"),
+        tic_id: text("tic_id"),
+        json: block.to_string(),
+    }
+}
+
+#[tonic::async_trait]
+impl Mef for MefService {
+    type IngestStreamStream = ReceiverStream<Result<proto::IngestResponse, Status>>;
+    type StreamBlocksStream = ReceiverStream<Result<proto::Block, Status>>;
+
+    async fn ingest(
+        &self,
+        request: Request<proto::IngestRequest>,
+    ) -> Result<Response<proto::IngestResponse>, Status> {
+        self.authorize(&request, Method::POST, "/ingest")?;
+        Ok(Response::new(
+            ingest(&self.state, request.into_inner()).await?,
+        ))
+    }
+
+    async fn ingest_stream(
+        &self,
+        request: Request<Streaming<proto::IngestRequest>>,
+    ) -> Result<Response<Self::IngestStreamStream>, Status> {
+        self.authorize(&request, Method::POST, "/ingest")?;
+        let mut inbound = request.into_inner();
+        let state = self.state.clone();
+        let (tx, rx) = mpsc::channel(STREAM_CAPACITY);
+
+        tokio::spawn(async move {
+            while let Some(message) = inbound.next().await {
+                let result = match message {
+                    Ok(message) => ingest(&state, message).await,
+                    Err(status) => Err(status),
+                };
+                let failed = result.is_err();
+                // Stop once the client is gone or after the first error
+                if tx.send(result).await.is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn process(
+        &self,
+        request: Request<proto::ProcessRequest>,
+    ) -> Result<Response<proto::ProcessResponse>, Status> {
+        self.authorize(&request, Method::POST, "/process")?;
+        let request = request.into_inner();
+        let Json(response) = routes::process::process(
+            State(self.state.clone()),
+            Json(models::ProcessRequest {
+                snapshot_id: request.snapshot_id,
+                commit: Some(request.commit),
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(proto::ProcessResponse {
+            tic_id: response.tic_id,
+            converged: response.converged,
+            iterations: response.iterations as u64,
+            final_eigenvalue: response.final_eigenvalue,
+            timestamp: response.timestamp,
+        }))
+    }
+
+    async fn append_block(
+        &self,
+        request: Request<proto::AppendBlockRequest>,
+    ) -> Result<Response<proto::AppendBlockResponse>, Status> {
+        self.authorize(&request, Method::POST, "/ledger")?;
+        let request = request.into_inner();
+        let Json(response) = routes::ledger::append_ledger(
+            State(self.state.clone()),
+            Json(models::LedgerAppendRequest {
+                tic_id: request.tic_id,
+                snapshot_id: request.snapshot_id,
+                expected_previous_hash: request.expected_previous_hash,
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(proto::AppendBlockResponse {
+            block_index: response.block_index as u64,
+            block_hash: response.block_hash,
+            timestamp: response.timestamp,
+        }))
+    }
+
+    async fn get_block(
+        &self,
+        request: Request<proto::GetBlockRequest>,
+    ) -> Result<Response<proto::Block>, Status> {
+        self.authorize(&request, Method::GET, "/ledger/:index")?;
+        let index = usize::try_from(request.into_inner().index)
+            .map_err(|_| Status::invalid_argument("Block index out of range"))?;
+        let Json(block) =
+            routes::ledger::get_block(State(self.state.clone()), axum::extract::Path(index))
+                .await?;
+        Ok(Response::new(block_message(block)))
+    }
+
+    async fn stream_blocks(
+        &self,
+        request: Request<proto::StreamBlocksRequest>,
+    ) -> Result<Response<Self::StreamBlocksStream>, Status> {
+        self.authorize(&request, Method::GET, "/ledger/:index")?;
+        let request = request.into_inner();
+        let to = request.to.unwrap_or(u64::MAX);
+
+        // Stream from a snapshot so appends are not blocked while the client reads
+        let ledger_path = self.state.config.ledger_path.clone();
+        let ledger = tokio::task::spawn_blocking(move || LedgerSnapshot::open(ledger_path))
+            .await
+            .map_err(|e| Status::internal(format!("Ledger task failed: {}", e)))?
+            .map_err(|e| Status::internal(format!("Failed to open ledger: {}", e)))?;
+
+        let (tx, rx) = mpsc::channel(STREAM_CAPACITY);
+        tokio::task::spawn_blocking(move || {
+            let indexes = ledger
+                .index()
+                .blocks
+                .iter()
+                .map(|summary| summary.index)
+                .filter(|&index| (request.from..=to).contains(&(index as u64)));
+            for index in indexes {
+                let result = match ledger.get_block(index) {
+                    Ok(Some(block)) => serde_json::to_value(&block)
+                        .map(block_message)
+                        .map_err(|e| Status::internal(format!("Failed to serialize block: {}", e))),
+                    Ok(None) => Err(Status::not_found(format!("Block {} not found", index))),
+                    Err(e) => Err(Status::internal(format!(
+                        "Failed to read block {}: {}",
+                        index, e
+                    ))),
+                };
+                let failed = result.is_err();
+                if tx.blocking_send(result).is_err() || failed {
+                    break;
+                }
+            }
+        });
+
+        Ok(Response::new(ReceiverStream::new(rx)))
+    }
+
+    async fn search(
+        &self,
+        request: Request<proto::SearchRequest>,
+    ) -> Result<Response<proto::SearchResponse>, Status> {
+        self.authorize(&request, Method::POST, "/search")?;
+        let request = request.into_inner();
+        let top_k = match request.top_k {
+            0 => DEFAULT_TOP_K,
+            k => k as usize,
+        };
+        let Json(response) = routes::vector::search(
+            State(self.state.clone()),
+            Json(models::SearchRequest {
+                collection: request.collection,
+                query_vector: request.query_vector,
+                top_k,
+                membership_proof: false,
+                pipeline_proof: false,
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(proto::SearchResponse {
+            results: response
+                .results
+                .into_iter()
+                .map(|result| proto::SearchResult {
+                    id: result.id,
+                    score: result.score,
+                    metadata_json: result
+                        .metadata
+                        .map(|metadata| serde_json::json!(metadata).to_string())
+                        .unwrap_or_default(),
+                })
+                .collect(),
+            collection: response.collection,
+            query_time_ms: response.query_time_ms,
+        }))
+    }
+
+    async fn evaluate_gate(
+        &self,
+        request: Request<proto::GateRequest>,
+    ) -> Result<Response<proto::GateResponse>, Status> {
+        self.authorize(&request, Method::POST, "/gate/merkaba")?;
+        let request = request.into_inner();
+        let Json(response) = routes::merkaba::evaluate_merkaba_gate(
+            State(self.state.clone()),
+            Json(routes::merkaba::MerkabaGateRequest {
+                snapshot_id: request.snapshot_id,
+                tic_candidate_id: request.tic_candidate_id,
+                params: request.params.map(|params| routes::merkaba::GateParams {
+                    epsilon: params.epsilon,
+                    phi_star: params.phi_star,
+                    eta: params.eta,
+                }),
+            }),
+        )
+        .await?;
+
+        Ok(Response::new(proto::GateResponse {
+            gate_id: response.gate_id,
+            snapshot_id: response.snapshot_id,
+            tic_candidate_id: response.tic_candidate_id,
+            checks: Some(proto::GateChecks {
+                por: response.checks.por,
+                delta_pi: response.checks.delta_pi,
+                phi: response.checks.phi,
+                delta_v: response.checks.delta_v,
+                mci: response.checks.mci,
+            }),
+            commit: response.decision.commit,
+            reason: response.decision.reason,
+            timestamp: response.timestamp,
+            ledger_block_id: response.ledger_block_id,
+        }))
+    }
+}
@@ -0,0 +1,558 @@
+/// `Idempotency-Key` support for mutating endpoints
+///
+/// A request to one of the [`IDEMPOTENT_ROUTES`] carrying an
+/// `Idempotency-Key` header is fingerprinted (method, path and body) and its
+/// response is stored for the configured TTL. Retrying with the same key and
+/// body replays the stored response, marked with `Idempotent-Replayed: true`,
+/// instead of running the handler again. Reusing a key with a different body
+/// is rejected with `422`, and a retry arriving while the first request is
+/// still running with `409`. A request the client abandons keeps running
+/// and its response is still stored. Server errors are not stored, so such
+/// requests can be retried with the same key.
+///
+/// Keys are scoped to the `Authorization` header, so clients with different
+/// tokens cannot observe each other's responses. Stored responses are kept
+/// as `<scope>.json` files and survive restarts.
+use anyhow::Context;
+use axum::{
+    body::Body,
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::{error::ApiError, Result};
+
+/// Request header carrying the client-chosen key
+pub const IDEMPOTENCY_KEY: &str = "idempotency-key";
+/// Response header set on replayed responses
+pub const IDEMPOTENT_REPLAYED: &str = "idempotent-replayed";
+/// Default time responses are kept for replay
+pub const DEFAULT_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// Longest accepted key
+const MAX_KEY_LEN: usize = 255;
+/// Largest request or response body buffered for fingerprinting and replay,
+/// the same as axum's default request body limit
+const MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+/// Minimum time between sweeps for expired entries
+const PRUNE_INTERVAL: chrono::Duration = chrono::Duration::seconds(60);
+
+/// Routes honoring `Idempotency-Key`, as (method, route) pairs
+pub const IDEMPOTENT_ROUTES: &[(&str, &str)] = &[
+    ("POST", "/ledger"),
+    ("POST", "/ingest"),
+    ("POST", "/collections/:name/upsert"),
+];
+
+/// Whether requests to `path` honor `Idempotency-Key`
+pub fn is_idempotent_route(method: &Method, path: &str) -> bool {
+    IDEMPOTENT_ROUTES
+        .iter()
+        .any(|(m, p)| *m == method.as_str() && *p == path)
+}
+
+/// Response kept for replay
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct StoredResponse {
+    status: u16,
+    #[serde(default)]
+    content_type: Option<String>,
+    body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+    /// Hash of method, path and body of the first request
+    fingerprint: String,
+    expires_at: DateTime<Utc>,
+    /// `None` while the first request is running
+    response: Option<StoredResponse>,
+}
+
+/// Outcome of [`IdempotencyStore::begin`]
+enum Begin {
+    /// First use of the key; the caller runs the request and records it
+    Started,
+    /// The key was used for this request before
+    Replay(StoredResponse),
+}
+
+struct Entries {
+    /// Entries by scope, the hash of authorization and key
+    by_scope: HashMap<String, Entry>,
+    pruned_at: DateTime<Utc>,
+}
+
+/// Stored fingerprints and responses, shared through [`crate::AppState`]
+pub struct IdempotencyStore {
+    dir: PathBuf,
+    ttl: Duration,
+    entries: Mutex<Entries>,
+}
+
+impl IdempotencyStore {
+    /// Open the store in `dir`, dropping expired entries
+    ///
+    /// # Arguments
+    /// * `dir` - Directory holding the stored responses
+    /// * `ttl` - How long a response is replayed after it was recorded
+    pub fn open(dir: impl AsRef<Path>, ttl: Duration) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create idempotency directory: {:?}", dir))?;
+
+        let now = Utc::now();
+        let mut entries = HashMap::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let path = entry?.path();
+            let Some(scope) = path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .and_then(|n| n.strip_suffix(".json"))
+            else {
+                continue;
+            };
+            let contents = std::fs::read_to_string(&path)?;
+            let entry: Entry = serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse idempotency entry: {:?}", path))?;
+            if entry.expires_at <= now || entry.response.is_none() {
+                let _ = std::fs::remove_file(&path);
+                continue;
+            }
+            entries.insert(scope.to_string(), entry);
+        }
+
+        Ok(Self {
+            dir,
+            ttl,
+            entries: Mutex::new(Entries {
+                by_scope: entries,
+                pruned_at: now,
+            }),
+        })
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Entries> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn entry_path(&self, scope: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", scope))
+    }
+
+    /// Claim `scope` for a request with `fingerprint`, or find its response
+    fn begin(&self, scope: &str, fingerprint: &str) -> Result<Begin> {
+        let now = Utc::now();
+        let mut entries = self.lock();
+
+        if entries.pruned_at + PRUNE_INTERVAL <= now
+            || entries
+                .by_scope
+                .get(scope)
+                .is_some_and(|entry| entry.expires_at <= now)
+        {
+            let expired: Vec<String> = entries
+                .by_scope
+                .iter()
+                .filter(|(_, entry)| entry.expires_at <= now)
+                .map(|(scope, _)| scope.clone())
+                .collect();
+            for scope in expired {
+                entries.by_scope.remove(&scope);
+                let _ = std::fs::remove_file(self.entry_path(&scope));
+            }
+            entries.pruned_at = now;
+        }
+
+        match entries.by_scope.get(scope) {
+            Some(entry) if entry.fingerprint != fingerprint => Err(ApiError::Unprocessable(
+                "Idempotency-Key was already used with a different request".to_string(),
+            )),
+            Some(Entry {
+                response: Some(response),
+                ..
+            }) => Ok(Begin::Replay(response.clone())),
+            Some(_) => Err(ApiError::Conflict(
+                "A request with this Idempotency-Key is still in progress".to_string(),
+            )),
+            None => {
+                let expires_at = chrono::Duration::from_std(self.ttl)
+                    .ok()
+                    .and_then(|ttl| now.checked_add_signed(ttl))
+                    .unwrap_or(DateTime::<Utc>::MAX_UTC);
+                entries.by_scope.insert(
+                    scope.to_string(),
+                    Entry {
+                        fingerprint: fingerprint.to_string(),
+                        expires_at,
+                        response: None,
+                    },
+                );
+                Ok(Begin::Started)
+            }
+        }
+    }
+
+    /// Store the response of a claimed scope, or release the claim if the
+    /// response should not be replayed
+    fn finish(&self, scope: &str, response: Option<StoredResponse>) {
+        let mut entries = self.lock();
+        let Some(response) = response else {
+            entries.by_scope.remove(scope);
+            return;
+        };
+        let Some(entry) = entries.by_scope.get_mut(scope) else {
+            return;
+        };
+        entry.response = Some(response);
+
+        let result = serde_json::to_vec(&entry)
+            .map_err(anyhow::Error::from)
+            .and_then(|contents| {
+                let path = self.entry_path(scope);
+                let tmp = path.with_extension("tmp");
+                std::fs::write(&tmp, contents)?;
+                std::fs::rename(&tmp, &path)?;
+                Ok(())
+            });
+        if let Err(e) = result {
+            tracing::error!("Failed to store idempotent response: {:#}", e);
+        }
+    }
+}
+
+/// Releases a claimed scope if the handler panics before it finishes
+struct Claim {
+    store: Arc<IdempotencyStore>,
+    scope: String,
+    finished: bool,
+}
+
+impl Claim {
+    fn finish(mut self, response: Option<StoredResponse>) {
+        self.finished = true;
+        self.store.finish(&self.scope, response);
+    }
+}
+
+impl Drop for Claim {
+    fn drop(&mut self) {
+        if !self.finished {
+            self.store.finish(&self.scope, None);
+        }
+    }
+}
+
+fn hex_digest(parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in parts {
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn idempotency_key(headers: &HeaderMap) -> Result<Option<String>> {
+    let Some(value) = headers.get(IDEMPOTENCY_KEY) else {
+        return Ok(None);
+    };
+    let key = value
+        .to_str()
+        .ok()
+        .map(str::trim)
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+        .ok_or_else(|| {
+            ApiError::InvalidInput(format!(
+                "Idempotency-Key must be 1 to {} visible ASCII characters",
+                MAX_KEY_LEN
+            ))
+        })?;
+    Ok(Some(key.to_string()))
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let status = StatusCode::from_u16(stored.status).unwrap_or(StatusCode::OK);
+    let mut response = (status, stored.body).into_response();
+    let headers = response.headers_mut();
+    match stored
+        .content_type
+        .and_then(|c| HeaderValue::from_str(&c).ok())
+    {
+        Some(content_type) => {
+            headers.insert(header::CONTENT_TYPE, content_type);
+        }
+        None => {
+            headers.remove(header::CONTENT_TYPE);
+        }
+    }
+    headers.insert(IDEMPOTENT_REPLAYED, HeaderValue::from_static("true"));
+    response
+}
+
+async fn enforce(
+    State(store): State<Arc<IdempotencyStore>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|p| p.as_str().to_string())
+        .unwrap_or_else(|| request.uri().path().to_string());
+    if !is_idempotent_route(request.method(), &route) {
+        return Ok(next.run(request).await);
+    }
+    let Some(key) = idempotency_key(request.headers())? else {
+        return Ok(next.run(request).await);
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = axum::body::to_bytes(body, MAX_BODY_BYTES)
+        .await
+        .map_err(|e| ApiError::InvalidInput(format!("Failed to read request body: {}", e)))?;
+
+    let authorization = parts
+        .headers
+        .get(header::AUTHORIZATION)
+        .map(|v| v.as_bytes())
+        .unwrap_or_default();
+    let scope = hex_digest(&[authorization, key.as_bytes()]);
+    let fingerprint = hex_digest(&[
+        parts.method.as_str().as_bytes(),
+        parts.uri.path().as_bytes(),
+        &body,
+    ]);
+
+    match store.begin(&scope, &fingerprint)? {
+        Begin::Replay(stored) => return Ok(replay(stored)),
+        Begin::Started => {}
+    }
+    let claim = Claim {
+        store: store.clone(),
+        scope,
+        finished: false,
+    };
+
+    // The handler runs detached: a client that gives up must not release the
+    // key while the mutation it started may still complete
+    let request = Request::from_parts(parts, Body::from(body));
+    tokio::spawn(run_claimed(claim, next, request))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Idempotent request failed: {}", e)))?
+}
+
+/// Run a request whose key was claimed and store its response for replay
+async fn run_claimed(claim: Claim, next: Next, request: Request) -> Result<Response> {
+    let response = next.run(request).await;
+    if response.status().is_server_error() {
+        claim.finish(None);
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = match axum::body::to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(e) => {
+            claim.finish(None);
+            return Err(ApiError::Internal(format!(
+                "Failed to buffer response: {}",
+                e
+            )));
+        }
+    };
+    let stored = String::from_utf8(body.to_vec())
+        .ok()
+        .map(|text| StoredResponse {
+            status: parts.status.as_u16(),
+            content_type: parts
+                .headers
+                .get(header::CONTENT_TYPE)
+                .and_then(|c| c.to_str().ok())
+                .map(str::to_string),
+            body: text,
+        });
+    claim.finish(stored);
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+/// Honor `Idempotency-Key` on the [`IDEMPOTENT_ROUTES`] of a router
+///
+/// Apply before [`crate::auth::protect`] so requests are authorized before
+/// a stored response is replayed.
+///
+/// # Arguments
+/// * `router` - Routes of one group
+/// * `store` - Store of fingerprints and responses
+pub fn enable<S>(router: Router<S>, store: &Arc<IdempotencyStore>) -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    router.route_layer(middleware::from_fn_with_state(store.clone(), enforce))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{routing::post, Json};
+    use serde_json::{json, Value as JsonValue};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    fn test_router(store: &Arc<IdempotencyStore>, calls: Arc<AtomicUsize>) -> Router {
+        let counted = calls.clone();
+        let upsert = move |Json(body): Json<JsonValue>| {
+            let calls = counted.clone();
+            async move {
+                if let Some(delay) = body["delay_ms"].as_u64() {
+                    tokio::time::sleep(Duration::from_millis(delay)).await;
+                }
+                let call = calls.fetch_add(1, Ordering::SeqCst) + 1;
+                if body["fail"].as_bool() == Some(true) {
+                    return ApiError::Internal("boom".to_string()).into_response();
+                }
+                Json(json!({ "call": call, "body": body })).into_response()
+            }
+        };
+        let other = move || {
+            let calls = calls.clone();
+            async move { Json(json!({ "call": calls.fetch_add(1, Ordering::SeqCst) + 1 })) }
+        };
+        enable(
+            Router::new()
+                .route("/collections/:name/upsert", post(upsert))
+                .route("/search", post(other)),
+            store,
+        )
+    }
+
+    async fn send(
+        router: &Router,
+        path: &str,
+        key: Option<&str>,
+        body: JsonValue,
+    ) -> (StatusCode, Option<String>, JsonValue) {
+        let mut request = Request::post(path).header(header::CONTENT_TYPE, "application/json");
+        if let Some(key) = key {
+            request = request.header(IDEMPOTENCY_KEY, key);
+        }
+        let response = router
+            .clone()
+            .oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let replayed = response
+            .headers()
+            .get(IDEMPOTENT_REPLAYED)
+            .map(|v| v.to_str().unwrap().to_string());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, replayed, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_replays_response_and_rejects_changed_body() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(IdempotencyStore::open(dir.path(), DEFAULT_TTL).unwrap());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = test_router(&store, calls.clone());
+        let path = "/collections/docs/upsert";
+
+        let (status, replayed, first) = send(&router, path, Some("k1"), json!({"id": 1})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed, None);
+
+        let (status, replayed, second) = send(&router, path, Some("k1"), json!({"id": 1})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed.as_deref(), Some("true"));
+        assert_eq!(second, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let (status, _, _) = send(&router, path, Some("k1"), json!({"id": 2})).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        let (status, _, _) = send(
+            &router,
+            "/collections/other/upsert",
+            Some("k1"),
+            json!({"id": 1}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // Without a key, or on other routes, every request runs
+        send(&router, path, None, json!({"id": 1})).await;
+        send(&router, "/search", Some("k1"), json!({})).await;
+        send(&router, "/search", Some("k1"), json!({})).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+        // Replays survive a restart
+        let reopened = Arc::new(IdempotencyStore::open(dir.path(), DEFAULT_TTL).unwrap());
+        let router = test_router(&reopened, calls.clone());
+        let (_, replayed, third) = send(&router, path, Some("k1"), json!({"id": 1})).await;
+        assert_eq!(replayed.as_deref(), Some("true"));
+        assert_eq!(third, first);
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_abandoned_request_keeps_its_key() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(IdempotencyStore::open(dir.path(), DEFAULT_TTL).unwrap());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = test_router(&store, calls.clone());
+        let path = "/collections/docs/upsert";
+        let body = json!({"id": 1, "delay_ms": 200});
+
+        // The client gives up while the handler is still running
+        let abandoned = tokio::time::timeout(
+            Duration::from_millis(20),
+            send(&router, path, Some("k"), body.clone()),
+        )
+        .await;
+        assert!(abandoned.is_err());
+        let (status, _, _) = send(&router, path, Some("k"), body.clone()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // Once it completes, the retry gets its response instead of a rerun
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        let (status, replayed, response) = send(&router, path, Some("k"), body).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed.as_deref(), Some("true"));
+        assert_eq!(response["call"], 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_and_expired_keys_run_again() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(IdempotencyStore::open(dir.path(), Duration::ZERO).unwrap());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let router = test_router(&store, calls.clone());
+        let path = "/collections/docs/upsert";
+
+        let (status, _, _) = send(&router, path, Some("k"), json!({"fail": true})).await;
+        assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+        send(&router, path, Some("k"), json!({"fail": true})).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        // With a zero TTL the stored response is gone on the next request
+        send(&router, path, Some("j"), json!({"id": 1})).await;
+        let (_, replayed, body) = send(&router, path, Some("j"), json!({"id": 1})).await;
+        assert_eq!(replayed, None);
+        assert_eq!(body["call"], 4);
+
+        let (status, _, _) = send(&router, path, Some(&"x".repeat(300)), json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
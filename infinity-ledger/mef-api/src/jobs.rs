@@ -0,0 +1,710 @@
+/// Persistent background job queue
+///
+/// Each job is stored as `<id>.json` under the jobs directory, with its input
+/// kept separately in `<id>.params.json` until the job completes. Workers take
+/// queued jobs in submission order; a job that was queued or running when the
+/// server stopped is queued again on startup. Jobs failing with a server-side
+/// error are retried with a linear backoff until `max_attempts` is reached.
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::sync::Notify;
+
+use crate::{error::ApiError, routes, AppState, Result};
+
+/// Default number of worker tasks
+pub const DEFAULT_WORKERS: usize = 2;
+/// Default number of attempts per job
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+/// Delay before a retry, multiplied by the attempts made so far
+const RETRY_BACKOFF: Duration = Duration::from_secs(1);
+/// Finished jobs older than this are removed on startup
+const RETENTION_DAYS: i64 = 7;
+
+/// Kind of work a job performs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    /// Upsert of `/points/bulk`
+    BulkUpsert,
+    /// Index rebuild of `/index/build`
+    IndexBuild,
+    /// Fixpoint solve of `/solve`
+    Solve,
+    /// Domain pipeline run of `/domain/process`
+    DomainProcess,
+}
+
+impl JobKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            JobKind::BulkUpsert => "bulk_upsert",
+            JobKind::IndexBuild => "index_build",
+            JobKind::Solve => "solve",
+            JobKind::DomainProcess => "domain_process",
+        }
+    }
+
+    /// Parse a job kind name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "bulk_upsert" => Some(JobKind::BulkUpsert),
+            "index_build" => Some(JobKind::IndexBuild),
+            "solve" => Some(JobKind::Solve),
+            "domain_process" => Some(JobKind::DomainProcess),
+            _ => None,
+        }
+    }
+}
+
+/// Lifecycle state of a job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job will not change any more unless retried
+    pub fn is_finished(self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+
+    /// Parse a status name
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "queued" => Some(JobStatus::Queued),
+            "running" => Some(JobStatus::Running),
+            "completed" => Some(JobStatus::Completed),
+            "failed" => Some(JobStatus::Failed),
+            "cancelled" => Some(JobStatus::Cancelled),
+            _ => None,
+        }
+    }
+}
+
+/// A background job and its progress
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    /// Units of work, e.g. points of a bulk upsert
+    pub total: usize,
+    pub processed: usize,
+    /// Fraction of `total` processed, from 0.0 to 1.0
+    pub progress: f64,
+    pub attempts: u32,
+    pub max_attempts: u32,
+    /// Set when a running job was asked to stop
+    #[serde(default)]
+    pub cancel_requested: bool,
+    /// Response body of the finished job
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<JsonValue>,
+    /// Error of the last failed attempt
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// HTTP status of `error`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_status: Option<u16>,
+    pub created_at: String,
+    pub updated_at: String,
+    /// Earliest start of the next attempt (RFC 3339)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_before: Option<String>,
+}
+
+impl Job {
+    fn not_before(&self) -> Option<DateTime<Utc>> {
+        self.not_before
+            .as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&Utc))
+    }
+}
+
+struct QueueState {
+    jobs: HashMap<String, Job>,
+    /// Queued job IDs in submission order
+    pending: VecDeque<String>,
+}
+
+/// Job queue persisted to a directory, shared through [`crate::AppState`]
+pub struct JobQueue {
+    dir: PathBuf,
+    max_attempts: u32,
+    state: Mutex<QueueState>,
+    /// Signals workers that a job was queued
+    queued: Notify,
+    /// Signals waiters that a job changed
+    changed: Notify,
+    /// Set once workers should stop taking jobs
+    closed: AtomicBool,
+}
+
+impl JobQueue {
+    /// Open the queue stored in `dir`, requeueing unfinished jobs
+    ///
+    /// # Arguments
+    /// * `dir` - Directory holding the job files
+    /// * `max_attempts` - Attempts per job before it is marked failed
+    pub fn open(dir: impl AsRef<Path>, max_attempts: u32) -> anyhow::Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create jobs directory: {:?}", dir))?;
+
+        let queue = Self {
+            dir,
+            max_attempts: max_attempts.max(1),
+            state: Mutex::new(QueueState {
+                jobs: HashMap::new(),
+                pending: VecDeque::new(),
+            }),
+            queued: Notify::new(),
+            changed: Notify::new(),
+            closed: AtomicBool::new(false),
+        };
+
+        let cutoff = Utc::now() - chrono::Duration::days(RETENTION_DAYS);
+        let mut jobs = Vec::new();
+        for entry in std::fs::read_dir(&queue.dir)? {
+            let path = entry?.path();
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+            if !name.ends_with(".json") || name.ends_with(".params.json") {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path)?;
+            let mut job: Job = serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse job file: {:?}", path))?;
+
+            let updated = DateTime::parse_from_rfc3339(&job.updated_at)
+                .map(|t| t.with_timezone(&Utc))
+                .unwrap_or(cutoff);
+            if job.status.is_finished() && updated < cutoff {
+                queue.remove_files(&job.id);
+                continue;
+            }
+
+            if job.status == JobStatus::Running {
+                job.status = if job.cancel_requested {
+                    JobStatus::Cancelled
+                } else {
+                    JobStatus::Queued
+                };
+                job.updated_at = Utc::now().to_rfc3339();
+                queue.persist(&job)?;
+            }
+            jobs.push(job);
+        }
+        jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+
+        {
+            let mut state = queue.lock();
+            for job in jobs {
+                if job.status == JobStatus::Queued {
+                    state.pending.push_back(job.id.clone());
+                }
+                state.jobs.insert(job.id.clone(), job);
+            }
+        }
+        Ok(queue)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, QueueState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn job_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", id))
+    }
+
+    fn params_path(&self, id: &str) -> PathBuf {
+        self.dir.join(format!("{}.params.json", id))
+    }
+
+    fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, contents)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    fn persist(&self, job: &Job) -> anyhow::Result<()> {
+        Self::write_atomic(&self.job_path(&job.id), &serde_json::to_vec_pretty(job)?)
+            .with_context(|| format!("Failed to write job {}", job.id))
+    }
+
+    fn remove_files(&self, id: &str) {
+        let _ = std::fs::remove_file(self.job_path(id));
+        let _ = std::fs::remove_file(self.params_path(id));
+    }
+
+    /// Persist a changed job and wake everyone waiting on it
+    fn update(&self, job: &mut Job) {
+        job.updated_at = Utc::now().to_rfc3339();
+        if let Err(e) = self.persist(job) {
+            tracing::error!("{:#}", e);
+        }
+        self.changed.notify_waiters();
+    }
+
+    /// Queue a new job
+    ///
+    /// # Arguments
+    /// * `kind` - Work to perform
+    /// * `params` - Input handed to the job, e.g. the request body
+    /// * `total` - Units of work used for progress reporting
+    pub fn submit(&self, kind: JobKind, params: &JsonValue, total: usize) -> Result<Job> {
+        let now = Utc::now().to_rfc3339();
+        let job = Job {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            status: JobStatus::Queued,
+            total,
+            processed: 0,
+            progress: 0.0,
+            attempts: 0,
+            max_attempts: self.max_attempts,
+            cancel_requested: false,
+            result: None,
+            error: None,
+            error_status: None,
+            created_at: now.clone(),
+            updated_at: now,
+            not_before: None,
+        };
+
+        Self::write_atomic(&self.params_path(&job.id), &serde_json::to_vec(params)?)
+            .map_err(|e| ApiError::Storage(format!("Failed to store job input: {}", e)))?;
+        self.persist(&job)
+            .map_err(|e| ApiError::Storage(format!("{:#}", e)))?;
+
+        let mut state = self.lock();
+        state.pending.push_back(job.id.clone());
+        state.jobs.insert(job.id.clone(), job.clone());
+        drop(state);
+
+        self.queued.notify_one();
+        Ok(job)
+    }
+
+    /// Look up a job
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.lock().jobs.get(id).cloned()
+    }
+
+    /// Jobs matching the optional filters, oldest first
+    pub fn list(&self, status: Option<JobStatus>, kind: Option<JobKind>) -> Vec<Job> {
+        let state = self.lock();
+        let mut jobs: Vec<Job> = state
+            .jobs
+            .values()
+            .filter(|job| status.is_none_or(|s| job.status == s))
+            .filter(|job| kind.is_none_or(|k| job.kind == k))
+            .cloned()
+            .collect();
+        jobs.sort_by(|a, b| a.created_at.cmp(&b.created_at));
+        jobs
+    }
+
+    /// Input of a job as given to [`JobQueue::submit`]
+    pub fn params(&self, id: &str) -> Result<JsonValue> {
+        let contents = std::fs::read(self.params_path(id))
+            .map_err(|e| ApiError::Storage(format!("Failed to read input of job {}: {}", id, e)))?;
+        Ok(serde_json::from_slice(&contents)?)
+    }
+
+    /// Cancel a job
+    ///
+    /// A queued job is cancelled immediately. A running job is asked to stop
+    /// at its next progress report; if it finishes first it completes.
+    pub fn cancel(&self, id: &str) -> Result<Job> {
+        let mut state = self.lock();
+        let job = state
+            .jobs
+            .get_mut(id)
+            .ok_or_else(|| ApiError::NotFound(format!("Job {} not found", id)))?;
+
+        match job.status {
+            JobStatus::Queued => job.status = JobStatus::Cancelled,
+            JobStatus::Running => job.cancel_requested = true,
+            status => {
+                return Err(ApiError::Conflict(format!(
+                    "Job {} is already {:?}",
+                    id, status
+                )))
+            }
+        }
+        self.update(job);
+        let job = job.clone();
+        state.pending.retain(|pending| pending != id);
+        Ok(job)
+    }
+
+    /// Queue a failed or cancelled job again with a fresh attempt budget
+    pub fn retry(&self, id: &str) -> Result<Job> {
+        let mut state = self.lock();
+        let job = state
+            .jobs
+            .get_mut(id)
+            .ok_or_else(|| ApiError::NotFound(format!("Job {} not found", id)))?;
+
+        if !matches!(job.status, JobStatus::Failed | JobStatus::Cancelled) {
+            return Err(ApiError::Conflict(format!(
+                "Only failed or cancelled jobs can be retried; job {} is {:?}",
+                id, job.status
+            )));
+        }
+        job.status = JobStatus::Queued;
+        job.attempts = 0;
+        job.processed = 0;
+        job.progress = 0.0;
+        job.cancel_requested = false;
+        job.error = None;
+        job.error_status = None;
+        job.not_before = None;
+        self.update(job);
+        let job = job.clone();
+        state.pending.push_back(id.to_string());
+        drop(state);
+
+        self.queued.notify_one();
+        Ok(job)
+    }
+
+    /// Wait until a job has finished
+    pub async fn wait(&self, id: &str) -> Result<Job> {
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            let job = self
+                .get(id)
+                .ok_or_else(|| ApiError::NotFound(format!("Job {} not found", id)))?;
+            if job.status.is_finished() {
+                return Ok(job);
+            }
+            changed.await;
+        }
+    }
+
+    /// Stop the workers once their current job is done
+    ///
+    /// Queued jobs stay on disk and are picked up when the queue is opened
+    /// again.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::SeqCst);
+        self.queued.notify_waiters();
+    }
+
+    /// Wait until no job is running
+    ///
+    /// After [`close`](Self::close) this returns once the workers are done
+    /// with the jobs they had taken.
+    pub async fn wait_idle(&self) {
+        loop {
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            let running = self
+                .lock()
+                .jobs
+                .values()
+                .any(|job| job.status == JobStatus::Running);
+            if !running {
+                return;
+            }
+            changed.await;
+        }
+    }
+
+    /// Whether [`close`](Self::close) was called
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::SeqCst)
+    }
+
+    /// Take the next runnable job, waiting until one is available
+    ///
+    /// Returns `None` once the queue is closed.
+    async fn next(&self) -> Option<Job> {
+        loop {
+            let queued = self.queued.notified();
+            tokio::pin!(queued);
+            queued.as_mut().enable();
+            if self.closed.load(Ordering::SeqCst) {
+                return None;
+            }
+
+            let now = Utc::now();
+            let mut retry_at = None;
+            {
+                let mut state = self.lock();
+                let runnable = state.pending.iter().position(|id| {
+                    match state.jobs.get(id).and_then(Job::not_before) {
+                        Some(at) if at > now => {
+                            retry_at = Some(retry_at.map_or(at, |r: DateTime<Utc>| r.min(at)));
+                            false
+                        }
+                        _ => true,
+                    }
+                });
+                if let Some(position) = runnable {
+                    let id = state.pending.remove(position).unwrap_or_default();
+                    if let Some(job) = state.jobs.get_mut(&id) {
+                        job.status = JobStatus::Running;
+                        job.attempts += 1;
+                        job.not_before = None;
+                        self.update(job);
+                        return Some(job.clone());
+                    }
+                    continue;
+                }
+            }
+
+            match retry_at {
+                Some(at) => {
+                    let delay = (at - now).to_std().unwrap_or_default();
+                    tokio::select! {
+                        _ = queued => {}
+                        _ = tokio::time::sleep(delay) => {}
+                    }
+                }
+                None => queued.await,
+            }
+        }
+    }
+
+    /// Record progress of a running job
+    ///
+    /// Returns a `Conflict` error when the job was cancelled, which the job
+    /// should pass on to stop.
+    pub fn report_progress(&self, id: &str, processed: usize) -> Result<()> {
+        let mut state = self.lock();
+        let job = state
+            .jobs
+            .get_mut(id)
+            .ok_or_else(|| ApiError::NotFound(format!("Job {} not found", id)))?;
+
+        if job.cancel_requested {
+            return Err(ApiError::Conflict(format!("Job {} was cancelled", id)));
+        }
+        job.processed = processed.min(job.total);
+        job.progress = if job.total == 0 {
+            1.0
+        } else {
+            job.processed as f64 / job.total as f64
+        };
+        self.update(job);
+        Ok(())
+    }
+
+    /// Record the outcome of an attempt
+    fn finish(&self, id: &str, outcome: Result<JsonValue>) {
+        let mut state = self.lock();
+        let Some(job) = state.jobs.get_mut(id) else {
+            return;
+        };
+
+        let mut requeue = false;
+        match outcome {
+            Ok(result) => {
+                job.status = JobStatus::Completed;
+                job.processed = job.total;
+                job.progress = 1.0;
+                job.result = Some(result);
+                job.error = None;
+                job.error_status = None;
+                let _ = std::fs::remove_file(self.params_path(id));
+            }
+            Err(e) => {
+                let (status, message) = e.parts();
+                job.error = Some(message.to_string());
+                job.error_status = Some(status.as_u16());
+                if job.cancel_requested {
+                    job.status = JobStatus::Cancelled;
+                } else if status.is_server_error() && job.attempts < job.max_attempts {
+                    let backoff = RETRY_BACKOFF * job.attempts;
+                    let retry_at =
+                        Utc::now() + chrono::Duration::from_std(backoff).unwrap_or_default();
+                    job.status = JobStatus::Queued;
+                    job.not_before = Some(retry_at.to_rfc3339());
+                    requeue = true;
+                } else {
+                    job.status = JobStatus::Failed;
+                }
+            }
+        }
+        self.update(job);
+
+        if requeue {
+            state.pending.push_back(id.to_string());
+            drop(state);
+            self.queued.notify_one();
+        }
+    }
+}
+
+/// Start `count` workers running jobs from the queue of `state`
+///
+/// Workers exit when the queue is closed with [`JobQueue::close`].
+pub fn spawn_workers(state: &AppState, count: usize) {
+    for _ in 0..count {
+        let state = state.clone();
+        tokio::spawn(async move {
+            while let Some(job) = state.jobs.next().await {
+                let id = job.id.clone();
+                let worker_state = state.clone();
+                let outcome = tokio::task::spawn_blocking(move || execute(&worker_state, &job))
+                    .await
+                    .unwrap_or_else(|e| Err(ApiError::Internal(format!("Job panicked: {}", e))));
+                if let Err(e) = &outcome {
+                    tracing::warn!("Job {} failed: {}", id, e);
+                }
+                state.jobs.finish(&id, outcome);
+            }
+        });
+    }
+}
+
+/// Run one attempt of a job on a blocking thread
+fn execute(state: &AppState, job: &Job) -> Result<JsonValue> {
+    match job.kind {
+        JobKind::BulkUpsert => routes::vector::run_bulk_upsert(state, job),
+        JobKind::IndexBuild => routes::index::run_build_index(state, job),
+        JobKind::Solve => routes::process::run_solve(state, job),
+        JobKind::DomainProcess => routes::domain::run_domain_process(state, job),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_jobs_survive_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = JobQueue::open(dir.path(), 3).unwrap();
+
+        let queued = queue.submit(JobKind::Solve, &json!({"a": 1}), 1).unwrap();
+        let cancelled = queue.submit(JobKind::Solve, &json!({"b": 2}), 1).unwrap();
+        queue.cancel(&cancelled.id).unwrap();
+
+        let reopened = JobQueue::open(dir.path(), 3).unwrap();
+        assert_eq!(reopened.get(&queued.id).unwrap().status, JobStatus::Queued);
+        assert_eq!(reopened.params(&queued.id).unwrap(), json!({"a": 1}));
+        assert_eq!(
+            reopened.get(&cancelled.id).unwrap().status,
+            JobStatus::Cancelled
+        );
+
+        // Only the queued job is handed out
+        let state = reopened.lock();
+        assert_eq!(state.pending, VecDeque::from(vec![queued.id.clone()]));
+    }
+
+    #[tokio::test]
+    async fn test_failed_attempts_are_retried() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = JobQueue::open(dir.path(), 2).unwrap();
+        let job = queue.submit(JobKind::IndexBuild, &json!({}), 1).unwrap();
+
+        let attempt = queue.next().await.unwrap();
+        assert_eq!(attempt.attempts, 1);
+        queue.finish(&job.id, Err(ApiError::Internal("disk full".to_string())));
+        let requeued = queue.get(&job.id).unwrap();
+        assert_eq!(requeued.status, JobStatus::Queued);
+        assert!(requeued.not_before.is_some());
+
+        let attempt = queue.next().await.unwrap();
+        assert_eq!(attempt.attempts, 2);
+        queue.finish(&job.id, Err(ApiError::Internal("disk full".to_string())));
+        let failed = queue.wait(&job.id).await.unwrap();
+        assert_eq!(failed.status, JobStatus::Failed);
+        assert_eq!(failed.error_status, Some(500));
+
+        // Client errors are not retried
+        let job = queue.submit(JobKind::Solve, &json!({}), 1).unwrap();
+        queue.next().await.unwrap();
+        queue.finish(&job.id, Err(ApiError::NotFound("missing".to_string())));
+        assert_eq!(queue.get(&job.id).unwrap().status, JobStatus::Failed);
+
+        let retried = queue.retry(&job.id).unwrap();
+        assert_eq!(retried.status, JobStatus::Queued);
+        assert_eq!(retried.attempts, 0);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_waits_for_running_jobs() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = std::sync::Arc::new(JobQueue::open(dir.path(), 3).unwrap());
+        let job = queue.submit(JobKind::Solve, &json!({}), 1).unwrap();
+        queue.submit(JobKind::Solve, &json!({}), 1).unwrap();
+        queue.next().await.unwrap();
+        queue.close();
+
+        let waiting = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.wait_idle().await }
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        // Queued jobs do not hold up a closed queue
+        queue.finish(&job.id, Ok(json!({})));
+        waiting.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_running_job_stops_on_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = JobQueue::open(dir.path(), 3).unwrap();
+        let job = queue.submit(JobKind::BulkUpsert, &json!({}), 10).unwrap();
+        queue.next().await.unwrap();
+
+        queue.report_progress(&job.id, 5).unwrap();
+        assert_eq!(queue.get(&job.id).unwrap().progress, 0.5);
+
+        queue.cancel(&job.id).unwrap();
+        let stopped = queue.report_progress(&job.id, 6).unwrap_err();
+        queue.finish(&job.id, Err(stopped));
+        let job = queue.get(&job.id).unwrap();
+        assert_eq!(job.status, JobStatus::Cancelled);
+        assert_eq!(job.processed, 5);
+    }
+
+    #[tokio::test]
+    async fn test_close_stops_waiting_workers() {
+        let dir = tempfile::tempdir().unwrap();
+        let queue = std::sync::Arc::new(JobQueue::open(dir.path(), 3).unwrap());
+
+        let worker = {
+            let queue = queue.clone();
+            tokio::spawn(async move { queue.next().await })
+        };
+        tokio::task::yield_now().await;
+        queue.close();
+        assert!(worker.await.unwrap().is_none());
+
+        // Jobs queued after closing wait for the next start
+        let job = queue.submit(JobKind::Solve, &json!({}), 1).unwrap();
+        assert!(queue.next().await.is_none());
+        let reopened = JobQueue::open(dir.path(), 3).unwrap();
+        assert_eq!(reopened.next().await.unwrap().id, job.id);
+    }
+}
@@ -2,11 +2,19 @@
 /// Migrated from: MEF-Core_v1.0/src/api/server.py
 ///
 /// FastAPI → Axum migration with identical API contract
+pub mod auth;
 pub mod config;
 pub mod error;
+pub mod events;
+pub mod grpc;
+pub mod idempotency;
+pub mod jobs;
+pub mod metrics;
 pub mod models;
+pub mod pagination;
 pub mod routes;
 pub mod state;
+pub mod tenants;
 
 pub use config::ApiConfig;
 pub use error::{ApiError, Result};
@@ -1,12 +1,15 @@
 /// MEF-Core API Server - Main Entry Point
 /// Migrated from: MEF-Core_v1.0/src/api/server.py
-use axum::Router;
+use axum::{extract::Request, middleware, ServiceExt};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tower_http::trace::TraceLayer;
+use std::time::Duration;
+use tokio::sync::watch;
+use tower::Layer;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use mef_api::{routes, ApiConfig, AppState};
+use mef_api::tenants::{self, TenantRegistry};
+use mef_api::{auth, grpc, metrics, routes, ApiConfig, AppState};
 use mef_knowledge::{ExtensionConfig, ExtensionPipeline};
 
 #[tokio::main]
@@ -28,24 +31,21 @@ async fn main() -> anyhow::Result<()> {
     let state = AppState::new(config.clone()).await?;
     tracing::info!("Application state initialized");
 
-    // Build router
-    let mut app = Router::new()
-        .merge(routes::health::router())
-        .merge(routes::ingest::router())
-        .merge(routes::process::router())
-        .merge(routes::ledger::router())
-        .merge(routes::vector::router())
-        .merge(routes::coupling::router())
-        .merge(routes::tic::router())
-        .merge(routes::index::router())
-        .merge(routes::system::router())
-        .merge(routes::commit::router())
-        .merge(routes::zk::router())
-        .merge(routes::domain::router())
-        .merge(routes::metatron::router())
-        .merge(routes::merkaba::router())
-        .with_state(state)
-        .layer(TraceLayer::new_for_http());
+    // The gRPC server shares the state with the REST routes of the default
+    // namespace; tenants are only reachable over REST
+    let grpc_state = state.clone();
+    let metrics = state.metrics.clone();
+    let auth_config = state.config.clone();
+
+    // Start the tenants stored next to the default namespace
+    let registry = Arc::new(TenantRegistry::open(state.config.clone()).await?);
+    tracing::info!("Tenants loaded");
+
+    let mut app = routes::app(state.clone()).merge(auth::protect(
+        routes::tenants::router(registry.clone()),
+        &auth_config,
+        "tenants",
+    ));
 
     // Optionally load and mount extension routes
     if let Ok(ext_config) = ExtensionConfig::load_from_env() {
@@ -55,7 +55,11 @@ async fn main() -> anyhow::Result<()> {
             let ext_state = routes::extension::ExtensionState {
                 pipeline: Arc::new(tokio::sync::Mutex::new(pipeline)),
             };
-            app = app.merge(routes::extension::router(ext_state));
+            app = app.merge(auth::protect(
+                routes::extension::router(ext_state),
+                &auth_config,
+                "extension",
+            ));
         } else {
             tracing::info!("Extension configuration loaded but all features disabled");
         }
@@ -63,12 +67,108 @@ async fn main() -> anyhow::Result<()> {
         tracing::info!("Extension configuration not found or invalid, skipping extension routes");
     }
 
-    // Start server
+    // Count requests and latencies per matched route, extension routes included
+    let app = app.layer(middleware::from_fn_with_state(
+        metrics,
+        metrics::track_requests,
+    ));
+
+    // Requests for a tenant are handed to its routes before routing
+    let app = middleware::from_fn_with_state(registry.clone(), tenants::dispatch).layer(app);
+
+    // On SIGTERM or Ctrl+C both servers stop accepting connections and wait
+    // for in-flight requests; live event streams end so their connections
+    // can close
+    let (stop, stopped) = watch::channel(false);
+    {
+        let state = state.clone();
+        let registry = registry.clone();
+        tokio::spawn(async move {
+            shutdown_signal().await;
+            tracing::info!("Shutdown requested, draining in-flight requests");
+            state.drain();
+            registry.drain().await;
+            let _ = stop.send(true);
+        });
+    }
+    let until_stopped = |mut stopped: watch::Receiver<bool>| async move {
+        let _ = stopped.wait_for(|stopped| *stopped).await;
+    };
+    let drain_timeout = Duration::from_secs(config.shutdown_timeout_secs);
+
+    // Start servers
     let addr = SocketAddr::from(([0, 0, 0, 0], config.port));
     tracing::info!("Starting server on {}", addr);
-
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(listener, app).await?;
+
+    let grpc_addr = SocketAddr::from(([0, 0, 0, 0], config.grpc_port));
+    tracing::info!("Starting gRPC server on {}", grpc_addr);
+    let grpc_listener = tokio::net::TcpListener::bind(grpc_addr).await?;
+
+    let servers = async {
+        tokio::try_join!(
+            async {
+                let app = ServiceExt::<Request>::into_make_service(app);
+                axum::serve(listener, app)
+                    .with_graceful_shutdown(until_stopped(stopped.clone()))
+                    .await?;
+                Ok::<_, anyhow::Error>(())
+            },
+            grpc::serve_with_shutdown(grpc_state, grpc_listener, until_stopped(stopped.clone())),
+        )
+    };
+    let deadline = async {
+        until_stopped(stopped.clone()).await;
+        tokio::time::sleep(drain_timeout).await;
+    };
+    tokio::select! {
+        result = servers => {
+            result?;
+        }
+        _ = deadline => {
+            tracing::warn!(
+                "Requests still in flight after {:?}, shutting down anyway",
+                drain_timeout
+            );
+        }
+    }
+
+    // Flush the audit log, index manifests and coupling state of every
+    // namespace
+    let tenants_flushed = registry.shutdown().await;
+    state.shutdown().await?;
+    tenants_flushed?;
+    tracing::info!("Shutdown complete");
 
     Ok(())
 }
+
+/// Resolve on Ctrl+C or, on Unix, SIGTERM
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
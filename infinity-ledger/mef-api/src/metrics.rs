@@ -0,0 +1,364 @@
+/// Prometheus metrics registry rendered in OpenMetrics text format
+///
+/// Request counters and latencies come from the [`track_requests`]
+/// middleware; ledger, vector and gate metrics from the observer hooks of
+/// `MEFLedger`, `IndexManager` and `MerkabaGate`, which are registered in
+/// [`crate::AppState::new`].
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use mef_core::gates::merkaba_gate::{GateEvent, GateObserver};
+use mef_ledger::{LedgerObserver, MefBlock};
+use mef_vector_db::IndexObserver;
+use prometheus::{
+    proto::{MetricFamily, MetricType},
+    Gauge, Histogram, HistogramOpts, HistogramVec, IntCounter, IntCounterVec, IntGauge,
+    IntGaugeVec, Opts, Registry,
+};
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Content type of [`Metrics::render`]
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// Route label for requests that matched no route
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Metrics exported at `GET /metrics`
+pub struct Metrics {
+    registry: Registry,
+    http_requests: IntCounterVec,
+    http_duration: HistogramVec,
+    ledger_height: IntGauge,
+    ledger_appends: IntCounter,
+    collection_vectors: IntGaugeVec,
+    index_builds: IntCounterVec,
+    index_build_duration: Histogram,
+    gate_decisions: IntCounterVec,
+    gate_fire_ratio: Gauge,
+    fixpoint_iterations: Histogram,
+}
+
+impl std::fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Metrics").finish_non_exhaustive()
+    }
+}
+
+impl Metrics {
+    /// Create and register all metrics
+    pub fn new() -> prometheus::Result<Self> {
+        let registry = Registry::new();
+
+        let http_requests = IntCounterVec::new(
+            Opts::new("mef_http_requests_total", "HTTP requests handled"),
+            &["method", "route", "status"],
+        )?;
+        let http_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "mef_http_request_duration_seconds",
+                "HTTP request latency in seconds",
+            ),
+            &["method", "route"],
+        )?;
+        let ledger_height = IntGauge::new("mef_ledger_height", "Blocks in the ledger")?;
+        let ledger_appends = IntCounter::new(
+            "mef_ledger_blocks_appended_total",
+            "Blocks appended since startup",
+        )?;
+        let collection_vectors = IntGaugeVec::new(
+            Opts::new("mef_collection_vectors", "Vectors stored per collection"),
+            &["collection"],
+        )?;
+        let index_builds = IntCounterVec::new(
+            Opts::new("mef_index_builds_total", "Vector index rebuilds"),
+            &["collection"],
+        )?;
+        let index_build_duration = Histogram::with_opts(HistogramOpts::new(
+            "mef_index_build_duration_seconds",
+            "Vector index rebuild time in seconds",
+        ))?;
+        let gate_decisions = IntCounterVec::new(
+            Opts::new("mef_gate_decisions_total", "Merkaba gate decisions"),
+            &["decision"],
+        )?;
+        let gate_fire_ratio = Gauge::new(
+            "mef_gate_fire_ratio",
+            "Share of Merkaba gate decisions that were FIRE",
+        )?;
+        let fixpoint_iterations = Histogram::with_opts(
+            HistogramOpts::new(
+                "mef_fixpoint_iterations",
+                "Solve-Coagula iterations until the fixpoint",
+            )
+            .buckets(vec![
+                1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0, 1000.0,
+            ]),
+        )?;
+
+        registry.register(Box::new(http_requests.clone()))?;
+        registry.register(Box::new(http_duration.clone()))?;
+        registry.register(Box::new(ledger_height.clone()))?;
+        registry.register(Box::new(ledger_appends.clone()))?;
+        registry.register(Box::new(collection_vectors.clone()))?;
+        registry.register(Box::new(index_builds.clone()))?;
+        registry.register(Box::new(index_build_duration.clone()))?;
+        registry.register(Box::new(gate_decisions.clone()))?;
+        registry.register(Box::new(gate_fire_ratio.clone()))?;
+        registry.register(Box::new(fixpoint_iterations.clone()))?;
+
+        Ok(Self {
+            registry,
+            http_requests,
+            http_duration,
+            ledger_height,
+            ledger_appends,
+            collection_vectors,
+            index_builds,
+            index_build_duration,
+            gate_decisions,
+            gate_fire_ratio,
+            fixpoint_iterations,
+        })
+    }
+
+    /// Record one handled HTTP request
+    ///
+    /// # Arguments
+    /// * `method` - HTTP method
+    /// * `route` - Matched route pattern, e.g. `/ledger/:index`
+    /// * `status` - Response status code
+    /// * `elapsed` - Time spent handling the request
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        self.http_requests
+            .with_label_values(&[method, route, &status.to_string()])
+            .inc();
+        self.http_duration
+            .with_label_values(&[method, route])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Record the iterations Solve-Coagula needed to reach a fixpoint
+    pub fn observe_fixpoint(&self, iterations: usize) {
+        self.fixpoint_iterations.observe(iterations as f64);
+    }
+
+    /// Set the ledger height, e.g. when the ledger is opened
+    pub fn set_ledger_height(&self, height: usize) {
+        self.ledger_height.set(height as i64);
+    }
+
+    /// Render all metrics in OpenMetrics text format
+    pub fn render(&self) -> String {
+        encode_openmetrics(&self.registry.gather())
+    }
+}
+
+impl LedgerObserver for Metrics {
+    fn block_appended(&self, _block: &MefBlock, height: usize) {
+        self.ledger_appends.inc();
+        self.set_ledger_height(height);
+    }
+}
+
+impl IndexObserver for Metrics {
+    fn collection_changed(&self, collection: &str, vectors: usize) {
+        self.collection_vectors
+            .with_label_values(&[collection])
+            .set(vectors as i64);
+    }
+
+    fn index_built(&self, collection: &str, vectors: usize, duration: Duration) {
+        self.collection_changed(collection, vectors);
+        self.index_builds.with_label_values(&[collection]).inc();
+        self.index_build_duration.observe(duration.as_secs_f64());
+    }
+}
+
+impl GateObserver for Metrics {
+    fn gate_decided(&self, event: &GateEvent) {
+        let decision = if event.decision.commit {
+            "fire"
+        } else {
+            "hold"
+        };
+        self.gate_decisions.with_label_values(&[decision]).inc();
+
+        let fire = self.gate_decisions.with_label_values(&["fire"]).get();
+        let hold = self.gate_decisions.with_label_values(&["hold"]).get();
+        self.gate_fire_ratio.set(fire as f64 / (fire + hold) as f64);
+    }
+}
+
+/// Middleware recording request counts and latencies per matched route
+pub async fn track_requests(
+    State(metrics): State<Arc<Metrics>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let method = request.method().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE.to_string());
+
+    let start = Instant::now();
+    let response = next.run(request).await;
+    metrics.observe_request(&method, &route, response.status().as_u16(), start.elapsed());
+    response
+}
+
+/// Encode gathered metric families as OpenMetrics text
+///
+/// Counter families are declared without their `_total` suffix, as
+/// OpenMetrics requires, and the exposition ends with `# EOF`.
+pub fn encode_openmetrics(families: &[MetricFamily]) -> String {
+    let mut out = String::new();
+    for family in families {
+        let name = family.get_name();
+        let (family_name, kind) = match family.get_field_type() {
+            MetricType::COUNTER => (name.strip_suffix("_total").unwrap_or(name), "counter"),
+            MetricType::GAUGE => (name, "gauge"),
+            MetricType::HISTOGRAM => (name, "histogram"),
+            MetricType::SUMMARY => (name, "summary"),
+            MetricType::UNTYPED => (name, "unknown"),
+        };
+        let _ = writeln!(out, "# TYPE {} {}", family_name, kind);
+        let _ = writeln!(
+            out,
+            "# HELP {} {}",
+            family_name,
+            escape_help(family.get_help())
+        );
+
+        for metric in family.get_metric() {
+            let labels: Vec<(&str, String)> = metric
+                .get_label()
+                .iter()
+                .map(|label| (label.get_name(), label.get_value().to_string()))
+                .collect();
+            match family.get_field_type() {
+                MetricType::COUNTER => {
+                    let value = metric.get_counter().get_value();
+                    sample(&mut out, &format!("{}_total", family_name), &labels, value);
+                }
+                MetricType::GAUGE => {
+                    sample(&mut out, name, &labels, metric.get_gauge().get_value());
+                }
+                MetricType::HISTOGRAM => {
+                    let histogram = metric.get_histogram();
+                    let bucket_name = format!("{}_bucket", name);
+                    let mut has_inf = false;
+                    for bucket in histogram.get_bucket() {
+                        let bound = bucket.get_upper_bound();
+                        has_inf |= bound.is_infinite();
+                        let mut with_le = labels.clone();
+                        with_le.push(("le", format_value(bound)));
+                        let count = bucket.get_cumulative_count() as f64;
+                        sample(&mut out, &bucket_name, &with_le, count);
+                    }
+                    let count = histogram.get_sample_count() as f64;
+                    if !has_inf {
+                        let mut with_le = labels.clone();
+                        with_le.push(("le", "+Inf".to_string()));
+                        sample(&mut out, &bucket_name, &with_le, count);
+                    }
+                    sample(
+                        &mut out,
+                        &format!("{}_sum", name),
+                        &labels,
+                        histogram.get_sample_sum(),
+                    );
+                    sample(&mut out, &format!("{}_count", name), &labels, count);
+                }
+                MetricType::SUMMARY | MetricType::UNTYPED => {
+                    sample(&mut out, name, &labels, metric.get_untyped().get_value());
+                }
+            }
+        }
+    }
+    out.push_str("# EOF\n");
+    out
+}
+
+fn sample(out: &mut String, name: &str, labels: &[(&str, String)], value: f64) {
+    out.push_str(name);
+    if !labels.is_empty() {
+        let labels: Vec<String> = labels
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, escape_label(value)))
+            .collect();
+        let _ = write!(out, "{{{}}}", labels.join(","));
+    }
+    let _ = writeln!(out, " {}", format_value(value));
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_openmetrics() {
+        let metrics = Metrics::new().unwrap();
+        metrics.observe_request("GET", "/ledger/:index", 200, Duration::from_millis(3));
+        metrics.observe_fixpoint(12);
+        metrics.collection_changed("spiral", 7);
+
+        let text = metrics.render();
+        assert!(text.ends_with("# EOF\n"));
+        assert!(text.contains("# TYPE mef_http_requests counter"));
+        assert!(text.contains(
+            "mef_http_requests_total{method=\"GET\",route=\"/ledger/:index\",status=\"200\"} 1"
+        ));
+        assert!(text.contains("mef_http_request_duration_seconds_bucket{method=\"GET\",route=\"/ledger/:index\",le=\"+Inf\"} 1"));
+        assert!(text.contains("mef_fixpoint_iterations_bucket{le=\"20\"} 1"));
+        assert!(text.contains("mef_collection_vectors{collection=\"spiral\"} 7"));
+    }
+
+    #[test]
+    fn test_gate_fire_ratio() {
+        let metrics = Metrics::new().unwrap();
+        let mut event: GateEvent = serde_json::from_value(serde_json::json!({
+            "gate_id": "g",
+            "snapshot_id": "s",
+            "tic_candidate_id": "t",
+            "checks": {"por": "valid", "delta_pi": 0.0, "phi": 1.0, "delta_v": -0.1, "mci": null},
+            "decision": {"commit": true, "reason": "ok"},
+            "timestamp": "2026-10-17T00:00:00Z",
+        }))
+        .unwrap();
+        metrics.gate_decided(&event);
+        event.decision.commit = false;
+        metrics.gate_decided(&event);
+        metrics.gate_decided(&event);
+        metrics.gate_decided(&event);
+
+        assert!(metrics.render().contains("mef_gate_fire_ratio 0.25"));
+    }
+}
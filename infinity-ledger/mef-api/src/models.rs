@@ -64,7 +64,7 @@ pub struct ProcessResponse {
     pub timestamp: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SolveRequest {
     pub snapshot_id: String,
 }
@@ -114,6 +114,9 @@ pub struct StabilityMetrics {
 pub struct LedgerAppendRequest {
     pub tic_id: String,
     pub snapshot_id: String,
+    /// Only append if the ledger head still has this hash
+    #[serde(default)]
+    pub expected_previous_hash: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
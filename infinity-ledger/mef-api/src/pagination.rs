@@ -0,0 +1,241 @@
+/// Cursor-based pagination for listing endpoints
+///
+/// Listings return a [`Page`] envelope of at most `limit` items in a stable
+/// order together with the number of items matching the filters. When more
+/// items follow, `next_cursor` is set; passing it back as `?cursor=` returns
+/// the next page. A cursor encodes the sort key of the last item returned
+/// rather than a position, so items inserted or removed between requests do
+/// not shift the pages.
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::borrow::Borrow;
+
+use crate::{error::ApiError, Result};
+
+/// Items returned when a request does not pass `limit`
+pub const DEFAULT_LIMIT: usize = 100;
+/// Largest accepted `limit`; larger values are capped
+pub const MAX_LIMIT: usize = 1000;
+
+/// Query parameters shared by all listing endpoints
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct PageQuery {
+    #[serde(default)]
+    pub limit: Option<usize>,
+    #[serde(default)]
+    pub cursor: Option<String>,
+}
+
+/// Response envelope of all listing endpoints
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items of this page in listing order
+    pub items: Vec<T>,
+    /// Cursor of the next page, absent on the last page
+    pub next_cursor: Option<String>,
+    /// Items matching the filters across all pages
+    pub total: usize,
+}
+
+impl<T> Page<T> {
+    /// Convert the items of the page, keeping cursor and total
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            next_cursor: self.next_cursor,
+            total: self.total,
+        }
+    }
+}
+
+/// Validated page request with the decoded cursor
+///
+/// Items are listed in ascending order of their key `K`; use
+/// [`std::cmp::Reverse`] for newest-first listings.
+#[derive(Debug, Clone)]
+pub struct PageRequest<K> {
+    pub limit: usize,
+    pub after: Option<K>,
+}
+
+impl<K: Ord + Serialize + DeserializeOwned> PageRequest<K> {
+    /// Validate `limit` and decode `cursor`
+    ///
+    /// # Arguments
+    /// * `query` - Pagination parameters of the request
+    pub fn parse(query: &PageQuery) -> Result<Self> {
+        let limit = match query.limit {
+            Some(0) => {
+                return Err(ApiError::InvalidInput(
+                    "limit must be at least 1".to_string(),
+                ))
+            }
+            Some(limit) => limit.min(MAX_LIMIT),
+            None => DEFAULT_LIMIT,
+        };
+        let after = query.cursor.as_deref().map(decode_cursor).transpose()?;
+        Ok(Self { limit, after })
+    }
+
+    /// Select the page from the items matching the filters
+    ///
+    /// `items` may be in any order; each is paired with its sort key, which
+    /// must be unique and may be borrowed. Only the items after the cursor
+    /// are kept in memory.
+    ///
+    /// # Arguments
+    /// * `items` - Matching items with their sort keys
+    pub fn select<Q: Borrow<K>, T>(&self, items: impl IntoIterator<Item = (Q, T)>) -> Page<T> {
+        let mut total = 0;
+        let mut candidates: Vec<(Q, T)> = items
+            .into_iter()
+            .inspect(|_| total += 1)
+            .filter(|(key, _)| self.after.as_ref().is_none_or(|after| key.borrow() > after))
+            .collect();
+
+        let by_key = |a: &(Q, T), b: &(Q, T)| a.0.borrow().cmp(b.0.borrow());
+        let has_more = candidates.len() > self.limit;
+        if has_more {
+            candidates.select_nth_unstable_by(self.limit, by_key);
+            candidates.truncate(self.limit);
+        }
+        candidates.sort_unstable_by(by_key);
+
+        let next_cursor = if has_more {
+            candidates
+                .last()
+                .map(|(key, _)| encode_cursor(key.borrow()))
+        } else {
+            None
+        };
+        Page {
+            items: candidates.into_iter().map(|(_, item)| item).collect(),
+            next_cursor,
+            total,
+        }
+    }
+}
+
+impl<K: Ord + Serialize + DeserializeOwned + Clone> PageRequest<K> {
+    /// Take the page from items already in ascending key order
+    ///
+    /// Unlike [`PageRequest::select`], only the items of the page are
+    /// consumed, so `items` may lazily start at the cursor of a large sorted
+    /// collection. Items at or before the cursor are skipped.
+    ///
+    /// # Arguments
+    /// * `items` - Items with their sort keys, in ascending key order
+    /// * `total` - Number of items matching the filters across all pages
+    pub fn take_sorted<T>(&self, items: impl IntoIterator<Item = (K, T)>, total: usize) -> Page<T> {
+        let mut page: Vec<(K, T)> = items
+            .into_iter()
+            .skip_while(|(key, _)| self.after.as_ref().is_some_and(|after| key <= after))
+            .take(self.limit + 1)
+            .collect();
+
+        let has_more = page.len() > self.limit;
+        page.truncate(self.limit);
+        let next_cursor = if has_more {
+            page.last().map(|(key, _)| encode_cursor(key))
+        } else {
+            None
+        };
+        Page {
+            items: page.into_iter().map(|(_, item)| item).collect(),
+            next_cursor,
+            total,
+        }
+    }
+}
+
+/// Opaque cursor for a sort key: hex of its JSON encoding
+fn encode_cursor<K: Serialize>(key: &K) -> String {
+    let json = serde_json::to_vec(key).unwrap_or_default();
+    json.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn decode_cursor<K: DeserializeOwned>(cursor: &str) -> Result<K> {
+    let invalid = || ApiError::InvalidInput(format!("Invalid cursor: {}", cursor));
+    if !cursor.len().is_multiple_of(2) || !cursor.is_ascii() {
+        return Err(invalid());
+    }
+    let bytes = (0..cursor.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&cursor[i..i + 2], 16))
+        .collect::<std::result::Result<Vec<u8>, _>>()
+        .map_err(|_| invalid())?;
+    serde_json::from_slice(&bytes).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cmp::Reverse;
+
+    fn query(limit: usize, cursor: Option<String>) -> PageQuery {
+        PageQuery {
+            limit: Some(limit),
+            cursor,
+        }
+    }
+
+    #[test]
+    fn test_pages_cover_all_items_once() {
+        let items: Vec<(String, usize)> = [5, 3, 9, 1, 7, 2, 8]
+            .into_iter()
+            .map(|i| (format!("v{}", i), i))
+            .collect();
+
+        let mut seen = Vec::new();
+        let mut cursor = None;
+        loop {
+            let request = PageRequest::<String>::parse(&query(3, cursor)).unwrap();
+            let page = request.select(items.clone());
+            assert_eq!(page.total, items.len());
+            assert!(page.items.len() <= 3);
+            seen.extend(page.items);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(seen, vec![1, 2, 3, 5, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_cursor_is_stable_under_inserts() {
+        let request = PageRequest::<Reverse<usize>>::parse(&query(2, None)).unwrap();
+        let page = request.select((0..5).map(|line| (Reverse(line), line)));
+        assert_eq!(page.items, vec![4, 3]);
+
+        // A newer item does not shift the following page
+        let request = PageRequest::parse(&query(2, page.next_cursor)).unwrap();
+        let page = request.select((0..6).map(|line| (Reverse(line), line)));
+        assert_eq!(page.items, vec![2, 1]);
+        assert_eq!(page.total, 6);
+    }
+
+    #[test]
+    fn test_take_sorted_matches_select() {
+        let mut cursor = None;
+        loop {
+            let request = PageRequest::<u32>::parse(&query(4, cursor)).unwrap();
+            let selected = request.select((0..10).map(|i| (i, i)));
+            let taken = request.take_sorted((0..10).map(|i| (i, i)), 10);
+            assert_eq!(taken.items, selected.items);
+            assert_eq!(taken.next_cursor, selected.next_cursor);
+            assert_eq!(taken.total, selected.total);
+            cursor = taken.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_limit_and_cursor() {
+        assert!(PageRequest::<String>::parse(&query(0, None)).is_err());
+        assert!(PageRequest::<String>::parse(&query(1, Some("zz".to_string()))).is_err());
+        let capped = PageRequest::<String>::parse(&query(MAX_LIMIT + 1, None)).unwrap();
+        assert_eq!(capped.limit, MAX_LIMIT);
+    }
+}
@@ -34,14 +34,16 @@ async fn coupling_seed(
     State(state): State<AppState>,
     Json(request): Json<CouplingSeedRequest>,
 ) -> Result<Json<CouplingSeedResponse>> {
-    let mut coupling_engine = state
-        .coupling_engine
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?;
-
-    let result = coupling_engine
-        .inject_seed(&request.event)
-        .map_err(|e| ApiError::Processing(format!("Failed to inject seed: {}", e)))?;
+    let result = state
+        .blocking(move |state| {
+            state
+                .coupling_engine
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?
+                .inject_seed(&request.event)
+                .map_err(|e| ApiError::Processing(format!("Failed to inject seed: {}", e)))
+        })
+        .await?;
 
     Ok(Json(CouplingSeedResponse {
         status: "ok".to_string(),
@@ -65,14 +67,16 @@ async fn coupling_sync(
     State(state): State<AppState>,
     Json(request): Json<CouplingSyncRequest>,
 ) -> Result<Json<CouplingSyncResponse>> {
-    let mut coupling_engine = state
-        .coupling_engine
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?;
-
-    let result = coupling_engine
-        .sync_hdag(request.threshold)
-        .map_err(|e| ApiError::Processing(format!("Failed to sync HDAG: {}", e)))?;
+    let result = state
+        .blocking(move |state| {
+            state
+                .coupling_engine
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?
+                .sync_hdag(request.threshold)
+                .map_err(|e| ApiError::Processing(format!("Failed to sync HDAG: {}", e)))
+        })
+        .await?;
 
     Ok(Json(CouplingSyncResponse {
         status: "ok".to_string(),
@@ -97,18 +101,20 @@ async fn spiral_nav(
     State(state): State<AppState>,
     Json(request): Json<SpiralNavRequest>,
 ) -> Result<Json<SpiralNavResponse>> {
-    let mut coupling_engine = state
-        .coupling_engine
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?;
-
-    let result = coupling_engine
-        .navigate_spiral(
-            request.theta_current,
-            &request.candidates,
-            None, // params
-        )
-        .map_err(|e| ApiError::Processing(format!("Failed to navigate spiral: {}", e)))?;
+    let result = state
+        .blocking(move |state| {
+            state
+                .coupling_engine
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?
+                .navigate_spiral(
+                    request.theta_current,
+                    &request.candidates,
+                    None, // params
+                )
+                .map_err(|e| ApiError::Processing(format!("Failed to navigate spiral: {}", e)))
+        })
+        .await?;
 
     // Extract best_theta and best_score from result
     let best_theta = result
@@ -144,14 +150,16 @@ async fn spiral_condense(
     State(state): State<AppState>,
     Json(request): Json<SpiralCondenseRequest>,
 ) -> Result<Json<SpiralCondenseResponse>> {
-    let mut coupling_engine = state
-        .coupling_engine
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?;
-
-    let result = coupling_engine
-        .condense_histories(&request.histories, &request.mode)
-        .map_err(|e| ApiError::Processing(format!("Failed to condense histories: {}", e)))?;
+    let result = state
+        .blocking(move |state| {
+            state
+                .coupling_engine
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?
+                .condense_histories(&request.histories, &request.mode)
+                .map_err(|e| ApiError::Processing(format!("Failed to condense histories: {}", e)))
+        })
+        .await?;
 
     let condensed = result
         .get("condensed")
@@ -1,13 +1,19 @@
 /// Domain Layer API endpoints - Resonit, Resonat, MeshHolo, Infogenome
 use axum::{
     extract::{Path, Query, State},
+    response::Response,
     routing::{get, post},
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
-use crate::{error::ApiError, AppState, Result};
+use super::jobs::{self, JobMode};
+use crate::{
+    error::ApiError,
+    jobs::{Job, JobKind},
+    AppState, Result,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -27,7 +33,7 @@ pub fn router() -> Router<AppState> {
 }
 
 /// Process domain data through MEF pipeline
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct DomainProcessRequest {
     data: Vec<f64>,
     domain_type: String,
@@ -63,16 +69,28 @@ struct DomainMetricsResult {
     cross_domain_transfers: usize,
 }
 
+/// Run the domain pipeline as a background job
+///
+/// Example: `POST /domain/process?async=true`
 async fn process_domain_data(
     State(state): State<AppState>,
+    Query(mode): Query<JobMode>,
     Json(request): Json<DomainProcessRequest>,
-) -> Result<Json<DomainProcessResponse>> {
+) -> Result<Response> {
+    let params = serde_json::to_value(&request)?;
+    jobs::submit(&state, JobKind::DomainProcess, &params, 1, mode).await
+}
+
+/// Job body of [`process_domain_data`]
+pub(crate) fn run_domain_process(state: &AppState, job: &Job) -> Result<JsonValue> {
+    let request: DomainProcessRequest = serde_json::from_value(state.jobs.params(&job.id)?)?;
+
     // Convert request data to JSON Value for domain adapter
     let raw_data = serde_json::json!({ "data": request.data });
 
     // Try to process through DomainLayer
     // If adapter doesn't exist, return a simplified result
-    let mut domain_layer = state.domain_layer.lock().unwrap();
+    let mut domain_layer = state.domain_layer.write().unwrap();
 
     let result = match domain_layer.process_domain_data(
         &raw_data,
@@ -107,7 +125,7 @@ async fn process_domain_data(
         }
     };
 
-    Ok(Json(DomainProcessResponse {
+    Ok(serde_json::to_value(DomainProcessResponse {
         resonat_id: result.resonat_id,
         mesh_id: result.mesh_id,
         tic_id: result.tic_id,
@@ -125,7 +143,7 @@ async fn process_domain_data(
             meshes_triangulated: result.metrics.meshes_triangulated,
             cross_domain_transfers: result.metrics.cross_domain_transfers,
         },
-    }))
+    })?)
 }
 
 /// Create a Resonit (elementary information atom)
@@ -174,7 +192,7 @@ async fn create_resonit(
     let resonance = (sigma.psi + sigma.rho + sigma.omega) / 3.0;
 
     {
-        let domain_layer = state.domain_layer.lock().unwrap();
+        let domain_layer = state.domain_layer.read().unwrap();
         let mut resonits = domain_layer.resonits.lock().unwrap();
         resonits.insert(resonit_id.clone(), resonit);
     }
@@ -192,7 +210,7 @@ async fn get_resonit(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<ResonitResponse>> {
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
     let resonits = domain_layer.resonits.lock().unwrap();
 
     let resonit = resonits
@@ -235,7 +253,7 @@ async fn cluster_resonat(
     use mef_domains::Resonat;
 
     // Load resonits from storage
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
     let resonits_map = domain_layer.resonits.lock().unwrap();
 
     let mut resonits = Vec::new();
@@ -275,7 +293,7 @@ async fn get_resonat(
     State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<ResonatResponse>> {
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
     let resonats = domain_layer.resonats.lock().unwrap();
 
     let resonat = resonats
@@ -316,7 +334,7 @@ async fn triangulate_mesh(
     use mef_domains::MeshHolo;
 
     // Load Resonat from storage
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
     let resonats = domain_layer.resonats.lock().unwrap();
 
     let resonat = resonats
@@ -368,7 +386,7 @@ async fn get_mesh(
     Path(id): Path<String>,
     Query(_query): Query<GetMeshQuery>,
 ) -> Result<Json<MeshResponse>> {
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
     let meshes = domain_layer.meshes.lock().unwrap();
 
     let mesh = meshes
@@ -420,12 +438,19 @@ async fn homeomorphic_transfer(
     // Process data through domain layer to create a mesh
     let raw_data = serde_json::json!({ "data": request.data });
 
-    let mut domain_layer = state.domain_layer.lock().unwrap();
-    let result = domain_layer.process_domain_data(
-        &raw_data,
-        &request.source_domain,
-        Some(&request.target_domain),
-    )?;
+    let source_domain = request.source_domain.clone();
+    let target_domain = request.target_domain.clone();
+    let result =
+        state
+            .blocking(move |state| {
+                let mut domain_layer = state.domain_layer.write().unwrap();
+                Ok(domain_layer.process_domain_data(
+                    &raw_data,
+                    &source_domain,
+                    Some(&target_domain),
+                )?)
+            })
+            .await?;
 
     // Check if cross-domain transfer occurred
     let (preserved, distortion) = if let Some(cross_domain) = result.cross_domain {
@@ -464,7 +489,7 @@ async fn check_compatibility(
     State(state): State<AppState>,
     Query(query): Query<CompatibilityQuery>,
 ) -> Result<Json<CompatibilityResponse>> {
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
 
     // Check if both adapters exist
     let source_exists = domain_layer.adapters.contains_key(&query.source);
@@ -505,7 +530,7 @@ async fn evolve_infogenome(
     State(state): State<AppState>,
     Json(request): Json<EvolveInfogenomeRequest>,
 ) -> Result<Json<InfogenomeResponse>> {
-    let mut domain_layer = state.domain_layer.lock().unwrap();
+    let mut domain_layer = state.domain_layer.write().unwrap();
 
     // Evolve population for requested generations
     for _ in 0..request.generations {
@@ -550,7 +575,7 @@ async fn evolve_infogenome(
 
 /// Get best Infogenome from population
 async fn get_best_infogenome(State(state): State<AppState>) -> Result<Json<InfogenomeResponse>> {
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
 
     let best = domain_layer
         .infogenomes
@@ -584,7 +609,7 @@ struct DomainStatusResponse {
 }
 
 async fn get_domain_status(State(state): State<AppState>) -> Result<Json<DomainStatusResponse>> {
-    let domain_layer = state.domain_layer.lock().unwrap();
+    let domain_layer = state.domain_layer.read().unwrap();
 
     let resonits_total = domain_layer.resonits.lock().unwrap().len();
     let resonats_total = domain_layer.resonats.lock().unwrap().len();
@@ -637,7 +662,8 @@ mod tests {
             domain_type: "test".to_string(),
             params: None,
         };
-        let result = process_domain_data(State(state), Json(request)).await;
+        let result =
+            process_domain_data(State(state), Query(JobMode::default()), Json(request)).await;
         assert!(result.is_ok());
     }
 
@@ -659,7 +685,7 @@ mod tests {
         // Create some resonits first
         use mef_domains::{Resonit, Sigma};
         {
-            let domain_layer = state.domain_layer.lock().unwrap();
+            let domain_layer = state.domain_layer.read().unwrap();
             let mut resonits = domain_layer.resonits.lock().unwrap();
             let r1 = Resonit::new(Sigma::new(0.5, 0.5, 0.5), "test".to_string(), 0);
             let r2 = Resonit::new(Sigma::new(0.6, 0.6, 0.6), "test".to_string(), 0);
@@ -683,7 +709,7 @@ mod tests {
         // Create a resonat first
         use mef_domains::{Resonat, Resonit, Sigma};
         {
-            let domain_layer = state.domain_layer.lock().unwrap();
+            let domain_layer = state.domain_layer.read().unwrap();
             let r1 = Resonit::new(Sigma::new(0.5, 0.5, 0.5), "test".to_string(), 0);
             let r2 = Resonit::new(Sigma::new(0.6, 0.6, 0.6), "test".to_string(), 0);
             let resonat = Resonat::new(vec![r1, r2]).unwrap();
@@ -0,0 +1,218 @@
+/// Live event endpoints - Server-Sent Events and WebSocket
+///
+/// Both endpoints accept `types` (comma-separated event kinds) and
+/// `last_event_id` to resume after a reconnect; SSE clients may send the
+/// standard `Last-Event-ID` header instead. A subscriber that falls behind
+/// the bus is disconnected and resumes from its last event ID; streams also
+/// end when the server shuts down.
+use axum::{
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::HeaderMap,
+    response::{
+        sse::{Event as SseEvent, KeepAlive, Sse},
+        Response,
+    },
+    routing::get,
+    Router,
+};
+use futures_util::stream::{self, BoxStream, StreamExt};
+use serde::Deserialize;
+use std::convert::Infallible;
+use tokio_stream::wrappers::BroadcastStream;
+
+use crate::{
+    error::ApiError,
+    events::{Event, EventKind},
+    AppState, Result,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/events", get(sse_events))
+        .route("/events/ws", get(ws_events))
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct EventsQuery {
+    /// Comma-separated event kinds; all kinds if absent
+    #[serde(default)]
+    types: Option<String>,
+    #[serde(default)]
+    last_event_id: Option<u64>,
+}
+
+impl EventsQuery {
+    fn kinds(&self) -> Result<Option<Vec<EventKind>>> {
+        let Some(types) = &self.types else {
+            return Ok(None);
+        };
+        types
+            .split(',')
+            .filter(|name| !name.trim().is_empty())
+            .map(|name| {
+                EventKind::parse(name)
+                    .ok_or_else(|| ApiError::InvalidInput(format!("Unknown event type: {}", name)))
+            })
+            .collect::<Result<Vec<_>>>()
+            .map(Some)
+    }
+}
+
+/// Retained events after `last_event_id` followed by live events of the given kinds
+///
+/// The stream ends when the subscriber lags behind the bus or the bus is
+/// closed.
+fn subscribe(
+    state: &AppState,
+    kinds: Option<Vec<EventKind>>,
+    last_event_id: Option<u64>,
+) -> BoxStream<'static, Event> {
+    let subscription = state.events.subscribe(last_event_id);
+    let live = BroadcastStream::new(subscription.live)
+        .take_while(|result| std::future::ready(result.is_ok()))
+        .filter_map(|result| std::future::ready(result.ok()))
+        .take_until(state.events.closed());
+
+    stream::iter(subscription.backlog)
+        .chain(live)
+        .filter(move |event| {
+            let wanted = kinds.as_ref().is_none_or(|k| k.contains(&event.kind));
+            std::future::ready(wanted)
+        })
+        .boxed()
+}
+
+/// Stream events as Server-Sent Events
+///
+/// Example: `GET /events?types=block_appended,gate_decision`
+async fn sse_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<EventsQuery>,
+) -> Result<Sse<BoxStream<'static, std::result::Result<SseEvent, Infallible>>>> {
+    let kinds = query.kinds()?;
+    let last_event_id = match headers.get("last-event-id") {
+        Some(value) => Some(
+            value
+                .to_str()
+                .ok()
+                .and_then(|id| id.trim().parse().ok())
+                .ok_or_else(|| ApiError::InvalidInput("Invalid Last-Event-ID".to_string()))?,
+        ),
+        None => query.last_event_id,
+    };
+
+    let events = subscribe(&state, kinds, last_event_id)
+        .map(|event| {
+            let sse = SseEvent::default()
+                .id(event.id.to_string())
+                .event(event.kind.as_str());
+            Ok(sse.json_data(&event).unwrap_or_else(|_| sse_error(&event)))
+        })
+        .boxed();
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+fn sse_error(event: &Event) -> SseEvent {
+    SseEvent::default()
+        .id(event.id.to_string())
+        .event("error")
+        .data("Failed to serialize event")
+}
+
+/// Stream events over a WebSocket as JSON text messages
+///
+/// Example: `GET /events/ws?types=gate_decision&last_event_id=42`
+async fn ws_events(
+    State(state): State<AppState>,
+    Query(query): Query<EventsQuery>,
+    upgrade: WebSocketUpgrade,
+) -> Result<Response> {
+    let events = subscribe(&state, query.kinds()?, query.last_event_id);
+    Ok(upgrade.on_upgrade(move |socket| forward(socket, events)))
+}
+
+async fn forward(mut socket: WebSocket, mut events: BoxStream<'static, Event>) {
+    loop {
+        tokio::select! {
+            event = events.next() => {
+                let Some(event) = event else {
+                    let frame = CloseFrame {
+                        code: close_code::AGAIN,
+                        reason: "Stream ended; reconnect with last_event_id".into(),
+                    };
+                    let _ = socket.send(Message::Close(Some(frame))).await;
+                    break;
+                };
+                let text = match serde_json::to_string(&event) {
+                    Ok(text) => text,
+                    Err(e) => {
+                        tracing::error!("Failed to serialize event {}: {}", event.id, e);
+                        continue;
+                    }
+                };
+                if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ApiConfig;
+    use axum::{body::Body, http::Request};
+    use http_body_util::BodyExt;
+    use serde_json::json;
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn test_sse_resumes_and_filters() {
+        let config = ApiConfig::default();
+        let state = AppState::new(config).await.unwrap();
+
+        let first = state
+            .events
+            .publish(EventKind::BlockAppended, json!({"index": 1}));
+        state
+            .events
+            .publish(EventKind::GateDecision, json!({"decision": "HOLD"}));
+        let third = state
+            .events
+            .publish(EventKind::BlockAppended, json!({"index": 2}));
+
+        let app = router().with_state(state);
+        let request = Request::get("/events?types=block_appended")
+            .header("Last-Event-ID", first.id.to_string())
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        // The first frame is the replayed block after the resume point
+        let mut body = response.into_body();
+        let frame = body.frame().await.unwrap().unwrap();
+        let text = String::from_utf8(frame.into_data().unwrap().to_vec()).unwrap();
+        assert!(text.contains(&format!("id: {}", third.id)));
+        assert!(text.contains("event: block_appended"));
+    }
+
+    #[test]
+    fn test_unknown_event_type_is_rejected() {
+        let query = EventsQuery {
+            types: Some("block_appended,bogus".to_string()),
+            last_event_id: None,
+        };
+        assert!(matches!(query.kinds(), Err(ApiError::InvalidInput(_))));
+    }
+}
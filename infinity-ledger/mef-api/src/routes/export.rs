@@ -0,0 +1,362 @@
+/// Export endpoints - streamed JSON dump and audit bundle
+///
+/// Exports are written on a blocking thread into a bounded channel, so the
+/// response is sent with chunked transfer encoding while the store is read,
+/// and gzip-compressed when the client sends `Accept-Encoding: gzip`.
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, State},
+    http::header,
+    response::Response,
+    routing::get,
+    Router,
+};
+use chrono::Utc;
+use serde_json::{json, Value as JsonValue};
+use std::io::{self, Write};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tower_http::compression::CompressionLayer;
+
+use crate::{error::ApiError, AppState, Result};
+use mef_audit::MEFAuditLogger;
+use mef_ledger::{LedgerSnapshot, VerifyOptions};
+
+/// Bytes buffered before a chunk is sent
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Chunks buffered in the channel before the writer waits for the client
+const CHANNEL_CAPACITY: usize = 8;
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/export/:format", get(export))
+        .layer(CompressionLayer::new())
+}
+
+/// Writer that forwards fixed-size chunks to the response body
+struct ChunkWriter {
+    tx: mpsc::Sender<io::Result<Bytes>>,
+    buf: Vec<u8>,
+}
+
+impl Write for ChunkWriter {
+    fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+        self.buf.extend_from_slice(data);
+        if self.buf.len() >= CHUNK_SIZE {
+            self.flush()?;
+        }
+        Ok(data.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buf.is_empty() {
+            return Ok(());
+        }
+        let chunk = Bytes::from(std::mem::replace(
+            &mut self.buf,
+            Vec::with_capacity(CHUNK_SIZE),
+        ));
+        self.tx
+            .blocking_send(Ok(chunk))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "Client disconnected"))
+    }
+}
+
+type ExportWriter = fn(&AppState, &LedgerSnapshot, &mut ChunkWriter) -> anyhow::Result<()>;
+
+/// Stream an export of the store
+///
+/// `json` dumps the ledger blocks, TICs, snapshots and vector collections;
+/// `audit` bundles the audit report, a full chain verification, the audit
+/// events and the Merkaba gate decisions.
+async fn export(State(state): State<AppState>, Path(format): Path<String>) -> Result<Response> {
+    let writer: ExportWriter = match format.as_str() {
+        "json" => write_json_dump,
+        "audit" => write_audit_bundle,
+        _ => {
+            return Err(ApiError::InvalidInput(format!(
+                "Unsupported export format: {} (expected json or audit)",
+                format
+            )))
+        }
+    };
+
+    // Pin the ledger before streaming so a missing ledger is still a proper error
+    let ledger_path = state.config.ledger_path.clone();
+    let ledger = tokio::task::spawn_blocking(move || LedgerSnapshot::open(ledger_path))
+        .await
+        .map_err(|e| ApiError::Internal(format!("Export task failed: {}", e)))?
+        .map_err(|e| ApiError::Ledger(format!("Failed to open ledger: {}", e)))?;
+
+    let (tx, rx) = mpsc::channel(CHANNEL_CAPACITY);
+    tokio::task::spawn_blocking(move || {
+        let mut out = ChunkWriter {
+            tx: tx.clone(),
+            buf: Vec::with_capacity(CHUNK_SIZE),
+        };
+        let result = writer(&state, &ledger, &mut out).and_then(|_| Ok(out.flush()?));
+        if let Err(e) = result {
+            // Headers are already sent; aborting the body marks the export as incomplete
+            tracing::error!("Export failed: {:#}", e);
+            let _ = tx.blocking_send(Err(io::Error::other(e.to_string())));
+        }
+    });
+
+    Response::builder()
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(
+            header::CONTENT_DISPOSITION,
+            format!(
+                "attachment; filename=\"mef-{}-{}.json\"",
+                format,
+                Utc::now().format("%Y%m%dT%H%M%SZ")
+            ),
+        )
+        .body(Body::from_stream(ReceiverStream::new(rx)))
+        .map_err(|e| ApiError::Internal(format!("Failed to build response: {}", e)))
+}
+
+/// Write `"key":` followed by a JSON array built from `items`
+fn write_array<T>(
+    out: &mut ChunkWriter,
+    key: &str,
+    items: impl IntoIterator<Item = anyhow::Result<T>>,
+) -> anyhow::Result<usize>
+where
+    T: serde::Serialize,
+{
+    write!(out, "{}:[", serde_json::to_string(key)?)?;
+    let mut count = 0;
+    for item in items {
+        if count > 0 {
+            out.write_all(b",")?;
+        }
+        serde_json::to_writer(&mut *out, &item?)?;
+        count += 1;
+    }
+    out.write_all(b"]")?;
+    Ok(count)
+}
+
+/// Parsed artifacts with the given extension in the store directory, by name
+fn artifacts(
+    state: &AppState,
+    extension: &str,
+) -> anyhow::Result<impl Iterator<Item = anyhow::Result<JsonValue>>> {
+    let mut paths: Vec<_> = match std::fs::read_dir(state.store_path.as_ref()) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().and_then(|e| e.to_str()) == Some(extension))
+            .collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    paths.sort();
+
+    Ok(paths.into_iter().map(|path| {
+        let contents = std::fs::read_to_string(&path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }))
+}
+
+fn write_json_dump(
+    state: &AppState,
+    ledger: &LedgerSnapshot,
+    out: &mut ChunkWriter,
+) -> anyhow::Result<()> {
+    let blocks = &ledger.index().blocks;
+    write!(
+        out,
+        "{{\"exported\":{},\"ledger\":{{\"block_count\":{},\"head_hash\":{},",
+        serde_json::to_string(&Utc::now().to_rfc3339())?,
+        blocks.len(),
+        serde_json::to_string(&blocks.last().map(|b| &b.hash))?,
+    )?;
+    write_array(
+        out,
+        "blocks",
+        blocks.iter().map(|summary| {
+            ledger
+                .get_block(summary.index)?
+                .ok_or_else(|| anyhow::anyhow!("Block {} not found", summary.index))
+        }),
+    )?;
+    out.write_all(b"},")?;
+    write_array(out, "tics", artifacts(state, "tic")?)?;
+    out.write_all(b",")?;
+    write_array(out, "snapshots", artifacts(state, "spiral")?)?;
+
+    // Collections are copied one at a time to keep the index manager available
+    let mut names: Vec<String> = state
+        .index_manager
+        .read()
+        .map_err(|e| anyhow::anyhow!("Failed to lock index manager: {}", e))?
+        .collections
+        .keys()
+        .cloned()
+        .collect();
+    names.sort();
+    out.write_all(b",\"collections\":{")?;
+    for (i, name) in names.iter().enumerate() {
+        let collection = state
+            .index_manager
+            .read()
+            .map_err(|e| anyhow::anyhow!("Failed to lock index manager: {}", e))?
+            .collections
+            .get(name)
+            .cloned();
+        if i > 0 {
+            out.write_all(b",")?;
+        }
+        write!(out, "{}:", serde_json::to_string(name)?)?;
+        serde_json::to_writer(&mut *out, &collection)?;
+    }
+    out.write_all(b"}}")?;
+    Ok(())
+}
+
+fn write_audit_bundle(
+    state: &AppState,
+    ledger: &LedgerSnapshot,
+    out: &mut ChunkWriter,
+) -> anyhow::Result<()> {
+    // Include events still buffered by the server's logger
+    state
+        .audit
+        .lock()
+        .map_err(|e| anyhow::anyhow!("Failed to lock audit log: {}", e))?
+        .flush()?;
+    let mut logger = MEFAuditLogger::new(state.config.logs_path.join("audit"))?;
+    let report = logger.generate_audit_report()?;
+
+    // Verify the pinned snapshot so appends are not blocked and the result
+    // describes the same blocks as `block_count` and `head_hash`
+    let verification = ledger.verify_chain_parallel(&VerifyOptions::default(), |_| {})?;
+
+    let blocks = &ledger.index().blocks;
+    write!(
+        out,
+        "{{\"generated\":{},\"report\":{},\"ledger\":{},",
+        serde_json::to_string(&report.generated)?,
+        serde_json::to_string(&report)?,
+        json!({
+            "block_count": blocks.len(),
+            "head_hash": blocks.last().map(|b| &b.hash),
+            "verification": verification,
+        }),
+    )?;
+
+    out.write_all(b"\"events\":[")?;
+    let mut first = true;
+    logger.for_each_event(|event| {
+        if !first {
+            out.write_all(b",")?;
+        }
+        first = false;
+        serde_json::to_writer(&mut *out, event)?;
+        Ok(())
+    })?;
+    out.write_all(b"],")?;
+
+    // The gate appends one JSON object per line
+    let gate_log = state
+        .merkaba_gate
+        .read()
+        .map_err(|e| anyhow::anyhow!("Failed to lock Merkaba gate: {}", e))?
+        .audit_path
+        .clone();
+    let gate_events: Vec<String> = match std::fs::read_to_string(&gate_log) {
+        Ok(contents) => contents.lines().map(str::to_string).collect(),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    write_array(
+        out,
+        "gate_events",
+        gate_events
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| Ok(serde_json::from_str::<JsonValue>(line)?)),
+    )?;
+    out.write_all(b"}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::test_state;
+    use axum::{body::to_bytes, http::Request};
+    use tower::ServiceExt;
+
+    async fn body_json(response: Response) -> JsonValue {
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_json_export_contains_ledger() {
+        let (_dir, state) = test_state().await;
+
+        let block = {
+            let mut ledger = state.ledger.write().unwrap();
+            let tic = json!({
+                "tic_id": format!("tic_export_{}", uuid::Uuid::new_v4()),
+                "seed": "export",
+                "fixpoint": [1.0, 0.0],
+                "window": [],
+                "invariants": {},
+                "sigma_bar": {},
+                "proof": null,
+            });
+            ledger.append_block(&tic, &json!({"id": "snap"})).unwrap()
+        };
+
+        let response = export(State(state), Path("json".to_string()))
+            .await
+            .unwrap();
+        let dump = body_json(response).await;
+        let hashes: Vec<&JsonValue> = dump["ledger"]["blocks"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|b| &b["hash"])
+            .collect();
+        assert!(hashes.contains(&&json!(block.hash)));
+        assert!(dump["tics"].is_array());
+        assert!(dump["collections"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_audit_export_bundles_report() {
+        let (_dir, state) = test_state().await;
+
+        let response = export(State(state), Path("audit".to_string()))
+            .await
+            .unwrap();
+        let bundle = body_json(response).await;
+        assert!(bundle["report"]["summary"]["total_events"].is_u64());
+        assert!(bundle["ledger"]["verification"]["failures"].is_array());
+        assert!(bundle["events"].is_array());
+        assert!(bundle["gate_events"].is_array());
+    }
+
+    #[tokio::test]
+    async fn test_export_gzip_and_unknown_format() {
+        let (_dir, state) = test_state().await;
+        let app = router().with_state(state);
+
+        let request = Request::get("/export/json")
+            .header(header::ACCEPT_ENCODING, "gzip")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+        assert!(response.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let request = Request::get("/export/xml").body(Body::empty()).unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), axum::http::StatusCode::BAD_REQUEST);
+    }
+}
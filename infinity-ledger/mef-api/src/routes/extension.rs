@@ -76,7 +76,7 @@ async fn derive_knowledge(
     // In a full implementation, this would use mef_knowledge::derive_seed and compute_mef_id
     let mef_id = format!(
         "mef_{}",
-        uuid::Uuid::new_v4().to_string().replace("-", "")[..16].to_string()
+        &uuid::Uuid::new_v4().to_string().replace("-", "")[..16]
     );
 
     let knowledge = KnowledgeObject::new(
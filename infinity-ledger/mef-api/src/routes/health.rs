@@ -1,7 +1,10 @@
 /// Health check and monitoring endpoints
-use axum::{extract::State, routing::get, Json, Router};
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use chrono::Utc;
+use mef_ledger::lock::LOCK_FILE;
 use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::path::Path;
 
 use crate::{models::*, AppState};
 
@@ -30,26 +33,79 @@ async fn healthz() -> Json<HealthResponse> {
 }
 
 /// Readiness endpoint - readiness probe
-async fn readyz(State(_state): State<AppState>) -> Json<ReadyResponse> {
+///
+/// Answers `503 Service Unavailable` unless every component is usable: the
+/// ledger, snapshot store and audit log directories are writable, every
+/// collection file is loaded, the job workers run and the server is not
+/// shutting down.
+async fn readyz(State(state): State<AppState>) -> (StatusCode, Json<ReadyResponse>) {
+    // The checks touch the disk
+    let components = state
+        .blocking(|state| Ok(check_components(state)))
+        .await
+        .unwrap_or_default();
+    let all_ready = !components.is_empty() && components.values().all(|&v| v);
+
+    let status = if all_ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(ReadyResponse {
+            ready: all_ready,
+            components,
+        }),
+    )
+}
+
+fn check_components(state: &AppState) -> HashMap<String, bool> {
     let mut components = HashMap::new();
 
-    // Check snapshot store
-    components.insert("snapshot_store".to_string(), true);
+    components.insert("snapshot_store".to_string(), is_writable(&state.store_path));
 
-    // Check ledger
-    components.insert("ledger".to_string(), true);
+    // Appending opens the lock file of the ledger directory for writing
+    let ledger_writable = state.ledger.read().is_ok()
+        && OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(state.config.ledger_path.join(LOCK_FILE))
+            .is_ok();
+    components.insert("ledger".to_string(), ledger_writable);
 
-    let all_ready = components.values().all(|&v| v);
+    let indexes_loaded = state
+        .index_manager
+        .read()
+        .ok()
+        .and_then(|index_manager| index_manager.unloaded_collections().ok())
+        .is_some_and(|unloaded| unloaded.is_empty());
+    components.insert("vector_index".to_string(), indexes_loaded);
 
-    Json(ReadyResponse {
-        ready: all_ready,
-        components,
-    })
+    components.insert("coupling".to_string(), state.coupling_engine.read().is_ok());
+    components.insert(
+        "audit_log".to_string(),
+        state.audit.lock().is_ok() && is_writable(&state.config.logs_path.join("audit")),
+    );
+    components.insert("jobs".to_string(), !state.jobs.is_closed());
+    components.insert("serving".to_string(), !state.is_draining());
+
+    components
+}
+
+/// Whether a file can be created in `dir`
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(".readyz");
+    let writable = std::fs::write(&probe, b"").is_ok();
+    let _ = std::fs::remove_file(&probe);
+    writable
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::test_state;
     use crate::ApiConfig;
 
     #[tokio::test]
@@ -66,4 +122,38 @@ mod tests {
         let response = healthz().await;
         assert_eq!(response.0.status, "healthy");
     }
+
+    #[tokio::test]
+    async fn test_readyz_reflects_component_health() {
+        let (dir, state) = test_state().await;
+
+        let (status, Json(ready)) = readyz(State(state.clone())).await;
+        assert_eq!(status, StatusCode::OK, "{:?}", ready.components);
+        assert!(ready.ready);
+
+        // A collection file that cannot be loaded makes the indexes unready
+        let broken = dir.path().join("store/vector_db/broken.json");
+        std::fs::write(&broken, "{not json").unwrap();
+        let (status, Json(ready)) = readyz(State(state.clone())).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!ready.components["vector_index"]);
+        assert!(ready.components["ledger"]);
+        std::fs::remove_file(&broken).unwrap();
+
+        // Shutting down flushes buffered audit events
+        state.events.publish(
+            crate::events::EventKind::GateDecision,
+            serde_json::json!({"decision": "HOLD"}),
+        );
+        let audit_log = dir.path().join("logs/audit/events.jsonl");
+        assert!(!audit_log.exists());
+        state.shutdown().await.unwrap();
+        let events = std::fs::read_to_string(audit_log).unwrap();
+        assert!(events.contains("GATE_DECISION"));
+
+        let (status, Json(ready)) = readyz(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!ready.components["serving"]);
+        assert!(!ready.components["jobs"]);
+    }
 }
@@ -1,6 +1,7 @@
 /// Index management endpoints
 use axum::{
     extract::{Query, State},
+    response::Response,
     routing::{get, post},
     Json, Router,
 };
@@ -8,7 +9,13 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 use std::collections::HashMap;
 
-use crate::{error::ApiError, AppState, Result};
+use super::jobs::{self, JobMode};
+use crate::{
+    error::ApiError,
+    events::EventKind,
+    jobs::{Job, JobKind},
+    AppState, Result,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -34,7 +41,7 @@ struct ProviderInfo {
 async fn list_providers(State(state): State<AppState>) -> Result<Json<ProvidersResponse>> {
     let index_manager = state
         .index_manager
-        .lock()
+        .read()
         .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
 
     let providers_raw = index_manager.list_providers();
@@ -63,7 +70,7 @@ async fn list_providers(State(state): State<AppState>) -> Result<Json<ProvidersR
 }
 
 /// Build index for a collection
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct BuildIndexRequest {
     collection: String,
 }
@@ -75,24 +82,62 @@ struct BuildIndexResponse {
     result: JsonValue,
 }
 
+/// Rebuild the index as a background job
+///
+/// Example: `POST /index/build?async=true`
 async fn build_index(
     State(state): State<AppState>,
+    Query(mode): Query<JobMode>,
     Json(request): Json<BuildIndexRequest>,
-) -> Result<Json<BuildIndexResponse>> {
-    let mut index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+) -> Result<Response> {
+    let params = serde_json::to_value(&request)?;
+    jobs::submit(&state, JobKind::IndexBuild, &params, 1, mode).await
+}
 
-    let result = index_manager
-        .build_index(&request.collection)
+/// Job body of [`build_index`]
+pub(crate) fn run_build_index(state: &AppState, job: &Job) -> Result<JsonValue> {
+    let request: BuildIndexRequest = serde_json::from_value(state.jobs.params(&job.id)?)?;
+
+    // Build from a snapshot without holding the index manager, so searches
+    // and upserts keep going; the write lock is only taken for the swap
+    let mut build = {
+        let index_manager = state
+            .index_manager
+            .read()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+        if !index_manager.collections.contains_key(&request.collection) {
+            return Err(ApiError::NotFound(format!(
+                "Collection {} not found",
+                request.collection
+            )));
+        }
+        index_manager
+            .prepare_build(&request.collection)
+            .map_err(|e| ApiError::VectorDB(format!("Failed to build index: {}", e)))?
+    };
+    build.run();
+
+    let result = state
+        .index_manager
+        .write()
+        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?
+        .install_build(build)
         .map_err(|e| ApiError::VectorDB(format!("Failed to build index: {}", e)))?;
 
-    Ok(Json(BuildIndexResponse {
+    let result = serde_json::to_value(result).unwrap_or(JsonValue::Null);
+    state.events.publish(
+        EventKind::IndexRebuilt,
+        serde_json::json!({
+            "collection": request.collection,
+            "result": result,
+        }),
+    );
+
+    Ok(serde_json::to_value(BuildIndexResponse {
         collection: request.collection,
         status: "built".to_string(),
-        result: serde_json::to_value(result).unwrap_or(JsonValue::Null),
-    }))
+        result,
+    })?)
 }
 
 /// Get index status
@@ -113,7 +158,7 @@ async fn index_status(
 ) -> Result<Json<IndexStatusResponse>> {
     let index_manager = state
         .index_manager
-        .lock()
+        .read()
         .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
 
     let status = index_manager.get_index_status(&query.collection);
@@ -133,7 +178,7 @@ struct SearchPlanResponse {
 async fn debug_search_plan(State(state): State<AppState>) -> Result<Json<SearchPlanResponse>> {
     let index_manager = state
         .index_manager
-        .lock()
+        .read()
         .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
 
     let plan = index_manager.last_search_plan();
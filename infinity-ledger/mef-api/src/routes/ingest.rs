@@ -14,7 +14,7 @@ pub fn router() -> Router<AppState> {
 }
 
 /// Ingest data into MEF-Core system
-async fn ingest(
+pub(crate) async fn ingest(
     State(state): State<AppState>,
     Json(request): Json<IngestRequest>,
 ) -> Result<Json<IngestResponse>> {
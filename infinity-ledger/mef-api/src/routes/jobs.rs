@@ -0,0 +1,194 @@
+/// Background job endpoints - status, cancellation and retry
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+use crate::{
+    error::ApiError,
+    jobs::{Job, JobKind, JobStatus},
+    AppState, Result,
+};
+
+pub fn router() -> Router<AppState> {
+    Router::new()
+        .route("/jobs", get(list_jobs))
+        .route("/jobs/:id", get(get_job))
+        .route("/jobs/:id/cancel", post(cancel_job))
+        .route("/jobs/:id/retry", post(retry_job))
+}
+
+/// Execution mode of endpoints backed by the job queue
+///
+/// With `?async=true` the endpoint answers `202 Accepted` with the queued job
+/// and a `Location` header; otherwise it waits and returns the job result.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct JobMode {
+    #[serde(default, rename = "async")]
+    pub(crate) run_async: bool,
+}
+
+/// Queue a job and answer according to `mode`
+///
+/// # Arguments
+/// * `kind` - Work to perform
+/// * `params` - Input of the job
+/// * `total` - Units of work for progress reporting
+/// * `mode` - Whether to wait for the result
+pub(crate) async fn submit(
+    state: &AppState,
+    kind: JobKind,
+    params: &JsonValue,
+    total: usize,
+    mode: JobMode,
+) -> Result<Response> {
+    let job = state.jobs.submit(kind, params, total)?;
+    if mode.run_async {
+        return Ok(accepted(job));
+    }
+
+    let job = state.jobs.wait(&job.id).await?;
+    match job.status {
+        JobStatus::Completed => Ok(Json(job.result.unwrap_or(JsonValue::Null)).into_response()),
+        JobStatus::Cancelled => Err(ApiError::Conflict(format!("Job {} was cancelled", job.id))),
+        _ => Err(ApiError::from_parts(
+            job.error_status.unwrap_or(500),
+            job.error
+                .unwrap_or_else(|| format!("Job {} failed", job.id)),
+        )),
+    }
+}
+
+/// `202 Accepted` pointing at the job status
+pub(crate) fn accepted(job: Job) -> Response {
+    (
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, format!("/jobs/{}", job.id))],
+        Json(job),
+    )
+        .into_response()
+}
+
+/// List jobs
+#[derive(Debug, Default, Deserialize)]
+struct ListJobsQuery {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    kind: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct ListJobsResponse {
+    jobs: Vec<Job>,
+    total: usize,
+}
+
+/// Example: `GET /jobs?status=running&kind=bulk_upsert`
+async fn list_jobs(
+    State(state): State<AppState>,
+    Query(query): Query<ListJobsQuery>,
+) -> Result<Json<ListJobsResponse>> {
+    let status = query
+        .status
+        .as_deref()
+        .map(|s| {
+            JobStatus::parse(s)
+                .ok_or_else(|| ApiError::InvalidInput(format!("Unknown job status: {}", s)))
+        })
+        .transpose()?;
+    let kind = query
+        .kind
+        .as_deref()
+        .map(|k| {
+            JobKind::parse(k)
+                .ok_or_else(|| ApiError::InvalidInput(format!("Unknown job kind: {}", k)))
+        })
+        .transpose()?;
+
+    let jobs = state.jobs.list(status, kind);
+    Ok(Json(ListJobsResponse {
+        total: jobs.len(),
+        jobs,
+    }))
+}
+
+/// Get a job with its progress and result
+async fn get_job(State(state): State<AppState>, Path(id): Path<String>) -> Result<Json<Job>> {
+    state
+        .jobs
+        .get(&id)
+        .map(Json)
+        .ok_or_else(|| ApiError::NotFound(format!("Job {} not found", id)))
+}
+
+/// Cancel a queued or running job
+async fn cancel_job(State(state): State<AppState>, Path(id): Path<String>) -> Result<Json<Job>> {
+    state.jobs.cancel(&id).map(Json)
+}
+
+/// Queue a failed or cancelled job again
+async fn retry_job(State(state): State<AppState>, Path(id): Path<String>) -> Result<Response> {
+    state.jobs.retry(&id).map(accepted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::test_state;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_failed_job_reports_error_and_can_be_retried() {
+        let (_dir, state) = test_state().await;
+
+        // Building an index of a missing collection fails with a client error
+        let params = json!({"collection": "missing"});
+        let result = submit(&state, JobKind::IndexBuild, &params, 1, JobMode::default()).await;
+        assert!(result.is_err());
+
+        let Json(listing) = list_jobs(
+            State(state.clone()),
+            Query(ListJobsQuery {
+                status: Some("failed".to_string()),
+                kind: Some("index_build".to_string()),
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(listing.total, 1);
+        let failed = &listing.jobs[0];
+        assert_eq!(failed.attempts, 1);
+        assert!(failed.error.is_some());
+
+        let response = retry_job(State(state.clone()), Path(failed.id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::ACCEPTED);
+        let retried = state.jobs.wait(&failed.id).await.unwrap();
+        assert_eq!(retried.status, JobStatus::Failed);
+        assert_eq!(retried.attempts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_unknown_job_and_filters() {
+        let (_dir, state) = test_state().await;
+
+        let missing = get_job(State(state.clone()), Path("nope".to_string())).await;
+        assert!(matches!(missing, Err(ApiError::NotFound(_))));
+        let missing = cancel_job(State(state.clone()), Path("nope".to_string())).await;
+        assert!(matches!(missing, Err(ApiError::NotFound(_))));
+
+        let query = ListJobsQuery {
+            status: Some("sleeping".to_string()),
+            kind: None,
+        };
+        let invalid = list_jobs(State(state), Query(query)).await;
+        assert!(matches!(invalid, Err(ApiError::InvalidInput(_))));
+    }
+}
@@ -1,24 +1,33 @@
 /// Ledger endpoints - blockchain operations and auditing
 use axum::{
-    extract::{Path, State},
-    routing::{get, post},
+    extract::{Path, Query, State},
+    routing::get,
     Json, Router,
 };
 use chrono::Utc;
+use mef_ledger::{AppendConflict, BlockSummary, KeyRegistry, LedgerQuery};
 use serde_json::json;
 use std::collections::HashMap;
 
-use crate::{error::ApiError, models::*, AppState, Result};
+use crate::{
+    error::ApiError,
+    events::EventKind,
+    models::*,
+    pagination::{Page, PageQuery, PageRequest},
+    AppState, Result,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
-        .route("/ledger", post(append_ledger))
+        .route("/ledger", get(query_ledger).post(append_ledger))
+        .route("/ledger/summaries", get(get_summaries))
+        .route("/ledger/keys", get(get_key_registry))
         .route("/ledger/:index", get(get_block))
         .route("/audit", get(audit))
 }
 
 /// Append a new block to the ledger
-async fn append_ledger(
+pub(crate) async fn append_ledger(
     State(state): State<AppState>,
     Json(request): Json<LedgerAppendRequest>,
 ) -> Result<Json<LedgerAppendResponse>> {
@@ -35,15 +44,37 @@ async fn append_ledger(
     });
     let snapshot_json = json!({ "id": request.snapshot_id });
 
-    // Append to ledger - need to lock the mutex
-    let mut ledger = state
-        .ledger
-        .lock()
-        .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
+    // Appending hashes, signs and writes the block, so run off the async workers
+    let block = state
+        .blocking(move |state| {
+            let mut ledger = state
+                .ledger
+                .write()
+                .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
+            state.config.quotas.check_blocks(&ledger)?;
+
+            match &request.expected_previous_hash {
+                Some(expected) => ledger.append_after(expected, &tic_json, &snapshot_json),
+                None => ledger.append_block(&tic_json, &snapshot_json),
+            }
+            .map_err(|e| match e.downcast_ref::<AppendConflict>() {
+                Some(conflict) => {
+                    ApiError::Conflict(format!("{}; fetch the current head and retry", conflict))
+                }
+                None => ApiError::Ledger(format!("Failed to append block: {}", e)),
+            })
+        })
+        .await?;
 
-    let block = ledger
-        .append_block(&tic_json, &snapshot_json)
-        .map_err(|e| ApiError::Ledger(format!("Failed to append block: {}", e)))?;
+    state.events.publish(
+        EventKind::BlockAppended,
+        json!({
+            "index": block.index,
+            "hash": block.hash,
+            "previous_hash": block.previous_hash,
+            "tic_id": block.tic_id,
+        }),
+    );
 
     Ok(Json(LedgerAppendResponse {
         block_index: block.index as usize,
@@ -52,52 +83,136 @@ async fn append_ledger(
     }))
 }
 
+/// Query blocks by TIC ID, seed, timestamp range or fixpoint norm
+///
+/// Matching blocks are listed in index order, one page at a time.
+///
+/// Example: `GET /ledger?tic_id=tic_123&from=2026-10-15&to=2026-10-16`
+async fn query_ledger(
+    State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
+    Query(mut query): Query<LedgerQuery>,
+) -> Result<Json<Page<BlockSummary>>> {
+    let request = PageRequest::<i32>::parse(&page)?;
+    // `limit` sizes the page; the ledger query returns every match
+    query.limit = None;
+
+    // Filtering scans the whole index under the ledger lock
+    state
+        .blocking(move |state| {
+            let ledger = state
+                .ledger
+                .read()
+                .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
+
+            let indices = ledger
+                .query_indices(&query)
+                .map_err(|e| ApiError::InvalidInput(format!("Invalid ledger query: {}", e)))?;
+            let blocks = &ledger.index().blocks;
+            let page = request.select(indices.into_iter().map(|i| (i, i)));
+
+            Ok(page.map(|i| blocks[i as usize].clone()))
+        })
+        .await
+        .map(Json)
+}
+
 /// Get a specific block by index
-async fn get_block(
+pub(crate) async fn get_block(
     State(state): State<AppState>,
     Path(index): Path<usize>,
 ) -> Result<Json<serde_json::Value>> {
-    let ledger = state
-        .ledger
-        .lock()
-        .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
-
-    let block = ledger
-        .get_block(index as i32)
-        .map_err(|e| ApiError::NotFound(format!("Failed to get block: {}", e)))?
-        .ok_or_else(|| ApiError::NotFound(format!("Block {} not found", index)))?;
-
-    Ok(Json(json!({
-        "index": block.index,
-        "hash": block.hash,
-        "previous_hash": block.previous_hash,
-        "timestamp": block.timestamp,
-        "tic_id": block.tic_id,
-        "snapshot_hash": block.snapshot_hash,
-    })))
+    // Blocks are read from disk
+    let block = state
+        .blocking(move |state| {
+            state
+                .ledger
+                .read()
+                .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?
+                .get_block(index as i32)
+                .map_err(|e| ApiError::NotFound(format!("Failed to get block: {}", e)))?
+                .ok_or_else(|| ApiError::NotFound(format!("Block {} not found", index)))
+        })
+        .await?;
+
+    let block = serde_json::to_value(&block)
+        .map_err(|e| ApiError::Ledger(format!("Failed to serialize block: {}", e)))?;
+
+    Ok(Json(block))
+}
+
+/// Get block summaries in index order, used by replicas to detect forks
+async fn get_summaries(
+    State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
+) -> Result<Json<Page<BlockSummary>>> {
+    let request = PageRequest::<i32>::parse(&page)?;
+    state
+        .blocking(move |state| {
+            let ledger = state
+                .ledger
+                .read()
+                .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
+
+            // Summaries are stored by index, so the page starts right at the cursor
+            let blocks = &ledger.index().blocks;
+            let start = request
+                .after
+                .map_or(0, |after| (after.max(-1) + 1) as usize)
+                .min(blocks.len());
+            let page = request.take_sorted(
+                blocks[start..].iter().map(|s| (s.index, s.clone())),
+                blocks.len(),
+            );
+
+            Ok(page)
+        })
+        .await
+        .map(Json)
+}
+
+/// Get the signer key registry
+async fn get_key_registry(State(state): State<AppState>) -> Result<Json<KeyRegistry>> {
+    state
+        .blocking(|state| {
+            let ledger = state
+                .ledger
+                .read()
+                .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
+
+            Ok(ledger.key_registry().clone())
+        })
+        .await
+        .map(Json)
 }
 
 /// Audit the entire ledger
 async fn audit(State(state): State<AppState>) -> Result<Json<AuditResponse>> {
-    let ledger = state
-        .ledger
-        .lock()
-        .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
+    // Verifying the chain rehashes every block
+    let (valid, stats, chain_hash) = state
+        .blocking(|state| {
+            let ledger = state
+                .ledger
+                .read()
+                .map_err(|e| ApiError::Ledger(format!("Failed to lock ledger: {}", e)))?;
 
-    // Validate the chain from start
-    let valid = ledger
-        .verify_chain_integrity(0)
-        .map_err(|e| ApiError::Ledger(format!("Chain validation failed: {}", e)))?;
+            // Validate the chain from start
+            let valid = ledger
+                .verify_chain_integrity(0)
+                .map_err(|e| ApiError::Ledger(format!("Chain validation failed: {}", e)))?;
 
-    // Get chain statistics
-    let stats = ledger
-        .get_chain_statistics()
-        .map_err(|e| ApiError::Ledger(format!("Failed to get statistics: {}", e)))?;
+            // Get chain statistics
+            let stats = ledger
+                .get_chain_statistics()
+                .map_err(|e| ApiError::Ledger(format!("Failed to get statistics: {}", e)))?;
 
+            let chain_hash = ledger
+                .get_last_hash()
+                .unwrap_or_else(|_| "empty".to_string());
+            Ok((valid, stats, chain_hash))
+        })
+        .await?;
     let blocks = stats.total_blocks as usize;
-    let chain_hash = ledger
-        .get_last_hash()
-        .unwrap_or_else(|_| "empty".to_string());
 
     let mut statistics = HashMap::new();
     statistics.insert("blocks".to_string(), json!(blocks));
@@ -115,16 +230,16 @@ async fn audit(State(state): State<AppState>) -> Result<Json<AuditResponse>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ApiConfig;
+    use crate::state::test_state;
 
     #[tokio::test]
     async fn test_append_ledger() {
-        let config = ApiConfig::default();
-        let state = AppState::new(config).await.unwrap();
+        let (_dir, state) = test_state().await;
 
         let request = LedgerAppendRequest {
             tic_id: "tic_123".to_string(),
             snapshot_id: "snapshot_456".to_string(),
+            expected_previous_hash: None,
         };
 
         let result = append_ledger(State(state), Json(request)).await;
@@ -134,12 +249,217 @@ mod tests {
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_summaries_match_blocks() {
+        let (_dir, state) = test_state().await;
+
+        let request = LedgerAppendRequest {
+            tic_id: "tic_sync".to_string(),
+            snapshot_id: "snapshot_sync".to_string(),
+            expected_previous_hash: None,
+        };
+        let appended = append_ledger(State(state.clone()), Json(request))
+            .await
+            .unwrap();
+
+        let summaries = get_summaries(State(state.clone()), Query(PageQuery::default()))
+            .await
+            .unwrap();
+        let summary = &summaries.0.items[appended.0.block_index];
+        assert_eq!(summary.hash, appended.0.block_hash);
+
+        let block = get_block(State(state), Path(appended.0.block_index))
+            .await
+            .unwrap();
+        assert_eq!(block.0["hash"], json!(summary.hash));
+        assert!(block.0["data"].is_object());
+    }
+
+    #[tokio::test]
+    async fn test_listings_are_paged() {
+        let (_dir, state) = test_state().await;
+        for i in 0..5 {
+            let request = LedgerAppendRequest {
+                tic_id: format!("tic_page_{}", i),
+                snapshot_id: "snapshot_page".to_string(),
+                expected_previous_hash: None,
+            };
+            let _ = append_ledger(State(state.clone()), Json(request))
+                .await
+                .unwrap();
+        }
+
+        // Without a limit the default page size applies
+        let Json(all) = query_ledger(
+            State(state.clone()),
+            Query(PageQuery::default()),
+            Query(LedgerQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(all.items.len(), 5);
+        assert!(all.next_cursor.is_none());
+
+        let mut indices = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = PageQuery {
+                limit: Some(2),
+                cursor,
+            };
+            let Json(page) = get_summaries(State(state.clone()), Query(page))
+                .await
+                .unwrap();
+            assert_eq!(page.total, 5);
+            indices.extend(page.items.iter().map(|s| s.index));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_eq!(indices, [0, 1, 2, 3, 4]);
+
+        let page = PageQuery {
+            limit: Some(3),
+            cursor: None,
+        };
+        let Json(first) = query_ledger(
+            State(state.clone()),
+            Query(page),
+            Query(LedgerQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(first.items.len(), 3);
+        let page = PageQuery {
+            limit: Some(3),
+            cursor: first.next_cursor,
+        };
+        let Json(rest) = query_ledger(State(state), Query(page), Query(LedgerQuery::default()))
+            .await
+            .unwrap();
+        let indices: Vec<_> = rest.items.iter().map(|s| s.index).collect();
+        assert_eq!(indices, [3, 4]);
+    }
+
+    #[tokio::test]
+    async fn test_query_ledger() {
+        let (_dir, state) = test_state().await;
+
+        let tic_id = format!("tic_query_{}", uuid::Uuid::new_v4());
+        let request = LedgerAppendRequest {
+            tic_id: tic_id.clone(),
+            snapshot_id: "snapshot_query".to_string(),
+            expected_previous_hash: None,
+        };
+        let appended = append_ledger(State(state.clone()), Json(request))
+            .await
+            .unwrap();
+
+        let query = LedgerQuery {
+            tic_id: Some(tic_id),
+            from: Some("2000-01-01".to_string()),
+            ..LedgerQuery::default()
+        };
+        let result = query_ledger(
+            State(state.clone()),
+            Query(PageQuery::default()),
+            Query(query),
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.0.total, 1);
+        assert_eq!(result.0.items[0].hash, appended.0.block_hash);
+
+        let bad = LedgerQuery {
+            from: Some("yesterday".to_string()),
+            ..LedgerQuery::default()
+        };
+        let result = query_ledger(State(state), Query(PageQuery::default()), Query(bad)).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_append_with_stale_head_conflicts() {
+        let (_dir, state) = test_state().await;
+
+        let request = LedgerAppendRequest {
+            tic_id: "tic_cas".to_string(),
+            snapshot_id: "snapshot_cas".to_string(),
+            expected_previous_hash: Some("f".repeat(64)),
+        };
+        let result = append_ledger(State(state.clone()), Json(request)).await;
+        assert!(matches!(&result, Err(ApiError::Conflict(message)) if message.contains("retry")));
+
+        // A block appended by another process is picked up before comparing
+        let mut other = mef_ledger::MEFLedger::new(&state.config.ledger_path).unwrap();
+        let head = other
+            .append_block(
+                &json!({
+                    "tic_id": "external",
+                    "seed": "s",
+                    "fixpoint": [1.0],
+                    "window": [],
+                    "invariants": {},
+                    "sigma_bar": {},
+                    "proof": null,
+                }),
+                &json!({"id": "external"}),
+            )
+            .unwrap();
+        let request = LedgerAppendRequest {
+            tic_id: "tic_cas".to_string(),
+            snapshot_id: "snapshot_cas".to_string(),
+            expected_previous_hash: Some(head.hash),
+        };
+        let Json(response) = append_ledger(State(state), Json(request)).await.unwrap();
+        assert_eq!(response.block_index, head.index as usize + 1);
+    }
+
     #[tokio::test]
     async fn test_audit() {
-        let config = ApiConfig::default();
-        let state = AppState::new(config).await.unwrap();
+        let (_dir, state) = test_state().await;
 
         let result = audit(State(state)).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_retried_append_with_idempotency_key_adds_one_block() {
+        use axum::{body::Body, http::Request};
+        use tower::ServiceExt;
+
+        let (_dir, state) = test_state().await;
+        let app =
+            crate::idempotency::enable(router(), &state.idempotency).with_state(state.clone());
+
+        let append = |body: serde_json::Value| {
+            Request::post("/ledger")
+                .header("content-type", "application/json")
+                .header("idempotency-key", "append-1")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let body = json!({"tic_id": "tic_retry", "snapshot_id": "snapshot_retry"});
+
+        let first = app.clone().oneshot(append(body.clone())).await.unwrap();
+        let first = axum::body::to_bytes(first.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let retry = app.clone().oneshot(append(body)).await.unwrap();
+        assert_eq!(retry.headers()["idempotent-replayed"], "true");
+        let retry = axum::body::to_bytes(retry.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(retry, first);
+        assert_eq!(state.ledger.read().unwrap().index().blocks.len(), 1);
+
+        let changed = json!({"tic_id": "tic_other", "snapshot_id": "snapshot_retry"});
+        let response = app.oneshot(append(changed)).await.unwrap();
+        assert_eq!(
+            response.status(),
+            axum::http::StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(state.ledger.read().unwrap().index().blocks.len(), 1);
+    }
 }
@@ -6,8 +6,14 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
+use std::cmp::Reverse;
 
-use crate::{AppState, Result};
+use crate::{
+    error::ApiError,
+    events::EventKind,
+    pagination::{Page, PageQuery, PageRequest},
+    AppState, Result,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -19,47 +25,47 @@ pub fn router() -> Router<AppState> {
 
 /// Evaluate TIC candidate through Merkaba Gate
 #[derive(Debug, Deserialize)]
-struct MerkabaGateRequest {
-    snapshot_id: String,
-    tic_candidate_id: String,
+pub(crate) struct MerkabaGateRequest {
+    pub(crate) snapshot_id: String,
+    pub(crate) tic_candidate_id: String,
     #[serde(default)]
-    params: Option<GateParams>,
+    pub(crate) params: Option<GateParams>,
 }
 
 #[derive(Debug, Deserialize)]
-struct GateParams {
-    epsilon: Option<f64>,
-    phi_star: Option<f64>,
-    eta: Option<f64>,
+pub(crate) struct GateParams {
+    pub(crate) epsilon: Option<f64>,
+    pub(crate) phi_star: Option<f64>,
+    pub(crate) eta: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
-struct MerkabaGateResponse {
-    gate_id: String,
-    snapshot_id: String,
-    tic_candidate_id: String,
-    checks: GateChecks,
-    decision: GateDecision,
-    timestamp: String,
-    ledger_block_id: Option<String>,
+pub(crate) struct MerkabaGateResponse {
+    pub(crate) gate_id: String,
+    pub(crate) snapshot_id: String,
+    pub(crate) tic_candidate_id: String,
+    pub(crate) checks: GateChecks,
+    pub(crate) decision: GateDecision,
+    pub(crate) timestamp: String,
+    pub(crate) ledger_block_id: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
-struct GateChecks {
-    por: String,
-    delta_pi: f64,
-    phi: f64,
-    delta_v: f64,
-    mci: Option<f64>,
+pub(crate) struct GateChecks {
+    pub(crate) por: String,
+    pub(crate) delta_pi: f64,
+    pub(crate) phi: f64,
+    pub(crate) delta_v: f64,
+    pub(crate) mci: Option<f64>,
 }
 
 #[derive(Debug, Serialize)]
-struct GateDecision {
-    commit: bool,
-    reason: String,
+pub(crate) struct GateDecision {
+    pub(crate) commit: bool,
+    pub(crate) reason: String,
 }
 
-async fn evaluate_merkaba_gate(
+pub(crate) async fn evaluate_merkaba_gate(
     State(state): State<AppState>,
     Json(request): Json<MerkabaGateRequest>,
 ) -> Result<Json<MerkabaGateResponse>> {
@@ -86,14 +92,25 @@ async fn evaluate_merkaba_gate(
         ]),
     };
 
-    // Run Merkaba Gate evaluation
-    let mut gate = state.merkaba_gate.lock().unwrap();
-    let gate_event = gate.run_merkaba(
-        request.snapshot_id.clone(),
-        tic_candidate,
-        epsilon,
-        phi_star,
-        eta,
+    // Run Merkaba Gate evaluation; it appends to the audit log
+    let snapshot_id = request.snapshot_id.clone();
+    let gate_event = state
+        .blocking(move |state| {
+            let mut gate = state.merkaba_gate.write().unwrap();
+            Ok(gate.run_merkaba(snapshot_id, tic_candidate, epsilon, phi_star, eta))
+        })
+        .await?;
+
+    state.events.publish(
+        EventKind::GateDecision,
+        serde_json::json!({
+            "gate_id": gate_event.gate_id,
+            "snapshot_id": gate_event.snapshot_id,
+            "tic_candidate_id": gate_event.tic_candidate_id,
+            "decision": if gate_event.decision.commit { "FIRE" } else { "HOLD" },
+            "reason": gate_event.decision.reason,
+            "checks": gate_event.checks,
+        }),
     );
 
     // Determine ledger block ID if committed
@@ -142,7 +159,7 @@ struct ThresholdConfig {
 
 async fn get_merkaba_status(State(state): State<AppState>) -> Result<Json<MerkabaStatusResponse>> {
     // Get actual gate configuration
-    let gate = state.merkaba_gate.lock().unwrap();
+    let gate = state.merkaba_gate.read().unwrap();
 
     Ok(Json(MerkabaStatusResponse {
         status: "operational".to_string(),
@@ -158,16 +175,35 @@ async fn get_merkaba_status(State(state): State<AppState>) -> Result<Json<Merkab
 }
 
 /// Get Merkaba Gate audit log
-#[derive(Debug, Deserialize)]
+///
+/// Entries are listed newest first, one page at a time; `decision=FIRE`
+/// (or `commit`) and `decision=HOLD` (or `reject`) filter by the decision.
+#[derive(Debug, Default, Deserialize)]
 struct AuditQuery {
-    #[serde(default = "default_audit_limit")]
-    limit: usize,
+    #[serde(default)]
+    decision: Option<String>,
 }
 
-fn default_audit_limit() -> usize {
-    100
+impl AuditQuery {
+    /// Requested commit flag, if filtering by decision
+    fn commit(&self) -> Result<Option<bool>> {
+        self.decision
+            .as_deref()
+            .map(|decision| match decision.to_ascii_uppercase().as_str() {
+                "FIRE" | "COMMIT" => Ok(true),
+                "HOLD" | "REJECT" => Ok(false),
+                _ => Err(ApiError::InvalidInput(format!(
+                    "Unknown decision: {} (expected FIRE or HOLD)",
+                    decision
+                ))),
+            })
+            .transpose()
+    }
 }
 
+/// Raw gate event as written to the audit log
+type AuditEvent = serde_json::Map<String, JsonValue>;
+
 #[derive(Debug, Serialize)]
 struct AuditEntry {
     gate_id: String,
@@ -178,74 +214,67 @@ struct AuditEntry {
     checks: JsonValue,
 }
 
-#[derive(Debug, Serialize)]
-struct AuditLogResponse {
-    entries: Vec<AuditEntry>,
-    total: usize,
+impl AuditEntry {
+    fn from_event(event: &AuditEvent) -> Self {
+        let text = |key: &str| {
+            event
+                .get(key)
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string()
+        };
+        AuditEntry {
+            gate_id: text("gate_id"),
+            snapshot_id: text("snapshot_id"),
+            tic_candidate_id: text("tic_candidate_id"),
+            decision: match committed(event) {
+                Some(true) => "commit".to_string(),
+                Some(false) => "reject".to_string(),
+                None => "unknown".to_string(),
+            },
+            timestamp: text("timestamp"),
+            checks: event
+                .get("checks")
+                .cloned()
+                .unwrap_or(serde_json::json!({})),
+        }
+    }
+}
+
+fn committed(event: &AuditEvent) -> Option<bool> {
+    event
+        .get("decision")
+        .and_then(|d| d.get("commit"))
+        .and_then(|c| c.as_bool())
 }
 
+/// Example: `GET /gate/merkaba/audit?decision=HOLD&limit=20`
 async fn get_audit_log(
     State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
     Query(query): Query<AuditQuery>,
-) -> Result<Json<AuditLogResponse>> {
-    // Read audit log from file
-    let gate = state.merkaba_gate.lock().unwrap();
-    let audit_path = &gate.audit_path;
-
-    let mut entries = Vec::new();
-
-    // Try to read audit log if it exists
-    if audit_path.exists() {
-        if let Ok(content) = std::fs::read_to_string(audit_path) {
-            // Parse JSONL format (one JSON object per line)
-            for line in content.lines().rev().take(query.limit) {
-                if let Ok(event) = serde_json::from_str::<serde_json::Value>(line) {
-                    // Convert to AuditEntry format
-                    if let Some(obj) = event.as_object() {
-                        entries.push(AuditEntry {
-                            gate_id: obj
-                                .get("gate_id")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("")
-                                .to_string(),
-                            snapshot_id: obj
-                                .get("snapshot_id")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("")
-                                .to_string(),
-                            tic_candidate_id: obj
-                                .get("tic_candidate_id")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("")
-                                .to_string(),
-                            decision: obj
-                                .get("decision")
-                                .and_then(|d| d.get("commit"))
-                                .and_then(|c| c.as_bool())
-                                .map(|b| {
-                                    if b {
-                                        "commit".to_string()
-                                    } else {
-                                        "reject".to_string()
-                                    }
-                                })
-                                .unwrap_or("unknown".to_string()),
-                            timestamp: obj
-                                .get("timestamp")
-                                .and_then(|v| v.as_str())
-                                .unwrap_or("")
-                                .to_string(),
-                            checks: obj.get("checks").cloned().unwrap_or(serde_json::json!({})),
-                        });
-                    }
-                }
-            }
-        }
-    }
-
-    let total = entries.len();
-
-    Ok(Json(AuditLogResponse { entries, total }))
+) -> Result<Json<Page<AuditEntry>>> {
+    // Entries are keyed by line number, newest first, so appends between
+    // requests do not shift the pages
+    let request = PageRequest::<Reverse<usize>>::parse(&page)?;
+    let commit = query.commit()?;
+
+    // Read audit log from file; the gate is not held while reading
+    let audit_path = state.merkaba_gate.read().unwrap().audit_path.clone();
+    let content = std::fs::read_to_string(&audit_path).unwrap_or_default();
+
+    // Parse JSONL format (one JSON object per line); lines are kept as text
+    // and only those of the selected page are converted to entries
+    let page = request.select(content.lines().enumerate().filter_map(|(line, text)| {
+        let event = serde_json::from_str::<AuditEvent>(text).ok()?;
+        commit
+            .is_none_or(|commit| committed(&event) == Some(commit))
+            .then_some((Reverse(line), text))
+    }));
+
+    Ok(Json(page.map(|text| {
+        AuditEntry::from_event(&serde_json::from_str(text).unwrap_or_default())
+    })))
 }
 
 /// Calibrate Merkaba Gate thresholds
@@ -271,7 +300,7 @@ async fn calibrate_thresholds(
     Json(request): Json<CalibrateRequest>,
 ) -> Result<Json<CalibrateResponse>> {
     // Update gate thresholds
-    let mut gate = state.merkaba_gate.lock().unwrap();
+    let mut gate = state.merkaba_gate.write().unwrap();
 
     let mut updated = serde_json::Map::new();
 
@@ -341,10 +370,56 @@ mod tests {
 
     #[tokio::test]
     async fn test_get_audit_log() {
-        let state = test_state().await;
-        let query = AuditQuery { limit: 50 };
-        let result = get_audit_log(State(state), Query(query)).await;
-        assert!(result.is_ok());
+        let (_dir, state) = crate::state::test_state().await;
+        let lines: Vec<String> = (0..5)
+            .map(|i| {
+                serde_json::json!({
+                    "gate_id": format!("g{}", i),
+                    "decision": {"commit": i % 2 == 0, "reason": ""},
+                })
+                .to_string()
+            })
+            .collect();
+        let audit_path = state.merkaba_gate.read().unwrap().audit_path.clone();
+        std::fs::write(&audit_path, lines.join("\n")).unwrap();
+
+        // Newest first, two at a time
+        let page = PageQuery {
+            limit: Some(2),
+            cursor: None,
+        };
+        let Json(first) = get_audit_log(
+            State(state.clone()),
+            Query(page),
+            Query(AuditQuery::default()),
+        )
+        .await
+        .unwrap();
+        let ids: Vec<_> = first.items.iter().map(|e| e.gate_id.as_str()).collect();
+        assert_eq!(ids, ["g4", "g3"]);
+        assert_eq!(first.total, 5);
+
+        let page = PageQuery {
+            limit: Some(2),
+            cursor: first.next_cursor,
+        };
+        let hold = AuditQuery {
+            decision: Some("HOLD".to_string()),
+        };
+        let Json(second) = get_audit_log(State(state.clone()), Query(page), Query(hold))
+            .await
+            .unwrap();
+        let ids: Vec<_> = second.items.iter().map(|e| e.gate_id.as_str()).collect();
+        assert_eq!(ids, ["g1"]);
+        assert_eq!(second.items[0].decision, "reject");
+        assert_eq!(second.total, 2);
+        assert!(second.next_cursor.is_none());
+
+        let unknown = AuditQuery {
+            decision: Some("MAYBE".to_string()),
+        };
+        let result = get_audit_log(State(state), Query(PageQuery::default()), Query(unknown)).await;
+        assert!(matches!(result, Err(ApiError::InvalidInput(_))));
     }
 
     #[tokio::test]
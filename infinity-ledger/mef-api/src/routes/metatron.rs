@@ -7,7 +7,12 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
-use crate::{error::ApiError, AppState, Result};
+use crate::{
+    error::ApiError,
+    events::EventKind,
+    pagination::{Page, PageQuery, PageRequest},
+    AppState, Result,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -123,22 +128,25 @@ async fn process_through_pipeline(
         }
     });
 
-    let route_spec = {
-        let mut metatron = state.metatron_router.lock().unwrap();
-        metatron.select_optimal_route(&padded, target_props.as_ref())
-    };
-
     // Step 4: Apply transformation through Metatron route
-    let transformed = {
-        let mut metatron = state.metatron_router.lock().unwrap();
-        metatron.transform(&padded, Some(&route_spec))
+    // Route search scores symmetry permutations, so run off the async workers
+    let (route_spec, transformed) = {
+        let padded = padded.clone();
+        state
+            .blocking(move |state| {
+                let mut metatron = state.metatron_router.write().unwrap();
+                let route_spec = metatron.select_optimal_route(&padded, target_props.as_ref());
+                let transformed = metatron.transform(&padded, Some(&route_spec));
+                Ok((route_spec, transformed))
+            })
+            .await?
     };
 
     // Step 5: Generate TIC from fixpoint
     let fixpoint_array = Array1::from_vec(transformed.output_vector.clone());
 
     let tic_config = TICConfig::default();
-    let tic_crystallizer = TICCrystallizer::new(tic_config, state.store_path.join("tics"))
+    let tic_crystallizer = TICCrystallizer::new(tic_config, state.store_path.as_ref())
         .map_err(|e| ApiError::Internal(format!("Failed to create TIC crystallizer: {}", e)))?;
 
     let convergence_info = serde_json::json!({
@@ -168,6 +176,18 @@ async fn process_through_pipeline(
             &snapshot_data,
         )
         .map_err(|e| ApiError::Internal(format!("Failed to create TIC: {}", e)))?;
+    tic_crystallizer
+        .save_tic(&tic)
+        .map_err(|e| ApiError::Internal(format!("Failed to save TIC: {}", e)))?;
+
+    state.events.publish(
+        EventKind::TicCrystallized,
+        serde_json::json!({
+            "tic_id": tic.tic_id,
+            "snapshot_id": snapshot_id,
+            "metatron_route": route_spec.route_id,
+        }),
+    );
 
     // Step 6: Prepare proof data
     let proof = serde_json::json!({
@@ -227,7 +247,7 @@ struct PipelineMetrics {
 async fn get_pipeline_metrics(State(state): State<AppState>) -> Result<Json<PipelineMetrics>> {
     // Get Metatron router metrics
     let (cache_enabled, _cache_size, _cache_max_size, cache_hit_rate) = {
-        let metatron = state.metatron_router.lock().unwrap();
+        let metatron = state.metatron_router.read().unwrap();
         let topology_metrics = metatron.get_topology_metrics();
 
         let cache_enabled = topology_metrics
@@ -255,7 +275,7 @@ async fn get_pipeline_metrics(State(state): State<AppState>) -> Result<Json<Pipe
 
     // Get domain layer metrics
     let (total_processed, resonats_count, _meshes_count) = {
-        let domain_layer = state.domain_layer.lock().unwrap();
+        let domain_layer = state.domain_layer.read().unwrap();
         let metrics = domain_layer.metrics.lock().unwrap();
 
         (
@@ -308,9 +328,6 @@ async fn select_optimal_route(
     State(state): State<AppState>,
     Json(request): Json<RouteSelectionRequest>,
 ) -> Result<Json<RouteSelectionResponse>> {
-    // Use MetatronRouter to select optimal route
-    let mut router = state.metatron_router.lock().unwrap();
-
     // Convert target properties to HashMap if present
     let target_props = request
         .target_properties
@@ -322,7 +339,13 @@ async fn select_optimal_route(
                 .collect::<std::collections::HashMap<String, String>>()
         });
 
-    let route_spec = router.select_optimal_route(&request.input_vector, target_props.as_ref());
+    // Use MetatronRouter to select optimal route
+    let route_spec = state
+        .blocking(move |state| {
+            let mut router = state.metatron_router.write().unwrap();
+            Ok(router.select_optimal_route(&request.input_vector, target_props.as_ref()))
+        })
+        .await?;
 
     // Convert operator types to strings
     let operator_sequence: Vec<String> = route_spec
@@ -382,40 +405,44 @@ async fn apply_transformation(
     State(state): State<AppState>,
     Json(request): Json<TransformRequest>,
 ) -> Result<Json<TransformResponse>> {
-    // Get MetatronRouter
-    let mut router = state.metatron_router.lock().unwrap();
-
-    // Look up route spec if route_id provided
-    let route_spec = if let Some(route_id) = &request.route_id {
-        router.route_cache.get(route_id).cloned()
-    } else if let Some(op_seq) = &request.operator_sequence {
-        // Create custom route spec from operator sequence
-        use mef_topology::OperatorType;
-        let operators: Vec<OperatorType> = op_seq
-            .iter()
-            .filter_map(|s| match s.as_str() {
-                "DK" => Some(OperatorType::DK),
-                "SW" => Some(OperatorType::SW),
-                "PI" => Some(OperatorType::PI),
-                "WT" => Some(OperatorType::WT),
-                _ => None,
-            })
-            .collect();
-
-        Some(mef_topology::RouteSpec {
-            route_id: uuid::Uuid::new_v4().to_string(),
-            permutation: (1..=13).collect(),
-            operator_sequence: operators,
-            symmetry_group: "Identity".to_string(),
-            score: 0.0,
-            metadata: std::collections::HashMap::new(),
-        })
-    } else {
-        None
-    };
+    let result = state
+        .blocking(move |state| {
+            // Get MetatronRouter
+            let mut router = state.metatron_router.write().unwrap();
+
+            // Look up route spec if route_id provided
+            let route_spec = if let Some(route_id) = &request.route_id {
+                router.route_cache.get(route_id).cloned()
+            } else if let Some(op_seq) = &request.operator_sequence {
+                // Create custom route spec from operator sequence
+                use mef_topology::OperatorType;
+                let operators: Vec<OperatorType> = op_seq
+                    .iter()
+                    .filter_map(|s| match s.as_str() {
+                        "DK" => Some(OperatorType::DK),
+                        "SW" => Some(OperatorType::SW),
+                        "PI" => Some(OperatorType::PI),
+                        "WT" => Some(OperatorType::WT),
+                        _ => None,
+                    })
+                    .collect();
+
+                Some(mef_topology::RouteSpec {
+                    route_id: uuid::Uuid::new_v4().to_string(),
+                    permutation: (1..=13).collect(),
+                    operator_sequence: operators,
+                    symmetry_group: "Identity".to_string(),
+                    score: 0.0,
+                    metadata: std::collections::HashMap::new(),
+                })
+            } else {
+                None
+            };
 
-    // Apply transformation
-    let result = router.transform(&request.input_vector, route_spec.as_ref());
+            // Apply transformation
+            Ok(router.transform(&request.input_vector, route_spec.as_ref()))
+        })
+        .await?;
 
     Ok(Json(TransformResponse {
         input: result.input_vector,
@@ -453,7 +480,10 @@ async fn apply_transformation(
 }
 
 /// Get Metatron topology nodes information
-#[derive(Debug, Deserialize)]
+///
+/// Nodes are listed in id order, one page at a time; `node_id` selects a
+/// single node.
+#[derive(Debug, Default, Deserialize)]
 struct TopologyNodesQuery {
     #[serde(default)]
     node_id: Option<usize>,
@@ -467,54 +497,47 @@ struct TopologyNode {
     connections: Vec<usize>,
 }
 
-#[derive(Debug, Serialize)]
-struct TopologyNodesResponse {
-    nodes: Vec<TopologyNode>,
-    total_nodes: usize,
-}
-
 async fn get_topology_nodes(
     State(_state): State<AppState>,
+    Query(page): Query<PageQuery>,
     Query(query): Query<TopologyNodesQuery>,
-) -> Result<Json<TopologyNodesResponse>> {
+) -> Result<Json<Page<TopologyNode>>> {
+    let request = PageRequest::<usize>::parse(&page)?;
+
     // Get canonical nodes from Metatron Cube
     use mef_core::canonical_nodes;
     let nodes = canonical_nodes();
 
-    let result_nodes: Vec<TopologyNode> = if let Some(node_id) = query.node_id {
-        // Return specific node
-        if node_id > 0 && node_id <= nodes.len() {
-            let node = &nodes[node_id - 1];
-            vec![TopologyNode {
-                id: node_id,
-                name: node.label.clone(),
-                position: vec![node.coords.0, node.coords.1, node.coords.2],
-                connections: vec![], // Would need adjacency info from graph
-            }]
-        } else {
+    if let Some(node_id) = query.node_id {
+        if !nodes.iter().any(|node| node.index == node_id) {
             return Err(ApiError::NotFound(format!("Node {} not found", node_id)));
         }
-    } else {
-        // Return all nodes
+    }
+
+    let page = request.select(
         nodes
             .iter()
-            .map(|node| TopologyNode {
-                id: node.index,
-                name: node.label.clone(),
-                position: vec![node.coords.0, node.coords.1, node.coords.2],
-                connections: vec![], // Would need to compute from adjacency matrix
-            })
-            .collect()
-    };
-
-    Ok(Json(TopologyNodesResponse {
-        total_nodes: result_nodes.len(),
-        nodes: result_nodes,
-    }))
+            .filter(|node| query.node_id.is_none_or(|id| node.index == id))
+            .map(|node| {
+                (
+                    node.index,
+                    TopologyNode {
+                        id: node.index,
+                        name: node.label.clone(),
+                        position: vec![node.coords.0, node.coords.1, node.coords.2],
+                        connections: vec![], // Would need to compute from adjacency matrix
+                    },
+                )
+            }),
+    );
+
+    Ok(Json(page))
 }
 
 /// Get Metatron topology edges information
-#[derive(Debug, Deserialize)]
+///
+/// Edges are listed by source, then target node, one page at a time.
+#[derive(Debug, Default, Deserialize)]
 struct TopologyEdgesQuery {
     #[serde(default)]
     edge_type: Option<String>,
@@ -528,18 +551,15 @@ struct TopologyEdge {
     weight: f64,
 }
 
-#[derive(Debug, Serialize)]
-struct TopologyEdgesResponse {
-    edges: Vec<TopologyEdge>,
-    total_edges: usize,
-}
-
 async fn get_topology_edges(
     State(state): State<AppState>,
+    Query(page): Query<PageQuery>,
     Query(query): Query<TopologyEdgesQuery>,
-) -> Result<Json<TopologyEdgesResponse>> {
+) -> Result<Json<Page<TopologyEdge>>> {
+    let request = PageRequest::<(usize, usize)>::parse(&page)?;
+
     // Get edges from Metatron graph
-    let router = state.metatron_router.lock().unwrap();
+    let router = state.metatron_router.read().unwrap();
     let adjacency = router.graph.get_adjacency_matrix();
 
     let mut edges = Vec::new();
@@ -561,20 +581,20 @@ async fn get_topology_edges(
                     continue;
                 };
 
-                edges.push(TopologyEdge {
-                    source: i + 1,
-                    target: j + 1,
-                    edge_type: edge_type.to_string(),
-                    weight,
-                });
+                edges.push((
+                    (i + 1, j + 1),
+                    TopologyEdge {
+                        source: i + 1,
+                        target: j + 1,
+                        edge_type: edge_type.to_string(),
+                        weight,
+                    },
+                ));
             }
         }
     }
 
-    Ok(Json(TopologyEdgesResponse {
-        total_edges: edges.len(),
-        edges,
-    }))
+    Ok(Json(request.select(edges)))
 }
 
 /// Get symmetry group information
@@ -591,7 +611,7 @@ async fn get_symmetry_group(
     Path(group): Path<String>,
 ) -> Result<Json<SymmetryGroupResponse>> {
     // Get symmetry permutations from MetatronRouter
-    let router = state.metatron_router.lock().unwrap();
+    let router = state.metatron_router.read().unwrap();
 
     let (permutations, order, description) = match group.as_str() {
         "C6" => (router.c6_perms.clone(), 6, "Cyclic group of order 6"),
@@ -756,7 +776,7 @@ struct CacheStatusResponse {
 
 async fn get_cache_status(State(state): State<AppState>) -> Result<Json<CacheStatusResponse>> {
     // Get cache status from MetatronRouter
-    let router = state.metatron_router.lock().unwrap();
+    let router = state.metatron_router.read().unwrap();
 
     Ok(Json(CacheStatusResponse {
         enabled: router.cache_enabled,
@@ -775,7 +795,7 @@ struct ClearCacheResponse {
 
 async fn clear_cache(State(state): State<AppState>) -> Result<Json<ClearCacheResponse>> {
     // Clear route cache in MetatronRouter
-    let mut router = state.metatron_router.lock().unwrap();
+    let mut router = state.metatron_router.write().unwrap();
     let cleared = router.route_cache.len();
     router.route_cache.clear();
 
@@ -802,7 +822,7 @@ async fn export_route(
     Path(route_id): Path<String>,
 ) -> Result<Json<RouteExportResponse>> {
     // Load route from cache
-    let router = state.metatron_router.lock().unwrap();
+    let router = state.metatron_router.read().unwrap();
 
     let route_spec = router
         .route_cache
@@ -913,4 +933,48 @@ mod tests {
         let result = get_integration_status(State(state)).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_topology_pages() {
+        let state = test_state().await;
+
+        let Json(all) = get_topology_edges(
+            State(state.clone()),
+            Query(PageQuery::default()),
+            Query(TopologyEdgesQuery::default()),
+        )
+        .await
+        .unwrap();
+        assert!(all.next_cursor.is_none());
+
+        // Walking small pages yields the same edges in the same order
+        let mut edges = Vec::new();
+        let mut cursor = None;
+        loop {
+            let page = PageQuery {
+                limit: Some(5),
+                cursor,
+            };
+            let Json(page) = get_topology_edges(
+                State(state.clone()),
+                Query(page),
+                Query(TopologyEdgesQuery::default()),
+            )
+            .await
+            .unwrap();
+            assert_eq!(page.total, all.total);
+            edges.extend(page.items.into_iter().map(|e| (e.source, e.target)));
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        let expected: Vec<_> = all.items.iter().map(|e| (e.source, e.target)).collect();
+        assert_eq!(edges, expected);
+
+        let query = TopologyNodesQuery { node_id: Some(14) };
+        let missing =
+            get_topology_nodes(State(state), Query(PageQuery::default()), Query(query)).await;
+        assert!(matches!(missing, Err(ApiError::NotFound(_))));
+    }
 }
@@ -1,16 +1,62 @@
 pub mod commit;
 pub mod coupling;
 pub mod domain;
+pub mod events;
+pub mod export;
 pub mod extension;
 /// API routes
 pub mod health;
 pub mod index;
 pub mod ingest;
+pub mod jobs;
 pub mod ledger;
 pub mod merkaba;
 pub mod metatron;
 pub mod process;
 pub mod system;
+pub mod tenants;
 pub mod tic;
 pub mod vector;
 pub mod zk;
+
+use axum::Router;
+use tower_http::trace::TraceLayer;
+
+use crate::{auth, idempotency, AppState};
+
+/// REST routes of one namespace, served for the default namespace and for
+/// every tenant
+///
+/// Each route group is guarded unless configured as public, and tokens are
+/// checked before an idempotent response is replayed.
+pub fn app(state: AppState) -> Router {
+    let auth_config = state.config.clone();
+    let idempotency = state.idempotency.clone();
+    let group = |name: &str, router: Router<AppState>| {
+        auth::protect(
+            idempotency::enable(router, &idempotency),
+            &auth_config,
+            name,
+        )
+    };
+    Router::new()
+        .merge(group("health", health::router()))
+        .merge(group("ingest", ingest::router()))
+        .merge(group("process", process::router()))
+        .merge(group("ledger", ledger::router()))
+        .merge(group("vector", vector::router()))
+        .merge(group("coupling", coupling::router()))
+        .merge(group("tic", tic::router()))
+        .merge(group("index", index::router()))
+        .merge(group("system", system::router()))
+        .merge(group("commit", commit::router()))
+        .merge(group("zk", zk::router()))
+        .merge(group("domain", domain::router()))
+        .merge(group("metatron", metatron::router()))
+        .merge(group("merkaba", merkaba::router()))
+        .merge(group("export", export::router()))
+        .merge(group("events", events::router()))
+        .merge(group("jobs", jobs::router()))
+        .with_state(state)
+        .layer(TraceLayer::new_for_http())
+}
@@ -1,14 +1,22 @@
 /// Processing endpoints - Solve-Coagula and TIC creation
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
+    response::Response,
     routing::post,
     Json, Router,
 };
 use chrono::Utc;
 use ndarray::Array1;
-use serde_json::json;
-
-use crate::{error::ApiError, models::*, AppState, Result};
+use serde_json::{json, Value as JsonValue};
+
+use super::jobs::{self, JobMode};
+use crate::{
+    error::ApiError,
+    events::EventKind,
+    jobs::{Job, JobKind},
+    models::*,
+    AppState, Result,
+};
 use mef_solvecoagula::{SolveCoagula, SolveCoagulaConfig};
 use mef_spiral::SpiralSnapshot;
 use mef_tic::{TICConfig, TICCrystallizer};
@@ -21,10 +29,18 @@ pub fn router() -> Router<AppState> {
 }
 
 /// Process a snapshot through Solve-Coagula to create a TIC
-async fn process(
+pub(crate) async fn process(
     State(state): State<AppState>,
     Json(request): Json<ProcessRequest>,
 ) -> Result<Json<ProcessResponse>> {
+    state
+        .blocking(move |state| run_process(state, &request))
+        .await
+        .map(Json)
+}
+
+/// Body of [`process`]: load the snapshot, solve it and save the TIC
+fn run_process(state: &AppState, request: &ProcessRequest) -> Result<ProcessResponse> {
     // Create spiral snapshot handler to load
     let spiral = SpiralSnapshot::new(
         state.spiral_config.as_ref().clone(),
@@ -48,6 +64,7 @@ async fn process(
     let (fixpoint, info) = solver
         .iterate_to_fixpoint(&coords_array, true)
         .map_err(|e| ApiError::Processing(format!("Solve-Coagula failed: {}", e)))?;
+    state.metrics.observe_fixpoint(info.iterations);
 
     // Create TIC from result
     let tic_config = TICConfig::default();
@@ -76,6 +93,18 @@ async fn process(
             &snapshot_json,
         )
         .map_err(|e| ApiError::Processing(format!("TIC creation failed: {}", e)))?;
+    crystallizer
+        .save_tic(&tic)
+        .map_err(|e| ApiError::Internal(format!("Failed to save TIC: {}", e)))?;
+
+    state.events.publish(
+        EventKind::TicCrystallized,
+        json!({
+            "tic_id": tic.tic_id,
+            "snapshot_id": snapshot.id,
+            "converged": info.converged,
+        }),
+    );
 
     let tic_id = tic.tic_id.clone();
 
@@ -85,20 +114,31 @@ async fn process(
         tracing::info!("Commit requested for TIC: {}", tic_id);
     }
 
-    Ok(Json(ProcessResponse {
+    Ok(ProcessResponse {
         tic_id,
         converged: info.converged,
         iterations: info.iterations,
         final_eigenvalue: fixpoint[0], // Use first component as representative
         timestamp: Utc::now().to_rfc3339(),
-    }))
+    })
 }
 
-/// Solve endpoint - alternative processing method
+/// Solve endpoint - alternative processing method, run as a background job
+///
+/// Example: `POST /solve?async=true`
 async fn solve(
     State(state): State<AppState>,
+    Query(mode): Query<JobMode>,
     Json(request): Json<SolveRequest>,
-) -> Result<Json<SolveResponse>> {
+) -> Result<Response> {
+    let params = serde_json::to_value(&request)?;
+    jobs::submit(&state, JobKind::Solve, &params, 1, mode).await
+}
+
+/// Job body of [`solve`]
+pub(crate) fn run_solve(state: &AppState, job: &Job) -> Result<JsonValue> {
+    let request: SolveRequest = serde_json::from_value(state.jobs.params(&job.id)?)?;
+
     // Create spiral snapshot handler to load
     let spiral = SpiralSnapshot::new(
         state.spiral_config.as_ref().clone(),
@@ -122,6 +162,7 @@ async fn solve(
     let (fixpoint, info) = solver
         .iterate_to_fixpoint(&coords_array, true)
         .map_err(|e| ApiError::Processing(format!("Solve-Coagula failed: {}", e)))?;
+    state.metrics.observe_fixpoint(info.iterations);
 
     // Create TIC
     let tic_config = TICConfig::default();
@@ -149,8 +190,20 @@ async fn solve(
             &snapshot_json,
         )
         .map_err(|e| ApiError::Processing(format!("TIC creation failed: {}", e)))?;
-
-    Ok(Json(SolveResponse {
+    crystallizer
+        .save_tic(&tic)
+        .map_err(|e| ApiError::Internal(format!("Failed to save TIC: {}", e)))?;
+
+    state.events.publish(
+        EventKind::TicCrystallized,
+        json!({
+            "tic_id": tic.tic_id,
+            "snapshot_id": snapshot.id,
+            "converged": info.converged,
+        }),
+    );
+
+    Ok(serde_json::to_value(SolveResponse {
         tic_id: tic.tic_id.clone(),
         status: if info.converged {
             "converged"
@@ -159,7 +212,7 @@ async fn solve(
         }
         .to_string(),
         steps: info.iterations,
-    }))
+    })?)
 }
 
 /// Validate a snapshot using Proof-of-Resonance
@@ -225,7 +278,7 @@ mod tests {
 
         let request = SolveRequest { snapshot_id };
 
-        let result = solve(State(state), Json(request)).await;
+        let result = solve(State(state), Query(JobMode::default()), Json(request)).await;
         assert!(result.is_ok());
     }
 }
@@ -1,10 +1,17 @@
 /// System metrics, gate FSM, and mode endpoints
-use axum::{extract::State, routing::get, Json, Router};
-use serde::Serialize;
-use serde_json::Value as JsonValue;
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+    routing::get,
+    Json, Router,
+};
+use mef_core::gates::{GateState, GateTransition};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
 use std::collections::HashMap;
 
-use crate::{error::ApiError, AppState, Result};
+use crate::{error::ApiError, metrics::OPENMETRICS_CONTENT_TYPE, AppState, Result};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -17,24 +24,56 @@ pub fn router() -> Router<AppState> {
 /// Gate FSM state
 #[derive(Debug, Serialize)]
 struct GateFsmResponse {
-    state: String,
+    state: GateState,
+    since: String,
+    cooldown_until: Option<String>,
+    /// Checks of the last decision; `t` is its time and `t'` the time of the current state
     reasons: HashMap<String, JsonValue>,
+    last_decision: Option<JsonValue>,
+    consecutive_holds: u64,
+    evaluations: u64,
+    history: Vec<GateTransition>,
 }
 
-async fn get_gate_fsm() -> Result<Json<GateFsmResponse>> {
-    // For now, return a placeholder FSM state
-    // In a real implementation, this would track actual gate decisions
-    let mut reasons = HashMap::new();
-    reasons.insert("phi".to_string(), JsonValue::Null);
-    reasons.insert("mci".to_string(), JsonValue::Null);
-    reasons.insert("por".to_string(), JsonValue::Null);
-    reasons.insert("deltaV".to_string(), JsonValue::Null);
-    reasons.insert("t".to_string(), JsonValue::Null);
-    reasons.insert("t'".to_string(), JsonValue::Null);
+#[derive(Debug, Deserialize)]
+struct GateFsmQuery {
+    /// Most recent transitions to return
+    #[serde(default = "default_history_limit")]
+    history: usize,
+}
+
+fn default_history_limit() -> usize {
+    50
+}
+
+/// Current gate FSM state with the checks behind the last decision
+///
+/// Example: `GET /gate/fsm?history=10`
+async fn get_gate_fsm(
+    State(state): State<AppState>,
+    Query(query): Query<GateFsmQuery>,
+) -> Result<Json<GateFsmResponse>> {
+    let snapshot = state.gate_fsm.snapshot();
+    let last = snapshot.last_decision.as_ref();
 
+    let mut reasons = HashMap::new();
+    reasons.insert("phi".to_string(), json!(last.map(|e| e.checks.phi)));
+    reasons.insert("mci".to_string(), json!(last.and_then(|e| e.checks.mci)));
+    reasons.insert("por".to_string(), json!(last.map(|e| &e.checks.por)));
+    reasons.insert("deltaV".to_string(), json!(last.map(|e| e.checks.delta_v)));
+    reasons.insert("t".to_string(), json!(last.map(|e| &e.timestamp)));
+    reasons.insert("t'".to_string(), json!(snapshot.since));
+
+    let skip = snapshot.history.len().saturating_sub(query.history);
     Ok(Json(GateFsmResponse {
-        state: "idle".to_string(),
+        state: snapshot.state,
+        since: snapshot.since.clone(),
+        cooldown_until: snapshot.cooldown_until.clone(),
         reasons,
+        last_decision: last.map(|e| json!(e)),
+        consecutive_holds: snapshot.consecutive_holds,
+        evaluations: snapshot.evaluations,
+        history: snapshot.history.into_iter().skip(skip).collect(),
     }))
 }
 
@@ -52,32 +91,12 @@ async fn get_mode() -> Result<Json<ModeResponse>> {
     }))
 }
 
-/// Prometheus metrics
-async fn get_metrics(State(_state): State<AppState>) -> Result<String> {
-    // Return Prometheus-formatted metrics
-    // In a real implementation, this would collect actual metrics
-    let metrics = r#"# HELP mef_api_requests_total Total number of API requests
-# TYPE mef_api_requests_total counter
-mef_api_requests_total{endpoint="/ping"} 0
-mef_api_requests_total{endpoint="/search"} 0
-mef_api_requests_total{endpoint="/ingest"} 0
-
-# HELP mef_api_request_duration_seconds Request duration in seconds
-# TYPE mef_api_request_duration_seconds histogram
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.005"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.01"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.025"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.05"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.1"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.25"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="0.5"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="1"} 0
-mef_api_request_duration_seconds_bucket{endpoint="/search",le="+Inf"} 0
-mef_api_request_duration_seconds_sum{endpoint="/search"} 0
-mef_api_request_duration_seconds_count{endpoint="/search"} 0
-"#;
-
-    Ok(metrics.to_string())
+/// Prometheus metrics in OpenMetrics text format
+async fn get_metrics(State(state): State<AppState>) -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, OPENMETRICS_CONTENT_TYPE)],
+        state.metrics.render(),
+    )
 }
 
 /// System statistics
@@ -94,7 +113,7 @@ async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsResponse>>
     // Get ledger stats
     let ledger = state
         .ledger
-        .lock()
+        .read()
         .map_err(|e| ApiError::Internal(format!("Failed to lock ledger: {}", e)))?;
 
     let chain_stats = ledger
@@ -104,7 +123,7 @@ async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsResponse>>
     // Get vector DB stats
     let index_manager = state
         .index_manager
-        .lock()
+        .read()
         .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
 
     let total_vectors: usize = index_manager
@@ -125,14 +144,56 @@ async fn get_stats(State(state): State<AppState>) -> Result<Json<StatsResponse>>
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::routes::merkaba::{evaluate_merkaba_gate, MerkabaGateRequest};
+    use crate::state::test_config;
     use crate::ApiConfig;
 
     #[tokio::test]
     async fn test_get_gate_fsm() {
-        let result = get_gate_fsm().await;
+        let config = ApiConfig::default();
+        let state = AppState::new(config).await.unwrap();
+
+        let query = GateFsmQuery { history: 10 };
+        let result = get_gate_fsm(State(state), Query(query)).await;
         assert!(result.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_gate_fsm_tracks_merkaba_decisions() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = ApiConfig {
+            gate_cooldown_secs: 3600,
+            ..test_config(dir.path())
+        };
+        let state = AppState::new(config.clone()).await.unwrap();
+
+        let request = MerkabaGateRequest {
+            snapshot_id: "snapshot".to_string(),
+            tic_candidate_id: "tic".to_string(),
+            params: None,
+        };
+        let Json(decision) = evaluate_merkaba_gate(State(state.clone()), Json(request))
+            .await
+            .unwrap();
+
+        let Json(fsm) = get_gate_fsm(State(state), Query(GateFsmQuery { history: 10 }))
+            .await
+            .unwrap();
+        assert_eq!(fsm.state, GateState::Cooldown);
+        assert_eq!(fsm.evaluations, 1);
+        assert_eq!(fsm.history.len(), 3);
+        assert_eq!(fsm.reasons["phi"], json!(decision.checks.phi));
+
+        // The state survives a restart
+        let restarted = AppState::new(config).await.unwrap();
+        let Json(fsm) = get_gate_fsm(State(restarted), Query(GateFsmQuery { history: 1 }))
+            .await
+            .unwrap();
+        assert_eq!(fsm.state, GateState::Cooldown);
+        assert_eq!(fsm.evaluations, 1);
+        assert_eq!(fsm.history.len(), 1);
+    }
+
     #[tokio::test]
     async fn test_get_mode() {
         let result = get_mode().await;
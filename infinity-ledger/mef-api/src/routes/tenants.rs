@@ -0,0 +1,78 @@
+/// Tenant administration - create, list and delete namespaces
+use axum::{
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use serde::Serialize;
+use std::sync::Arc;
+
+use crate::{
+    tenants::{NewTenant, TenantInfo, TenantRegistry, TenantSummary, TENANT_PREFIX},
+    Result,
+};
+
+pub fn router(registry: Arc<TenantRegistry>) -> Router {
+    Router::new()
+        .route("/admin/tenants", get(list_tenants).post(create_tenant))
+        .route(
+            "/admin/tenants/:name",
+            get(get_tenant).delete(delete_tenant),
+        )
+        .with_state(registry)
+}
+
+#[derive(Debug, Serialize)]
+struct ListTenantsResponse {
+    tenants: Vec<TenantSummary>,
+    total: usize,
+}
+
+/// List tenants with their usage
+async fn list_tenants(
+    State(registry): State<Arc<TenantRegistry>>,
+) -> Result<Json<ListTenantsResponse>> {
+    let tenants = registry.list().await?;
+    Ok(Json(ListTenantsResponse {
+        total: tenants.len(),
+        tenants,
+    }))
+}
+
+/// Create a tenant
+///
+/// Example: `POST /admin/tenants {"name": "acme", "quotas": {"max_vectors": 100000}}`;
+/// the tenant is then served below `/tenants/acme/`.
+async fn create_tenant(
+    State(registry): State<Arc<TenantRegistry>>,
+    Json(request): Json<NewTenant>,
+) -> Result<Response> {
+    let tenant = registry.create(request).await?;
+    Ok((
+        StatusCode::CREATED,
+        [(
+            header::LOCATION,
+            format!("{}{}", TENANT_PREFIX, tenant.info.name),
+        )],
+        Json(tenant),
+    )
+        .into_response())
+}
+
+/// Get a tenant with its usage
+async fn get_tenant(
+    State(registry): State<Arc<TenantRegistry>>,
+    Path(name): Path<String>,
+) -> Result<Json<TenantSummary>> {
+    registry.get(&name).await.map(Json)
+}
+
+/// Stop a tenant and delete its ledger, collections and audit log
+async fn delete_tenant(
+    State(registry): State<Arc<TenantRegistry>>,
+    Path(name): Path<String>,
+) -> Result<Json<TenantInfo>> {
+    registry.delete(&name).await.map(Json)
+}
@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
 use crate::{error::ApiError, AppState, Result};
+use mef_tic::{Invariants, Proof, SigmaBar, TICConfig, TICCrystallizer};
+use mef_vector_db::MembershipProof;
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -22,21 +24,47 @@ pub fn router() -> Router<AppState> {
 struct TicResponse {
     id: String,
     snapshot_id: String,
-    eigenvalues: Vec<f64>,
-    timestamp: String,
+    seed: String,
+    fixpoint: Vec<f64>,
+    window: Vec<String>,
+    invariants: Invariants,
+    sigma_bar: SigmaBar,
+    proof: Proof,
+    /// SHA-256 of the stored TIC, as computed by `TICCrystallizer::get_tic_hash`
+    hash: String,
 }
 
 async fn get_tic(
-    State(_state): State<AppState>,
+    State(state): State<AppState>,
     Path(id): Path<String>,
 ) -> Result<Json<TicResponse>> {
-    // For now, return a placeholder
-    // In a real implementation, we would load the TIC from storage
+    let crystallizer = TICCrystallizer::new(TICConfig::default(), state.store_path.as_ref())
+        .map_err(|e| ApiError::Internal(format!("Failed to create TICCrystallizer: {}", e)))?;
+
+    // IDs name files in the TIC store, so anything path-like cannot exist
+    let tic = if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        None
+    } else {
+        crystallizer
+            .load_tic(&id)
+            .map_err(|e| ApiError::Internal(format!("Failed to load TIC {}: {}", id, e)))?
+    };
+    let tic = tic.ok_or_else(|| ApiError::NotFound(format!("TIC {} not found", id)))?;
+
+    let hash = crystallizer
+        .get_tic_hash(&tic)
+        .map_err(|e| ApiError::Internal(format!("Failed to hash TIC: {}", e)))?;
+
     Ok(Json(TicResponse {
-        id: id.clone(),
-        snapshot_id: format!("snapshot_{}", id),
-        eigenvalues: vec![1.0, 0.5, 0.25],
-        timestamp: chrono::Utc::now().to_rfc3339(),
+        id: tic.tic_id,
+        snapshot_id: tic.source_snapshot,
+        seed: tic.seed,
+        fixpoint: tic.fixpoint,
+        window: tic.window,
+        invariants: tic.invariants,
+        sigma_bar: tic.sigma_bar,
+        proof: tic.proof,
+        hash,
     }))
 }
 
@@ -70,7 +98,7 @@ async fn tic_query(
 ) -> Result<Json<TicQueryResponse>> {
     let coupling_engine = state
         .coupling_engine
-        .lock()
+        .read()
         .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?;
 
     let results = coupling_engine
@@ -95,25 +123,106 @@ async fn tic_query(
     }))
 }
 
-/// Get membership proof by ID
+/// Membership proof for a vector, addressed as `<collection>:<vector_id>`
 #[derive(Debug, Serialize)]
 struct ProofResponse {
     id: String,
     proof_type: String,
+    /// Root of the collection tree the proof path leads to
     merkle_root: String,
+    /// Sibling hashes from leaf to root
     path: Vec<String>,
+    /// Result of verifying the proof against the current commit root
     valid: bool,
+    #[serde(flatten)]
+    proof: MembershipProof,
 }
 
-async fn get_proof(Path(id): Path<String>) -> Result<Json<ProofResponse>> {
-    // For now, return a placeholder
-    // In a real implementation, we would load the proof from storage
-    Ok(Json(ProofResponse {
-        id: id.clone(),
-        proof_type: "membership".to_string(),
-        merkle_root: "0x1234...".to_string(),
-        path: vec!["0xabcd...".to_string(), "0xef01...".to_string()],
-        valid: true,
+impl ProofResponse {
+    fn new(id: String, proof: MembershipProof, commit_root: &str) -> Self {
+        Self {
+            id,
+            proof_type: "membership".to_string(),
+            merkle_root: proof.collection_root.clone(),
+            path: proof
+                .siblings
+                .iter()
+                .map(|(_, hash)| hash.clone())
+                .collect(),
+            valid: proof.verify(None, Some(commit_root)),
+            proof,
+        }
+    }
+}
+
+/// Signed commit root the proofs chain up to
+#[derive(Debug, Serialize)]
+struct CommitInfo {
+    commit_root: String,
+    kid: String,
+    signature: String,
+}
+
+/// Split a proof ID into collection and vector ID
+fn parse_proof_id(id: &str) -> Result<(&str, &str)> {
+    id.split_once(':')
+        .filter(|(collection, vector_id)| !collection.is_empty() && !vector_id.is_empty())
+        .ok_or_else(|| {
+            ApiError::InvalidInput(format!(
+                "Proof ID must be <collection>:<vector_id>, got {}",
+                id
+            ))
+        })
+}
+
+/// Bring the proof registry up to date with the vector collections
+///
+/// Rebuilding a stale collection hashes all of its vectors, so this runs on
+/// the blocking pool.
+async fn refresh_proofs(state: &AppState) -> Result<CommitInfo> {
+    state
+        .blocking(|state| {
+            let index_manager = state
+                .index_manager
+                .read()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+            index_manager.refresh_proofs(&state.proof_registry);
+
+            Ok(CommitInfo {
+                commit_root: state.proof_registry.commit_root(),
+                kid: state.proof_registry.kid(),
+                signature: state.proof_registry.signature(),
+            })
+        })
+        .await
+}
+
+#[derive(Debug, Serialize)]
+struct GetProofResponse {
+    #[serde(flatten)]
+    proof: ProofResponse,
+    kid: String,
+    signature: String,
+}
+
+/// Get membership proof by ID
+///
+/// Example: `GET /proof/quality:vec_42`
+async fn get_proof(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<GetProofResponse>> {
+    let (collection, vector_id) = parse_proof_id(&id)?;
+    let commit = refresh_proofs(&state).await?;
+    let proof = state
+        .proof_registry
+        .get_membership_proof(collection, vector_id)
+        .ok_or_else(|| ApiError::NotFound(format!("Proof {} not found", id)))?;
+
+    Ok(Json(GetProofResponse {
+        proof: ProofResponse::new(id, proof, &commit.commit_root),
+        kid: commit.kid,
+        signature: commit.signature,
     }))
 }
 
@@ -121,47 +230,162 @@ async fn get_proof(Path(id): Path<String>) -> Result<Json<ProofResponse>> {
 #[derive(Debug, Deserialize)]
 struct BatchProofRequest {
     ids: Vec<String>,
+    /// Commit root the caller trusts; defaults to the current one
+    #[serde(default)]
+    commit_root: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct BatchProofResponse {
+    #[serde(flatten)]
+    commit: CommitInfo,
     proofs: Vec<ProofResponse>,
+    /// IDs without a proof
+    missing: Vec<String>,
+    /// Whether every requested proof exists and verified
+    all_valid: bool,
 }
 
-async fn batch_proofs(Json(request): Json<BatchProofRequest>) -> Result<Json<BatchProofResponse>> {
-    // For now, return placeholders for each ID
-    let proofs = request
-        .ids
-        .into_iter()
-        .map(|id| ProofResponse {
-            id: id.clone(),
-            proof_type: "membership".to_string(),
-            merkle_root: "0x1234...".to_string(),
-            path: vec!["0xabcd...".to_string(), "0xef01...".to_string()],
-            valid: true,
-        })
-        .collect();
+/// Fetch and verify membership proofs for several vectors
+async fn batch_proofs(
+    State(state): State<AppState>,
+    Json(request): Json<BatchProofRequest>,
+) -> Result<Json<BatchProofResponse>> {
+    let commit = refresh_proofs(&state).await?;
+    let expected_root = request
+        .commit_root
+        .unwrap_or_else(|| commit.commit_root.clone());
 
-    Ok(Json(BatchProofResponse { proofs }))
+    let mut proofs = Vec::new();
+    let mut missing = Vec::new();
+    for id in request.ids {
+        let (collection, vector_id) = parse_proof_id(&id)?;
+        match state
+            .proof_registry
+            .get_membership_proof(collection, vector_id)
+        {
+            Some(proof) => proofs.push(ProofResponse::new(id, proof, &expected_root)),
+            None => missing.push(id),
+        }
+    }
+
+    let all_valid = missing.is_empty() && proofs.iter().all(|p| p.valid);
+    Ok(Json(BatchProofResponse {
+        commit,
+        proofs,
+        missing,
+        all_valid,
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::ApiConfig;
+    use mef_vector_db::VectorRecord;
+    use ndarray::Array1;
+    use std::collections::HashMap;
 
     #[tokio::test]
     async fn test_get_tic() {
         let config = ApiConfig::default();
         let state = AppState::new(config).await.unwrap();
 
-        let result = get_tic(State(state), Path("test_tic".to_string())).await;
-        assert!(result.is_ok());
+        let crystallizer =
+            TICCrystallizer::new(TICConfig::default(), state.store_path.as_ref()).unwrap();
+        let fixpoint = Array1::from_vec(vec![0.5, 0.25, 0.125]);
+        let tic = crystallizer
+            .create_tic(
+                &fixpoint,
+                "snapshot_tic_test",
+                "tic_test_seed",
+                &serde_json::json!({"converged": true}),
+                &serde_json::json!({"id": "snapshot_tic_test"}),
+            )
+            .unwrap();
+        crystallizer.save_tic(&tic).unwrap();
+
+        let Json(response) = get_tic(State(state), Path(tic.tic_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(response.id, tic.tic_id);
+        assert_eq!(response.snapshot_id, "snapshot_tic_test");
+        assert_eq!(response.fixpoint, tic.fixpoint);
+        assert_eq!(response.hash, crystallizer.get_tic_hash(&tic).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_get_unknown_tic_is_not_found() {
+        let config = ApiConfig::default();
+        let state = AppState::new(config).await.unwrap();
+
+        for id in ["test_tic_missing", "../config"] {
+            let result = get_tic(State(state.clone()), Path(id.to_string())).await;
+            assert!(matches!(result, Err(ApiError::NotFound(_))));
+        }
+    }
+
+    async fn state_with_vectors(collection: &str) -> AppState {
+        let state = AppState::new(ApiConfig::default()).await.unwrap();
+        let records = ["a", "b", "c"]
+            .iter()
+            .enumerate()
+            .map(|(i, id)| {
+                VectorRecord::new(id.to_string(), vec![i as f64, 1.0], HashMap::new(), Some(1))
+            })
+            .collect();
+        state
+            .index_manager
+            .write()
+            .unwrap()
+            .upsert_vectors(collection, records, Some(1), None)
+            .unwrap();
+        state
     }
 
     #[tokio::test]
     async fn test_get_proof() {
-        let result = get_proof(Path("test_proof".to_string())).await;
-        assert!(result.is_ok());
+        let collection = format!("proof_{}", uuid::Uuid::new_v4().simple());
+        let state = state_with_vectors(&collection).await;
+
+        let id = format!("{}:b", collection);
+        let Json(response) = get_proof(State(state.clone()), Path(id)).await.unwrap();
+        assert!(response.proof.valid);
+        assert_eq!(response.proof.proof.vector_id, "b");
+        assert_eq!(
+            response.proof.proof.commit_root,
+            state.proof_registry.commit_root()
+        );
+
+        let missing = get_proof(State(state.clone()), Path(format!("{}:z", collection))).await;
+        assert!(matches!(missing, Err(ApiError::NotFound(_))));
+        let malformed = get_proof(State(state), Path("no_separator".to_string())).await;
+        assert!(matches!(malformed, Err(ApiError::InvalidInput(_))));
+    }
+
+    #[tokio::test]
+    async fn test_batch_proofs_verify_against_commit_root() {
+        let collection = format!("proof_{}", uuid::Uuid::new_v4().simple());
+        let state = state_with_vectors(&collection).await;
+
+        let request = BatchProofRequest {
+            ids: vec![format!("{}:a", collection), format!("{}:c", collection)],
+            commit_root: None,
+        };
+        let Json(response) = batch_proofs(State(state.clone()), Json(request))
+            .await
+            .unwrap();
+        assert!(response.all_valid);
+        assert_eq!(response.proofs.len(), 2);
+
+        // A stale or forged root fails verification, as does an unknown ID
+        let request = BatchProofRequest {
+            ids: vec![format!("{}:a", collection), format!("{}:z", collection)],
+            commit_root: Some("0".repeat(64)),
+        };
+        let Json(response) = batch_proofs(State(state), Json(request)).await.unwrap();
+        assert!(!response.all_valid);
+        assert!(!response.proofs[0].valid);
+        assert_eq!(response.missing, vec![format!("{}:z", collection)]);
     }
 }
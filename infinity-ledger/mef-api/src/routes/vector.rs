@@ -6,8 +6,16 @@ use axum::{
     Json, Router,
 };
 use serde::{Deserialize, Serialize};
-
-use crate::{error::ApiError, models::*, AppState, Result};
+use serde_json::Value as JsonValue;
+use std::collections::HashMap;
+
+use crate::{
+    error::ApiError,
+    jobs::{Job, JobKind, JobStatus},
+    models::*,
+    pagination::{Page, PageQuery, PageRequest},
+    AppState, Result,
+};
 
 pub fn router() -> Router<AppState> {
     Router::new()
@@ -24,29 +32,35 @@ pub fn router() -> Router<AppState> {
 }
 
 /// Search for vectors in a collection
-async fn search(
+pub(crate) async fn search(
     State(state): State<AppState>,
     Json(request): Json<SearchRequest>,
 ) -> Result<Json<SearchResponse>> {
     let start = std::time::Instant::now();
 
-    // Get the index manager (thread-safe access)
-    let mut index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
-
-    // Perform search with all required parameters
-    let results = index_manager
-        .search_vectors(
-            &request.collection,
-            &request.query_vector,
-            request.top_k,
-            None, // provider
-            None, // mode
-            None, // ef_search
-        )
-        .map_err(|e| ApiError::VectorDB(format!("Search failed: {}", e)))?;
+    // Searches share a read lock, so they run alongside each other and only
+    // wait for the short write phases of upserts and index builds
+    let collection = request.collection.clone();
+    let results = state
+        .blocking(move |state| {
+            let index_manager = state
+                .index_manager
+                .read()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+
+            // Perform search with all required parameters
+            index_manager
+                .search_vectors(
+                    &request.collection,
+                    &request.query_vector,
+                    request.top_k,
+                    None, // provider
+                    None, // mode
+                    None, // ef_search
+                )
+                .map_err(|e| ApiError::VectorDB(format!("Search failed: {}", e)))
+        })
+        .await?;
 
     // Convert results to SearchResult format
     let search_results: Vec<SearchResult> = results
@@ -69,46 +83,51 @@ async fn search(
 
     Ok(Json(SearchResponse {
         results: search_results,
-        collection: request.collection,
+        collection,
         query_time_ms: elapsed,
     }))
 }
 
 /// List all collections
 async fn list_collections(State(state): State<AppState>) -> Result<Json<CollectionsResponse>> {
-    let index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
-
-    let mut collections = Vec::new();
-
-    for (name, coll_state) in index_manager.collections.iter() {
-        // Get dimensions from first vector if available
-        let dimensions = coll_state
-            .vectors
-            .values()
-            .next()
-            .and_then(|v| v.get("vector"))
-            .and_then(|v| v.as_array())
-            .map(|arr| arr.len());
-
-        collections.push(CollectionInfo {
-            name: name.clone(),
-            vectors: coll_state.vectors.len(),
-            dimensions,
-            provider: coll_state
-                .indexes
-                .get("provider")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string()),
-        });
-    }
+    state
+        .blocking(|state| {
+            let index_manager = state
+                .index_manager
+                .read()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+
+            let mut collections = Vec::new();
+
+            for (name, coll_state) in index_manager.collections.iter() {
+                // Get dimensions from first vector if available
+                let dimensions = coll_state
+                    .vectors
+                    .values()
+                    .next()
+                    .and_then(|v| v.get("vector"))
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.len());
+
+                collections.push(CollectionInfo {
+                    name: name.clone(),
+                    vectors: coll_state.vectors.len(),
+                    dimensions,
+                    provider: coll_state
+                        .indexes
+                        .get("provider")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                });
+            }
 
-    // Sort by name for deterministic output
-    collections.sort_by(|a, b| a.name.cmp(&b.name));
+            // Sort by name for deterministic output
+            collections.sort_by(|a, b| a.name.cmp(&b.name));
 
-    Ok(Json(CollectionsResponse { collections }))
+            Ok(CollectionsResponse { collections })
+        })
+        .await
+        .map(Json)
 }
 
 /// Upsert vectors into a collection
@@ -130,11 +149,6 @@ async fn upsert_collection_vectors(
 ) -> Result<Json<UpsertResponse>> {
     use mef_vector_db::VectorRecord;
 
-    let mut index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
-
     // Convert VectorPayload to VectorRecord, using epoch from payload or default to 1 for benchmarking
     let records: Vec<VectorRecord> = request
         .vectors
@@ -153,83 +167,133 @@ async fn upsert_collection_vectors(
 
     let count = records.len();
 
-    // Provide default epoch of 1 for records that don't have one
-    index_manager
-        .upsert_vectors(&collection, records, Some(1), None)
-        .map_err(|e| ApiError::VectorDB(format!("Failed to upsert vectors: {}", e)))?;
+    // Persisting the collection touches the disk, so run off the async workers
+    let name = collection.clone();
+    state
+        .blocking(move |state| {
+            let mut index_manager = state
+                .index_manager
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+            state
+                .config
+                .quotas
+                .check_vectors(&index_manager, &name, &records)?;
+
+            // Provide default epoch of 1 for records that don't have one
+            index_manager
+                .upsert_vectors(&name, records, Some(1), None)
+                .map_err(|e| ApiError::VectorDB(format!("Failed to upsert vectors: {}", e)))
+        })
+        .await?;
 
     Ok(Json(UpsertResponse { count, collection }))
 }
 
 /// List vectors in a collection
-#[derive(Debug, Deserialize)]
-struct ListVectorsQuery {
+///
+/// Vectors are listed in ascending id order, one page at a time. Filters:
+/// `epoch_from` / `epoch_to` (inclusive) and `metadata.<key>=<value>` for
+/// metadata equality; a value matches a string field by its text and other
+/// fields by their JSON form (`metadata.rank=3`, `metadata.draft=false`).
+#[derive(Debug, Default, Deserialize)]
+struct VectorFilterQuery {
     #[serde(default)]
-    limit: Option<usize>,
+    epoch_from: Option<i64>,
     #[serde(default)]
-    offset: Option<usize>,
+    epoch_to: Option<i64>,
+    #[serde(flatten)]
+    fields: HashMap<String, String>,
 }
 
-#[derive(Debug, Serialize)]
-struct ListVectorsResponse {
-    vectors: Vec<VectorPayload>,
-    total: usize,
-    collection: String,
+impl VectorFilterQuery {
+    /// Predicate over stored vectors; metadata values are parsed once
+    fn matcher(&self) -> impl Fn(&HashMap<String, JsonValue>) -> bool + '_ {
+        let metadata: Vec<(&str, &str, Option<JsonValue>)> = self
+            .fields
+            .iter()
+            .filter_map(|(param, expected)| {
+                let key = param.strip_prefix("metadata.")?;
+                Some((key, expected.as_str(), expected.parse().ok()))
+            })
+            .collect();
+
+        move |vec_data| {
+            if self.epoch_from.is_some() || self.epoch_to.is_some() {
+                let Some(epoch) = vec_data.get("epoch").and_then(|v| v.as_i64()) else {
+                    return false;
+                };
+                if self.epoch_from.is_some_and(|from| epoch < from)
+                    || self.epoch_to.is_some_and(|to| epoch > to)
+                {
+                    return false;
+                }
+            }
+
+            metadata.iter().all(|(key, text, parsed)| {
+                match vec_data.get("metadata").and_then(|m| m.get(*key)) {
+                    Some(JsonValue::String(value)) => value == text,
+                    Some(value) => parsed.as_ref() == Some(value),
+                    None => false,
+                }
+            })
+        }
+    }
 }
 
+/// Example: `GET /collections/docs/vectors?limit=50&metadata.kind=note&epoch_from=3`
 async fn list_collection_vectors(
     State(state): State<AppState>,
     Path(collection): Path<String>,
-    Query(params): Query<ListVectorsQuery>,
-) -> Result<Json<ListVectorsResponse>> {
-    let index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
-
-    let coll_state = index_manager
-        .collections
-        .get(&collection)
-        .ok_or_else(|| ApiError::NotFound(format!("Collection {} not found", collection)))?;
-
-    let total = coll_state.vectors.len();
-    let offset = params.offset.unwrap_or(0);
-    let limit = params.limit.unwrap_or(100).min(1000); // Cap at 1000
-
-    // Convert HashMap entries to VectorPayload
-    let vectors: Vec<VectorPayload> = coll_state
-        .vectors
-        .iter()
-        .skip(offset)
-        .take(limit)
-        .map(|(id, vec_data)| {
-            let vector = vec_data
-                .get("vector")
-                .and_then(|v| v.as_array())
-                .map(|arr| arr.iter().filter_map(|x| x.as_f64()).collect())
-                .unwrap_or_else(Vec::new);
-
-            let metadata = vec_data
-                .get("metadata")
-                .and_then(|v| v.as_object())
-                .cloned();
-
-            let epoch = vec_data.get("epoch").and_then(|v| v.as_i64());
-
-            VectorPayload {
-                id: id.clone(),
-                vector,
-                metadata,
-                epoch,
-            }
+    Query(page): Query<PageQuery>,
+    Query(filter): Query<VectorFilterQuery>,
+) -> Result<Json<Page<VectorPayload>>> {
+    let request = PageRequest::<String>::parse(&page)?;
+    state
+        .blocking(move |state| {
+            let index_manager = state
+                .index_manager
+                .read()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+
+            let coll_state = index_manager.collections.get(&collection).ok_or_else(|| {
+                ApiError::NotFound(format!("Collection {} not found", collection))
+            })?;
+
+            // Only the selected page is converted to payloads
+            let matches = filter.matcher();
+            let page = request.select(
+                coll_state
+                    .vectors
+                    .iter()
+                    .filter(|(_, vec_data)| matches(vec_data))
+                    .map(|(id, vec_data)| (id, (id, vec_data))),
+            );
+
+            Ok(page.map(|(id, vec_data)| {
+                let vector = vec_data
+                    .get("vector")
+                    .and_then(|v| v.as_array())
+                    .map(|arr| arr.iter().filter_map(|x| x.as_f64()).collect())
+                    .unwrap_or_else(Vec::new);
+
+                let metadata = vec_data
+                    .get("metadata")
+                    .and_then(|v| v.as_object())
+                    .cloned();
+
+                let epoch = vec_data.get("epoch").and_then(|v| v.as_i64());
+
+                VectorPayload {
+                    id: id.clone(),
+                    vector,
+                    metadata,
+                    epoch,
+                }
+            }))
         })
-        .collect();
-
-    Ok(Json(ListVectorsResponse {
-        vectors,
-        total,
-        collection,
-    }))
+        .await
+        .map(Json)
 }
 
 /// Update collection provider
@@ -250,24 +314,29 @@ async fn update_collection_provider(
     Path(collection): Path<String>,
     Json(request): Json<UpdateProviderRequest>,
 ) -> Result<Json<UpdateProviderResponse>> {
-    let mut index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
-
-    index_manager
-        .set_collection_provider(&collection, &request.provider)
-        .map_err(|e| ApiError::VectorDB(format!("Failed to set provider: {}", e)))?;
-
-    Ok(Json(UpdateProviderResponse {
-        collection,
-        provider: request.provider,
-        status: "updated".to_string(),
-    }))
+    state
+        .blocking(move |state| {
+            let mut index_manager = state
+                .index_manager
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+
+            index_manager
+                .set_collection_provider(&collection, &request.provider)
+                .map_err(|e| ApiError::VectorDB(format!("Failed to set provider: {}", e)))?;
+
+            Ok(UpdateProviderResponse {
+                collection,
+                provider: request.provider,
+                status: "updated".to_string(),
+            })
+        })
+        .await
+        .map(Json)
 }
 
 /// Bulk upsert points (async operation)
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 struct BulkPointsRequest {
     collection: String,
     points: Vec<VectorPayload>,
@@ -276,80 +345,119 @@ struct BulkPointsRequest {
 #[derive(Debug, Serialize)]
 struct BulkPointsResponse {
     job_id: String,
-    status: String,
+    status: JobStatus,
     total_points: usize,
 }
 
+/// Points upserted per index manager lock
+const BULK_CHUNK_SIZE: usize = 500;
+
 async fn bulk_upsert_points(
     State(state): State<AppState>,
     Json(request): Json<BulkPointsRequest>,
 ) -> Result<(StatusCode, Json<BulkPointsResponse>)> {
-    use mef_vector_db::VectorRecord;
-    use uuid::Uuid;
-
-    let job_id = Uuid::new_v4().to_string();
     let total_points = request.points.len();
-
-    // For now, we'll process synchronously but could make async in future
-    let mut index_manager = state
-        .index_manager
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
-
-    // Convert points to VectorRecord
-    let records: Vec<VectorRecord> = request
-        .points
-        .into_iter()
-        .map(|v| {
-            VectorRecord::new(
-                v.id,
-                v.vector,
-                v.metadata
-                    .map(|m| m.into_iter().collect())
-                    .unwrap_or_default(),
-                None, // epoch
-            )
-        })
-        .collect();
-
-    index_manager
-        .upsert_vectors(&request.collection, records, None, None)
-        .map_err(|e| ApiError::VectorDB(format!("Failed to upsert vectors: {}", e)))?;
+    let params = serde_json::to_value(&request)?;
+    let job = state
+        .jobs
+        .submit(JobKind::BulkUpsert, &params, total_points)?;
 
     Ok((
         StatusCode::ACCEPTED,
         Json(BulkPointsResponse {
-            job_id,
-            status: "completed".to_string(),
+            job_id: job.id,
+            status: job.status,
             total_points,
         }),
     ))
 }
 
+/// Job body of [`bulk_upsert_points`]
+///
+/// Points are upserted in chunks so searches are not blocked for the whole
+/// job and cancellation takes effect between chunks.
+pub(crate) fn run_bulk_upsert(state: &AppState, job: &Job) -> Result<JsonValue> {
+    use mef_vector_db::VectorRecord;
+
+    let request: BulkPointsRequest = serde_json::from_value(state.jobs.params(&job.id)?)?;
+    let total_points = request.points.len();
+
+    let mut processed = 0;
+    for chunk in request.points.chunks(BULK_CHUNK_SIZE) {
+        state.jobs.report_progress(&job.id, processed)?;
+
+        // Default epoch of 1 for points without one, as for collection upserts
+        let records: Vec<VectorRecord> = chunk
+            .iter()
+            .cloned()
+            .map(|v| {
+                VectorRecord::new(
+                    v.id,
+                    v.vector,
+                    v.metadata
+                        .map(|m| m.into_iter().collect())
+                        .unwrap_or_default(),
+                    v.epoch,
+                )
+            })
+            .collect();
+
+        let mut index_manager = state
+            .index_manager
+            .write()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+        state
+            .config
+            .quotas
+            .check_vectors(&index_manager, &request.collection, &records)?;
+        index_manager
+            .upsert_vectors(&request.collection, records, Some(1), None)
+            .map_err(|e| ApiError::VectorDB(format!("Failed to upsert vectors: {}", e)))?;
+        processed += chunk.len();
+    }
+
+    Ok(serde_json::json!({
+        "collection": request.collection,
+        "upserted": total_points,
+    }))
+}
+
 /// Get bulk job status
 #[derive(Debug, Serialize)]
 struct BulkJobStatusResponse {
     job_id: String,
-    status: String,
+    status: JobStatus,
     progress: f64,
     total: usize,
     processed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
 }
 
-async fn bulk_job_status(Path(job_id): Path<String>) -> Result<Json<BulkJobStatusResponse>> {
-    // For now, all jobs complete immediately, so always return completed
+async fn bulk_job_status(
+    State(state): State<AppState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<BulkJobStatusResponse>> {
+    let job = state
+        .jobs
+        .get(&job_id)
+        .filter(|job| job.kind == JobKind::BulkUpsert)
+        .ok_or_else(|| ApiError::NotFound(format!("Bulk job {} not found", job_id)))?;
+
     Ok(Json(BulkJobStatusResponse {
-        job_id,
-        status: "completed".to_string(),
-        progress: 1.0,
-        total: 0,
-        processed: 0,
+        job_id: job.id,
+        status: job.status,
+        progress: job.progress,
+        total: job.total,
+        processed: job.processed,
+        error: job.error,
     }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::test_state;
     use crate::ApiConfig;
 
     #[tokio::test]
@@ -360,4 +468,108 @@ mod tests {
         let result = list_collections(State(state)).await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_bulk_upsert_runs_as_job() {
+        let (_dir, state) = test_state().await;
+
+        let points = (0..BULK_CHUNK_SIZE + 10)
+            .map(|i| VectorPayload {
+                id: format!("p{}", i),
+                vector: vec![i as f64, 1.0],
+                metadata: None,
+                epoch: None,
+            })
+            .collect();
+        let request = BulkPointsRequest {
+            collection: "bulk".to_string(),
+            points,
+        };
+        let (status, Json(accepted)) = bulk_upsert_points(State(state.clone()), Json(request))
+            .await
+            .unwrap();
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        state.jobs.wait(&accepted.job_id).await.unwrap();
+        let Json(job) = bulk_job_status(State(state.clone()), Path(accepted.job_id))
+            .await
+            .unwrap();
+        assert_eq!(job.status, JobStatus::Completed);
+        assert_eq!(job.processed, BULK_CHUNK_SIZE + 10);
+        assert_eq!(job.progress, 1.0);
+        assert_eq!(
+            state.index_manager.read().unwrap().collections["bulk"]
+                .vectors
+                .len(),
+            BULK_CHUNK_SIZE + 10
+        );
+
+        let unknown = bulk_job_status(State(state), Path("unknown".to_string())).await;
+        assert!(matches!(unknown, Err(ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_vectors_pages_and_filters() {
+        let (_dir, state) = test_state().await;
+        let records = (0..10)
+            .map(|i| {
+                let kind = if i % 2 == 0 { "even" } else { "odd" };
+                let metadata = HashMap::from([
+                    ("kind".to_string(), serde_json::json!(kind)),
+                    ("rank".to_string(), serde_json::json!(i)),
+                ]);
+                mef_vector_db::VectorRecord::new(
+                    format!("v{}", i),
+                    vec![i as f64],
+                    metadata,
+                    Some(i),
+                )
+            })
+            .collect();
+        state
+            .index_manager
+            .write()
+            .unwrap()
+            .upsert_vectors("docs", records, None, None)
+            .unwrap();
+
+        let list = |uri: String| {
+            let state = state.clone();
+            async move {
+                let uri: axum::http::Uri = uri.parse().unwrap();
+                let page = Query::try_from_uri(&uri).unwrap();
+                let filter = Query::try_from_uri(&uri).unwrap();
+                list_collection_vectors(State(state), Path("docs".to_string()), page, filter)
+                    .await
+                    .map(|Json(page)| page)
+            }
+        };
+
+        // Pages follow id order and cover every match once
+        let mut ids = Vec::new();
+        let mut uri = "/?limit=2&metadata.kind=even&epoch_from=2&epoch_to=8".to_string();
+        loop {
+            let page = list(uri.clone()).await.unwrap();
+            assert_eq!(page.total, 4);
+            ids.extend(page.items.into_iter().map(|v| v.id));
+            match page.next_cursor {
+                Some(cursor) => {
+                    uri = format!(
+                        "/?limit=2&metadata.kind=even&epoch_from=2&epoch_to=8&cursor={}",
+                        cursor
+                    )
+                }
+                None => break,
+            }
+        }
+        assert_eq!(ids, ["v2", "v4", "v6", "v8"]);
+
+        let page = list("/?metadata.rank=3".to_string()).await.unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].id, "v3");
+        assert_eq!(page.items[0].epoch, Some(3));
+
+        let invalid = list("/?cursor=not-a-cursor".to_string()).await;
+        assert!(matches!(invalid, Err(ApiError::InvalidInput(_))));
+    }
 }
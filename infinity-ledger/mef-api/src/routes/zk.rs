@@ -27,14 +27,16 @@ async fn zk_infer(
     State(state): State<AppState>,
     Json(request): Json<ZkInferRequest>,
 ) -> Result<Json<ZkInferResponse>> {
-    let mut coupling_engine = state
-        .coupling_engine
-        .lock()
-        .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?;
-
-    let result = coupling_engine
-        .zk_infer(&request.input)
-        .map_err(|e| ApiError::Processing(format!("ZK inference failed: {}", e)))?;
+    let result = state
+        .blocking(move |state| {
+            state
+                .coupling_engine
+                .write()
+                .map_err(|e| ApiError::Internal(format!("Failed to lock coupling engine: {}", e)))?
+                .zk_infer(&request.input)
+                .map_err(|e| ApiError::Processing(format!("ZK inference failed: {}", e)))
+        })
+        .await?;
 
     // Extract proof from result
     let proof = result
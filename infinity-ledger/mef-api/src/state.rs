@@ -1,44 +1,79 @@
 /// Application state for API server
 use anyhow::Result;
 use std::path::PathBuf;
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use crate::config::ApiConfig;
-use mef_core::gates::merkaba_gate::MerkabaGate;
+use crate::error::ApiError;
+use crate::events::EventBus;
+use crate::idempotency::IdempotencyStore;
+use crate::jobs::{self, JobQueue};
+use crate::metrics::Metrics;
+use mef_audit::MEFAuditLogger;
+use mef_core::gates::{merkaba_gate::MerkabaGate, GateFsm};
 use mef_core::MEFCore;
 use mef_coupling::SpiralCouplingEngine;
 use mef_domains::DomainLayer;
-use mef_ledger::MEFLedger;
+use mef_ledger::{LedgerConfig, LedgerSigner, MEFLedger};
 use mef_spiral::SpiralConfig;
 use mef_topology::MetatronRouter;
-use mef_vector_db::IndexManager;
+use mef_vector_db::{IndexManager, ProofRegistry};
 
 /// Shared application state
+///
+/// Components sit behind read-write locks so reads such as searches, block
+/// lookups and status queries run concurrently; handlers only take the write
+/// lock for mutations and run heavy work on the blocking thread pool.
 #[derive(Clone)]
 pub struct AppState {
     pub config: Arc<ApiConfig>,
     pub spiral_config: Arc<SpiralConfig>,
     pub store_path: Arc<PathBuf>,
-    pub ledger: Arc<Mutex<MEFLedger>>,
-    pub index_manager: Arc<Mutex<IndexManager>>,
-    pub coupling_engine: Arc<Mutex<SpiralCouplingEngine>>,
-    pub metatron_router: Arc<Mutex<MetatronRouter>>,
-    pub merkaba_gate: Arc<Mutex<MerkabaGate>>,
-    pub domain_layer: Arc<Mutex<DomainLayer>>,
+    pub ledger: Arc<RwLock<MEFLedger>>,
+    pub index_manager: Arc<RwLock<IndexManager>>,
+    pub proof_registry: Arc<ProofRegistry>,
+    pub coupling_engine: Arc<RwLock<SpiralCouplingEngine>>,
+    pub metatron_router: Arc<RwLock<MetatronRouter>>,
+    pub merkaba_gate: Arc<RwLock<MerkabaGate>>,
+    pub domain_layer: Arc<RwLock<DomainLayer>>,
+    pub gate_fsm: Arc<GateFsm>,
+    pub events: Arc<EventBus>,
+    pub metrics: Arc<Metrics>,
+    pub jobs: Arc<JobQueue>,
+    pub idempotency: Arc<IdempotencyStore>,
+    /// Audit log recording the events published on `events`
+    pub audit: Arc<Mutex<MEFAuditLogger>>,
+    /// Set once shutdown has started
+    pub draining: Arc<AtomicBool>,
 }
 
 impl AppState {
     /// Create new application state with initialized components
+    ///
+    /// Also starts the background job workers, resuming jobs left unfinished
+    /// by a previous run.
     pub async fn new(config: ApiConfig) -> Result<Self> {
         // Initialize spiral configuration
         let spiral_config = SpiralConfig::default();
         let store_path = config.store_path.clone();
 
-        // Initialize ledger
-        let ledger = MEFLedger::new(&config.ledger_path)?;
+        // Initialize ledger, signing blocks if a signer key is configured
+        let ledger_config = LedgerConfig {
+            signer: LedgerSigner::from_env()?,
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(&config.ledger_path, ledger_config)?;
+
+        // Metrics are fed by observers on the ledger, index manager and gate
+        let metrics = Arc::new(Metrics::new()?);
+        metrics.set_ledger_height(ledger.index().blocks.len());
+        ledger.set_observer(metrics.clone());
 
         // Initialize index manager
-        let index_manager = IndexManager::new(Some(store_path.join("vector_db")))?;
+        let mut index_manager = IndexManager::new(Some(store_path.join("vector_db")))?;
+        index_manager.set_observer(metrics.clone());
 
         // Initialize coupling engine with proper parameters
         // SpiralCouplingEngine::new(base_path, params, resonance, eps_pi, zk_mu)
@@ -53,8 +88,14 @@ impl AppState {
         // Initialize Metatron Router
         let metatron_router = MetatronRouter::new(store_path.join("metatron"));
 
-        // Initialize Merkaba Gate
-        let merkaba_gate = MerkabaGate::new(store_path.join("merkaba_audit.jsonl"));
+        // Initialize Merkaba Gate; its decisions drive the persisted gate FSM
+        let gate_fsm = Arc::new(GateFsm::open(
+            store_path.join("gate_fsm.json"),
+            Duration::from_secs(config.gate_cooldown_secs),
+        )?);
+        let mut merkaba_gate = MerkabaGate::new(store_path.join("merkaba_audit.jsonl"));
+        merkaba_gate.observers.push(metrics.clone());
+        merkaba_gate.observers.push(gate_fsm.clone());
 
         // Initialize MEF-Core pipeline for domain layer
         let mef_pipeline = Arc::new(MEFCore::new("api-domain-seed", None)?);
@@ -66,16 +107,139 @@ impl AppState {
             store_path.join("domains"),
         )?;
 
-        Ok(Self {
+        // Background jobs persist next to the other stores
+        let job_queue = JobQueue::open(store_path.join("jobs"), config.job_max_attempts)?;
+        let job_workers = config.job_workers;
+
+        // Responses replayed for retried requests with an Idempotency-Key
+        let idempotency = IdempotencyStore::open(
+            store_path.join("idempotency"),
+            Duration::from_secs(config.idempotency_ttl_secs),
+        )?;
+
+        // Pipeline events are kept in the audit log next to the other logs
+        let audit = Arc::new(Mutex::new(MEFAuditLogger::new(
+            config.logs_path.join("audit"),
+        )?));
+        let mut events = EventBus::default();
+        events.set_audit_log(audit.clone());
+
+        let state = Self {
             config: Arc::new(config),
             spiral_config: Arc::new(spiral_config),
             store_path: Arc::new(store_path),
-            ledger: Arc::new(Mutex::new(ledger)),
-            index_manager: Arc::new(Mutex::new(index_manager)),
-            coupling_engine: Arc::new(Mutex::new(coupling_engine)),
-            metatron_router: Arc::new(Mutex::new(metatron_router)),
-            merkaba_gate: Arc::new(Mutex::new(merkaba_gate)),
-            domain_layer: Arc::new(Mutex::new(domain_layer)),
-        })
+            ledger: Arc::new(RwLock::new(ledger)),
+            index_manager: Arc::new(RwLock::new(index_manager)),
+            proof_registry: Arc::new(ProofRegistry::default()),
+            coupling_engine: Arc::new(RwLock::new(coupling_engine)),
+            metatron_router: Arc::new(RwLock::new(metatron_router)),
+            merkaba_gate: Arc::new(RwLock::new(merkaba_gate)),
+            domain_layer: Arc::new(RwLock::new(domain_layer)),
+            gate_fsm,
+            events: Arc::new(events),
+            metrics,
+            jobs: Arc::new(job_queue),
+            idempotency: Arc::new(idempotency),
+            audit,
+            draining: Arc::new(AtomicBool::new(false)),
+        };
+        jobs::spawn_workers(&state, job_workers);
+        Ok(state)
+    }
+
+    /// Start shutting down: report not ready and end live event streams so
+    /// connections can drain
+    pub fn drain(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+        self.events.close();
+    }
+
+    /// Whether [`drain`](Self::drain) was called
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Stop the job workers and flush buffered state to disk
+    ///
+    /// Called once in-flight requests have drained. Running jobs finish in the
+    /// background; queued jobs stay on disk for the next start.
+    pub async fn shutdown(&self) -> Result<()> {
+        self.drain();
+        self.jobs.close();
+        let state = self.clone();
+        tokio::task::spawn_blocking(move || state.flush()).await?
     }
+
+    /// Write the audit log buffer, the collections with their index manifest
+    /// and the coupling state to disk
+    ///
+    /// Every component is flushed even if another one fails.
+    pub fn flush(&self) -> Result<()> {
+        let results = [
+            (
+                "audit log",
+                self.audit.lock().unwrap_or_else(|e| e.into_inner()).flush(),
+            ),
+            (
+                "vector indexes",
+                self.index_manager
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .flush(),
+            ),
+            (
+                "coupling state",
+                self.coupling_engine
+                    .read()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .flush(),
+            ),
+        ];
+
+        let failures: Vec<String> = results
+            .into_iter()
+            .filter_map(|(component, result)| {
+                result.err().map(|e| format!("{}: {:#}", component, e))
+            })
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!("Failed to flush {}", failures.join("; ")))
+        }
+    }
+
+    /// Run `f` with a clone of the state on the blocking thread pool
+    ///
+    /// Handlers use this for work that holds component locks while computing
+    /// or touching the disk, so the async workers keep serving other requests.
+    pub async fn blocking<T, F>(&self, f: F) -> crate::Result<T>
+    where
+        F: FnOnce(&AppState) -> crate::Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let state = self.clone();
+        tokio::task::spawn_blocking(move || f(&state))
+            .await
+            .map_err(|e| ApiError::Internal(format!("Blocking task failed: {}", e)))?
+    }
+}
+
+/// Configuration whose store, ledger and logs live under `dir`
+#[cfg(test)]
+pub(crate) fn test_config(dir: &std::path::Path) -> ApiConfig {
+    ApiConfig {
+        store_path: dir.join("store"),
+        ledger_path: dir.join("ledger"),
+        logs_path: dir.join("logs"),
+        ..ApiConfig::default()
+    }
+}
+
+/// State backed by a fresh temporary directory, kept alive by the guard
+#[cfg(test)]
+pub(crate) async fn test_state() -> (tempfile::TempDir, AppState) {
+    let dir = tempfile::tempdir().unwrap();
+    let state = AppState::new(test_config(dir.path())).await.unwrap();
+    (dir, state)
 }
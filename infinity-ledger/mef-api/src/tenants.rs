@@ -0,0 +1,801 @@
+/// Tenant namespaces
+///
+/// Every tenant gets its own [`AppState`] - ledger, collections, audit log,
+/// jobs and seed - stored under `<store>/tenants/<name>/`. A request reaches a
+/// tenant through the `/tenants/<name>/...` path prefix or by presenting a
+/// token bound to the tenant; all other requests are served by the default
+/// namespace. Each namespace enforces the [`Quotas`] of its configuration.
+use anyhow::Context;
+use axum::{
+    extract::{Request, State},
+    http::Uri,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Router,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::{Notify, RwLock};
+use tower::ServiceExt;
+
+use crate::{
+    auth::{self, Scope},
+    config::ApiConfig,
+    error::ApiError,
+    metrics, routes, AppState, Result,
+};
+use mef_ledger::MEFLedger;
+use mef_vector_db::{IndexManager, VectorRecord};
+
+/// Path prefix addressing a tenant, followed by its name
+pub const TENANT_PREFIX: &str = "/tenants/";
+/// Settings file inside a tenant directory
+const TENANT_FILE: &str = "tenant.json";
+/// Longest accepted tenant name
+const MAX_NAME_LEN: usize = 63;
+
+/// Limits on the data stored in a namespace
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Quotas {
+    /// Vectors across all collections
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_vectors: Option<usize>,
+    /// Ledger blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_blocks: Option<usize>,
+}
+
+impl Quotas {
+    /// Fail if upserting `records` into `collection` would exceed `max_vectors`
+    ///
+    /// Records replacing a stored vector do not count against the quota.
+    ///
+    /// # Arguments
+    /// * `index_manager` - Index manager of the namespace
+    /// * `collection` - Target collection
+    /// * `records` - Vectors about to be upserted
+    pub fn check_vectors(
+        &self,
+        index_manager: &IndexManager,
+        collection: &str,
+        records: &[VectorRecord],
+    ) -> Result<()> {
+        let Some(max) = self.max_vectors else {
+            return Ok(());
+        };
+        let stored = index_manager.collections.get(collection);
+        let added = records
+            .iter()
+            .map(|record| record.id.as_str())
+            .filter(|id| !stored.is_some_and(|c| c.vectors.contains_key(*id)))
+            .collect::<HashSet<_>>()
+            .len();
+
+        let count = vector_count(index_manager);
+        if count + added > max {
+            return Err(ApiError::Forbidden(format!(
+                "Vector quota exceeded: {} stored and {} new vectors, limit {}",
+                count, added, max
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fail if appending a block would exceed `max_blocks`
+    pub fn check_blocks(&self, ledger: &MEFLedger) -> Result<()> {
+        match self.max_blocks {
+            Some(max) if ledger.index().blocks.len() >= max => Err(ApiError::Forbidden(format!(
+                "Block quota exceeded: limit {}",
+                max
+            ))),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn vector_count(index_manager: &IndexManager) -> usize {
+    index_manager
+        .collections
+        .values()
+        .map(|collection| collection.vectors.len())
+        .sum()
+}
+
+/// Stored settings of a tenant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TenantInfo {
+    pub name: String,
+    /// Default seed of the tenant's pipelines
+    pub seed: String,
+    #[serde(default)]
+    pub quotas: Quotas,
+    /// RFC 3339 timestamp
+    pub created_at: String,
+}
+
+/// Data held by a tenant
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantUsage {
+    pub collections: usize,
+    pub vectors: usize,
+    pub blocks: usize,
+}
+
+/// A tenant with its current usage
+#[derive(Debug, Clone, Serialize)]
+pub struct TenantSummary {
+    #[serde(flatten)]
+    pub info: TenantInfo,
+    pub usage: TenantUsage,
+}
+
+/// Settings for a new tenant
+#[derive(Debug, Clone, Deserialize)]
+pub struct NewTenant {
+    pub name: String,
+    /// Defaults to the server seed suffixed with the tenant name
+    #[serde(default)]
+    pub seed: Option<String>,
+    #[serde(default)]
+    pub quotas: Quotas,
+}
+
+struct Tenant {
+    info: TenantInfo,
+    state: AppState,
+    app: Router,
+    requests: Arc<InFlight>,
+}
+
+/// Requests a tenant is currently serving
+#[derive(Default)]
+struct InFlight {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl InFlight {
+    fn enter(self: &Arc<Self>) -> InFlightGuard {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(self.clone())
+    }
+
+    /// Wait until every request that entered has finished
+    async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+            if self.count.load(Ordering::SeqCst) == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// Marks a request as finished when dropped
+struct InFlightGuard(Arc<InFlight>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.0.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+impl Tenant {
+    fn summary(&self) -> Result<TenantSummary> {
+        let index_manager = self
+            .state
+            .index_manager
+            .read()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock index manager: {}", e)))?;
+        let ledger = self
+            .state
+            .ledger
+            .read()
+            .map_err(|e| ApiError::Internal(format!("Failed to lock ledger: {}", e)))?;
+
+        Ok(TenantSummary {
+            info: self.info.clone(),
+            usage: TenantUsage {
+                collections: index_manager.collections.len(),
+                vectors: vector_count(&index_manager),
+                blocks: ledger.index().blocks.len(),
+            },
+        })
+    }
+}
+
+/// Tenants served next to the default namespace
+pub struct TenantRegistry {
+    /// Configuration of the default namespace, the template for tenants
+    config: Arc<ApiConfig>,
+    dir: PathBuf,
+    tenants: RwLock<BTreeMap<String, Tenant>>,
+    /// Names of tenants being created, reserved outside the `tenants` lock
+    creating: std::sync::Mutex<HashSet<String>>,
+}
+
+/// Releases a reserved tenant name when creation ends, successful or not
+struct Reservation<'a> {
+    creating: &'a std::sync::Mutex<HashSet<String>>,
+    name: String,
+}
+
+impl Drop for Reservation<'_> {
+    fn drop(&mut self) {
+        self.creating
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.name);
+    }
+}
+
+impl TenantRegistry {
+    /// Open the tenants stored below the store of the default namespace
+    ///
+    /// Starts the state, including the job workers, of every tenant.
+    ///
+    /// # Arguments
+    /// * `config` - Configuration of the default namespace
+    pub async fn open(config: Arc<ApiConfig>) -> anyhow::Result<Self> {
+        let dir = config.store_path.join("tenants");
+        std::fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create tenants directory: {:?}", dir))?;
+
+        let mut registry = Self {
+            config,
+            dir,
+            tenants: RwLock::new(BTreeMap::new()),
+            creating: Default::default(),
+        };
+        let mut tenants = BTreeMap::new();
+        for entry in std::fs::read_dir(&registry.dir)? {
+            let path = entry?.path().join(TENANT_FILE);
+            if !path.is_file() {
+                continue;
+            }
+            let contents = std::fs::read_to_string(&path)?;
+            let info: TenantInfo = serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse tenant file: {:?}", path))?;
+            let tenant = Self::start(&registry.config, &registry.dir, info).await?;
+            tenants.insert(tenant.info.name.clone(), tenant);
+        }
+        *registry.tenants.get_mut() = tenants;
+        Ok(registry)
+    }
+
+    fn tenant_dir(&self, name: &str) -> PathBuf {
+        self.dir.join(name)
+    }
+
+    /// Build the state and routes of a tenant from the default configuration
+    ///
+    /// # Arguments
+    /// * `template` - Configuration of the default namespace
+    /// * `tenants_dir` - Directory holding every tenant
+    /// * `info` - Tenant to start
+    async fn start(
+        template: &ApiConfig,
+        tenants_dir: &std::path::Path,
+        info: TenantInfo,
+    ) -> anyhow::Result<Tenant> {
+        let dir = tenants_dir.join(&info.name);
+        let config = ApiConfig {
+            store_path: dir.join("store"),
+            ledger_path: dir.join("ledger"),
+            logs_path: dir.join("logs"),
+            seed: info.seed.clone(),
+            quotas: info.quotas,
+            tenant: Some(info.name.clone()),
+            ..template.clone()
+        };
+        for path in [&config.store_path, &config.ledger_path, &config.logs_path] {
+            std::fs::create_dir_all(path)
+                .with_context(|| format!("Failed to create directory: {:?}", path))?;
+        }
+
+        let state = AppState::new(config)
+            .await
+            .with_context(|| format!("Failed to start tenant {}", info.name))?;
+        let app = routes::app(state.clone()).layer(middleware::from_fn_with_state(
+            state.metrics.clone(),
+            metrics::track_requests,
+        ));
+        Ok(Tenant {
+            info,
+            state,
+            app,
+            requests: Arc::default(),
+        })
+    }
+
+    /// Create and start a tenant
+    ///
+    /// # Arguments
+    /// * `request` - Name, seed and quotas of the tenant
+    pub async fn create(&self, request: NewTenant) -> Result<TenantSummary> {
+        validate_name(&request.name)?;
+
+        // Reserve the name, so the tenants stay available to requests while
+        // the new one is set up
+        let exists = ApiError::Conflict(format!("Tenant {} already exists", request.name));
+        let reservation = {
+            let tenants = self.tenants.read().await;
+            let mut creating = self.creating.lock().unwrap_or_else(|e| e.into_inner());
+            if tenants.contains_key(&request.name) || !creating.insert(request.name.clone()) {
+                return Err(exists);
+            }
+            Reservation {
+                creating: &self.creating,
+                name: request.name.clone(),
+            }
+        };
+        let dir = self.tenant_dir(&request.name);
+
+        let info = TenantInfo {
+            seed: request
+                .seed
+                .unwrap_or_else(|| format!("{}/{}", self.config.seed, request.name)),
+            name: request.name,
+            quotas: request.quotas,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        // Opening the ledger and loading the indexes touches the disk
+        let template = self.config.clone();
+        let tenants_dir = self.dir.clone();
+        let runtime = tokio::runtime::Handle::current();
+        let tenant = tokio::task::spawn_blocking(move || {
+            if dir.exists() {
+                return Err(exists);
+            }
+            std::fs::create_dir_all(&dir)
+                .and_then(|_| {
+                    let tmp = dir.join(format!("{}.tmp", TENANT_FILE));
+                    std::fs::write(&tmp, serde_json::to_vec_pretty(&info)?)?;
+                    std::fs::rename(&tmp, dir.join(TENANT_FILE))
+                })
+                .map_err(|e| ApiError::Storage(format!("Failed to store tenant: {}", e)))?;
+
+            runtime
+                .block_on(Self::start(&template, &tenants_dir, info))
+                .map_err(|e| {
+                    let _ = std::fs::remove_dir_all(&dir);
+                    ApiError::Internal(format!("{:#}", e))
+                })
+        })
+        .await
+        .map_err(|e| ApiError::Internal(format!("Blocking task failed: {}", e)))??;
+
+        let summary = tenant.summary()?;
+        self.tenants
+            .write()
+            .await
+            .insert(summary.info.name.clone(), tenant);
+        drop(reservation);
+        Ok(summary)
+    }
+
+    /// All tenants by name
+    pub async fn list(&self) -> Result<Vec<TenantSummary>> {
+        self.tenants
+            .read()
+            .await
+            .values()
+            .map(Tenant::summary)
+            .collect()
+    }
+
+    /// Look up a tenant
+    pub async fn get(&self, name: &str) -> Result<TenantSummary> {
+        self.tenants
+            .read()
+            .await
+            .get(name)
+            .ok_or_else(|| ApiError::NotFound(format!("Tenant {} not found", name)))?
+            .summary()
+    }
+
+    /// Stop a tenant and delete all of its data
+    ///
+    /// The tenant stops taking requests, then its in-flight requests and
+    /// running jobs finish before the directory is removed. Their results are
+    /// discarded with the tenant.
+    pub async fn delete(&self, name: &str) -> Result<TenantInfo> {
+        let tenant = self
+            .tenants
+            .write()
+            .await
+            .remove(name)
+            .ok_or_else(|| ApiError::NotFound(format!("Tenant {} not found", name)))?;
+        tenant.state.drain();
+        tenant.requests.wait_idle().await;
+        if let Err(e) = tenant.state.shutdown().await {
+            tracing::warn!("Failed to flush deleted tenant {}: {:#}", name, e);
+        }
+        tenant.state.jobs.wait_idle().await;
+
+        let dir = self.tenant_dir(name);
+        tokio::task::spawn_blocking(move || std::fs::remove_dir_all(dir))
+            .await
+            .map_err(|e| ApiError::Internal(format!("Blocking task failed: {}", e)))?
+            .map_err(|e| ApiError::Storage(format!("Failed to delete tenant {}: {}", name, e)))?;
+        Ok(tenant.info)
+    }
+
+    /// Start shutting down every tenant, see [`AppState::drain`]
+    pub async fn drain(&self) {
+        for tenant in self.tenants.read().await.values() {
+            tenant.state.drain();
+        }
+    }
+
+    /// Stop every tenant and flush its state, see [`AppState::shutdown`]
+    pub async fn shutdown(&self) -> anyhow::Result<()> {
+        let mut failed = Vec::new();
+        for (name, tenant) in self.tenants.read().await.iter() {
+            if let Err(e) = tenant.state.shutdown().await {
+                tracing::error!("Failed to shut down tenant {}: {:#}", name, e);
+                failed.push(name.clone());
+            }
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow::anyhow!(
+                "Failed to shut down tenants: {}",
+                failed.join(", ")
+            ))
+        }
+    }
+
+    /// Routes of a tenant, with a guard counting the request until dropped
+    async fn app(&self, name: &str) -> Option<(Router, InFlightGuard)> {
+        self.tenants
+            .read()
+            .await
+            .get(name)
+            .map(|tenant| (tenant.app.clone(), tenant.requests.enter()))
+    }
+}
+
+/// Tenant names are used as directory names and path segments
+fn validate_name(name: &str) -> Result<()> {
+    let valid = !name.is_empty()
+        && name.len() <= MAX_NAME_LEN
+        && name.starts_with(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ApiError::InvalidInput(format!(
+            "Invalid tenant name {:?}: use up to {} lowercase letters, digits, '-' or '_'",
+            name, MAX_NAME_LEN
+        )))
+    }
+}
+
+/// Split `/tenants/<name>/rest` into the name and a URI for `/rest`
+fn split_prefix(uri: &Uri) -> Option<(String, Uri)> {
+    let rest = uri.path().strip_prefix(TENANT_PREFIX)?;
+    let (name, path) = match rest.find('/') {
+        Some(i) => rest.split_at(i),
+        None => (rest, "/"),
+    };
+    if name.is_empty() {
+        return None;
+    }
+
+    let path_and_query = match uri.query() {
+        Some(query) => format!("{}?{}", path, query),
+        None => path.to_string(),
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query = Some(path_and_query.parse().ok()?);
+    Some((name.to_string(), Uri::from_parts(parts).ok()?))
+}
+
+/// Hand requests addressed to a tenant to the tenant's routes
+///
+/// Wraps the router of the default namespace. The path prefix takes
+/// precedence over the tenant a token is bound to; the tenant's routes then
+/// reject tokens bound to another tenant.
+pub async fn dispatch(
+    State(registry): State<Arc<TenantRegistry>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let name = match split_prefix(request.uri()) {
+        Some((name, uri)) => {
+            *request.uri_mut() = uri;
+            Some(name)
+        }
+        None => auth::bearer_token(&request)
+            .and_then(|token| auth::find_token(&registry.config, token))
+            .and_then(|token| token.tenant),
+    };
+    let Some(name) = name else {
+        return next.run(request).await;
+    };
+
+    match registry.app(&name).await {
+        Some((app, _guard)) => app
+            .oneshot(request)
+            .await
+            .unwrap_or_else(|never| match never {}),
+        None => {
+            // Unauthenticated callers cannot probe which tenants exist
+            let denied = registry
+                .config
+                .auth_required
+                .then(|| {
+                    auth::authorize(&registry.config, auth::bearer_token(&request), Scope::Read)
+                        .err()
+                })
+                .flatten();
+            denied
+                .unwrap_or_else(|| ApiError::NotFound(format!("Tenant {} not found", name)))
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::ApiToken;
+    use crate::state::test_config;
+    use axum::{
+        body::{to_bytes, Body},
+        http::{header, StatusCode},
+    };
+    use serde_json::{json, Value as JsonValue};
+
+    const ADMIN: &str = "admin-token";
+
+    async fn server(dir: &tempfile::TempDir) -> (Arc<TenantRegistry>, Router) {
+        let config = ApiConfig {
+            api_token: ADMIN.to_string(),
+            tokens: vec![
+                ApiToken::parse("acme-token:write@acme").unwrap(),
+                ApiToken::parse("beta-token:write@beta").unwrap(),
+            ],
+            ..test_config(dir.path())
+        };
+        let state = AppState::new(config).await.unwrap();
+        let registry = Arc::new(TenantRegistry::open(state.config.clone()).await.unwrap());
+        let app = routes::app(state.clone()).merge(auth::protect(
+            routes::tenants::router(registry.clone()),
+            &state.config,
+            "tenants",
+        ));
+        let app = app.layer(middleware::from_fn_with_state(registry.clone(), dispatch));
+        (registry, app)
+    }
+
+    async fn call(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        token: &str,
+        body: JsonValue,
+    ) -> (StatusCode, JsonValue) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header(header::AUTHORIZATION, format!("Bearer {}", token))
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (
+            status,
+            serde_json::from_slice(&body).unwrap_or(JsonValue::Null),
+        )
+    }
+
+    fn block(tic_id: &str) -> JsonValue {
+        json!({"tic_id": tic_id, "snapshot_id": "snap"})
+    }
+
+    #[test]
+    fn test_split_prefix_and_names() {
+        let uri: Uri = "/tenants/acme/ledger?limit=5".parse().unwrap();
+        let (name, rest) = split_prefix(&uri).unwrap();
+        assert_eq!(name, "acme");
+        assert_eq!(rest, "/ledger?limit=5");
+        assert!(split_prefix(&"/ledger".parse().unwrap()).is_none());
+
+        assert!(validate_name("team-1").is_ok());
+        assert!(validate_name("..").is_err());
+        assert!(validate_name("Team").is_err());
+        assert!(validate_name("").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tenants_are_isolated_and_deleted() {
+        let dir = tempfile::tempdir().unwrap();
+        let (registry, app) = server(&dir).await;
+
+        let (status, created) = call(
+            &app,
+            "POST",
+            "/admin/tenants",
+            ADMIN,
+            json!({"name": "acme", "quotas": {"max_blocks": 1}}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["seed"], "MEF_SEED_42/acme");
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/admin/tenants",
+            ADMIN,
+            json!({"name": "acme"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        // Tenant tokens cannot manage tenants
+        let (status, _) = call(&app, "GET", "/admin/tenants", "acme-token", json!(null)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        // The bound token selects the tenant, the prefix works for global tokens
+        let (status, _) = call(&app, "POST", "/ledger", "acme-token", block("t1")).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "POST", "/tenants/acme/ledger", ADMIN, block("t2")).await;
+        assert_eq!(status, StatusCode::FORBIDDEN, "block quota applies");
+        let (status, _) = call(&app, "POST", "/ledger", ADMIN, block("t3")).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Tokens of other tenants are rejected
+        call(
+            &app,
+            "POST",
+            "/admin/tenants",
+            ADMIN,
+            json!({"name": "beta"}),
+        )
+        .await;
+        let (status, _) = call(
+            &app,
+            "GET",
+            "/tenants/acme/ledger/0",
+            "beta-token",
+            json!(null),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (_, listing) = call(&app, "GET", "/admin/tenants", ADMIN, json!(null)).await;
+        assert_eq!(listing["total"], 2);
+        assert_eq!(listing["tenants"][0]["name"], "acme");
+        assert_eq!(listing["tenants"][0]["usage"]["blocks"], 1);
+        assert_eq!(listing["tenants"][1]["usage"]["blocks"], 0);
+
+        // Tenants are restored on restart
+        let reopened = TenantRegistry::open(registry.config.clone()).await.unwrap();
+        assert_eq!(reopened.get("acme").await.unwrap().usage.blocks, 1);
+
+        let (status, _) = call(&app, "DELETE", "/admin/tenants/acme", ADMIN, json!(null)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!dir.path().join("store/tenants/acme").exists());
+        let (status, _) = call(&app, "GET", "/tenants/acme/ledger/0", ADMIN, json!(null)).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let (status, _) = call(
+            &app,
+            "GET",
+            "/tenants/beta/ledger/0",
+            "acme-token",
+            json!(null),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_concurrent_creates_reserve_the_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let (registry, _app) = server(&dir).await;
+        let create = || {
+            let registry = registry.clone();
+            tokio::spawn(async move {
+                registry
+                    .create(NewTenant {
+                        name: "acme".to_string(),
+                        seed: None,
+                        quotas: Quotas::default(),
+                    })
+                    .await
+            })
+        };
+        let (first, second) = tokio::join!(create(), create());
+        let results = [first.unwrap(), second.unwrap()];
+        assert_eq!(results.iter().filter(|r| r.is_ok()).count(), 1);
+        assert!(results
+            .iter()
+            .any(|r| matches!(r, Err(ApiError::Conflict(_)))));
+        assert_eq!(registry.list().await.unwrap().len(), 1);
+        assert!(registry.creating.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_delete_waits_for_in_flight_requests() {
+        let dir = tempfile::tempdir().unwrap();
+        let (registry, _app) = server(&dir).await;
+        registry
+            .create(NewTenant {
+                name: "acme".to_string(),
+                seed: None,
+                quotas: Quotas::default(),
+            })
+            .await
+            .unwrap();
+        let tenant_dir = dir.path().join("store/tenants/acme");
+
+        let (_, request) = registry.app("acme").await.unwrap();
+        let deleting = tokio::spawn({
+            let registry = registry.clone();
+            async move { registry.delete("acme").await }
+        });
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        assert!(!deleting.is_finished());
+        assert!(tenant_dir.exists());
+
+        drop(request);
+        deleting.await.unwrap().unwrap();
+        assert!(!tenant_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_vector_quota_counts_new_ids() {
+        let dir = tempfile::tempdir().unwrap();
+        let (_registry, app) = server(&dir).await;
+        call(
+            &app,
+            "POST",
+            "/admin/tenants",
+            ADMIN,
+            json!({"name": "acme", "quotas": {"max_vectors": 2}}),
+        )
+        .await;
+
+        let upsert = |ids: &[&str]| {
+            let vectors: Vec<_> = ids
+                .iter()
+                .map(|id| json!({"id": id, "vector": [1.0, 0.0]}))
+                .collect();
+            json!({ "vectors": vectors })
+        };
+        let uri = "/tenants/acme/collections/docs/upsert";
+        let (status, _) = call(&app, "POST", uri, ADMIN, upsert(&["a", "b"])).await;
+        assert_eq!(status, StatusCode::OK);
+        // Replacing stored vectors stays within the quota
+        let (status, _) = call(&app, "POST", uri, ADMIN, upsert(&["a"])).await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = call(&app, "POST", uri, ADMIN, upsert(&["c"])).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        // The default namespace has no quota
+        let (status, _) = call(
+            &app,
+            "POST",
+            "/collections/docs/upsert",
+            ADMIN,
+            upsert(&["c"]),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+}
@@ -0,0 +1,223 @@
+//! Integration tests for the MEF gRPC interface
+//!
+//! Each test starts the gRPC server in-process on an ephemeral local port
+//! and talks to it through the generated client.
+
+use mef_api::auth::ApiToken;
+use mef_api::grpc::{self, proto, proto::mef_client::MefClient};
+use mef_api::{ApiConfig, AppState};
+use mef_vector_db::VectorRecord;
+use std::collections::HashMap;
+use tokio_stream::StreamExt;
+use tonic::{transport::Channel, Code, Request};
+
+const ADMIN_TOKEN: &str = "grpc-test-admin";
+const READ_TOKEN: &str = "grpc-test-reader";
+
+async fn start_server() -> (AppState, MefClient<Channel>) {
+    let config = ApiConfig {
+        api_token: ADMIN_TOKEN.to_string(),
+        tokens: vec![ApiToken::parse(&format!("{}:read", READ_TOKEN)).unwrap()],
+        ..ApiConfig::default()
+    };
+    let state = AppState::new(config).await.unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(grpc::serve(state.clone(), listener));
+
+    let client = MefClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+    (state, client)
+}
+
+fn with_token<T>(message: T, token: &str) -> Request<T> {
+    let mut request = Request::new(message);
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    request
+}
+
+fn ingest_request(data: &str) -> proto::IngestRequest {
+    proto::IngestRequest {
+        data: data.to_string(),
+        data_type: "json".to_string(),
+        seed: "grpc_seed".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_ingest_process_and_ledger_roundtrip() {
+    let (_state, mut client) = start_server().await;
+
+    let ingested = client
+        .ingest(with_token(ingest_request(r#"{"grpc": 1}"#), ADMIN_TOKEN))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!ingested.snapshot_id.is_empty());
+
+    let processed = client
+        .process(with_token(
+            proto::ProcessRequest {
+                snapshot_id: ingested.snapshot_id.clone(),
+                commit: false,
+            },
+            ADMIN_TOKEN,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(!processed.tic_id.is_empty());
+
+    let appended = client
+        .append_block(with_token(
+            proto::AppendBlockRequest {
+                tic_id: processed.tic_id.clone(),
+                snapshot_id: ingested.snapshot_id,
+                expected_previous_hash: None,
+            },
+            ADMIN_TOKEN,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+
+    let block = client
+        .get_block(with_token(
+            proto::GetBlockRequest {
+                index: appended.block_index,
+            },
+            READ_TOKEN,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(block.hash, appended.block_hash);
+    assert_eq!(block.tic_id, processed.tic_id);
+    let json: serde_json::Value = serde_json::from_str(&block.json).unwrap();
+    assert_eq!(json["hash"], appended.block_hash);
+
+    let streamed: Vec<proto::Block> = client
+        .stream_blocks(with_token(
+            proto::StreamBlocksRequest {
+                from: appended.block_index,
+                to: Some(appended.block_index),
+            },
+            READ_TOKEN,
+        ))
+        .await
+        .unwrap()
+        .into_inner()
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+    assert_eq!(streamed.len(), 1);
+    assert_eq!(streamed[0].hash, appended.block_hash);
+
+    let missing = client
+        .get_block(with_token(
+            proto::GetBlockRequest {
+                index: u32::MAX as u64,
+            },
+            READ_TOKEN,
+        ))
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), Code::NotFound);
+}
+
+#[tokio::test]
+async fn test_ingest_stream_answers_each_message() {
+    let (_state, mut client) = start_server().await;
+
+    let messages = tokio_stream::iter(vec![
+        ingest_request(r#"{"stream": 1}"#),
+        ingest_request(r#"{"stream": 2}"#),
+        ingest_request(r#"{"stream": 3}"#),
+    ]);
+    let responses: Vec<proto::IngestResponse> = client
+        .ingest_stream(with_token(messages, ADMIN_TOKEN))
+        .await
+        .unwrap()
+        .into_inner()
+        .collect::<Result<_, _>>()
+        .await
+        .unwrap();
+
+    assert_eq!(responses.len(), 3);
+    assert!(responses.iter().all(|r| !r.snapshot_id.is_empty()));
+}
+
+#[tokio::test]
+async fn test_search_and_gate_evaluation() {
+    let (state, mut client) = start_server().await;
+
+    let collection = format!("grpc_{}", uuid::Uuid::new_v4().simple());
+    state
+        .index_manager
+        .write()
+        .unwrap()
+        .upsert_vectors(
+            &collection,
+            vec![
+                VectorRecord::new("a".to_string(), vec![1.0, 0.0], HashMap::new(), Some(1)),
+                VectorRecord::new("b".to_string(), vec![0.0, 1.0], HashMap::new(), Some(1)),
+            ],
+            Some(1),
+            None,
+        )
+        .unwrap();
+
+    let found = client
+        .search(with_token(
+            proto::SearchRequest {
+                collection: collection.clone(),
+                query_vector: vec![1.0, 0.1],
+                top_k: 1,
+            },
+            ADMIN_TOKEN,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(found.collection, collection);
+    assert_eq!(found.results.len(), 1);
+    assert_eq!(found.results[0].id, "a");
+
+    let gate = client
+        .evaluate_gate(with_token(
+            proto::GateRequest {
+                snapshot_id: "grpc_snapshot".to_string(),
+                tic_candidate_id: "grpc_tic".to_string(),
+                params: None,
+            },
+            ADMIN_TOKEN,
+        ))
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(gate.tic_candidate_id, "grpc_tic");
+    assert!(gate.checks.is_some());
+    assert_eq!(gate.commit, gate.ledger_block_id.is_some());
+}
+
+#[tokio::test]
+async fn test_requests_are_authorized_like_rest() {
+    let (_state, mut client) = start_server().await;
+
+    let anonymous = client
+        .ingest(Request::new(ingest_request("{}")))
+        .await
+        .unwrap_err();
+    assert_eq!(anonymous.code(), Code::Unauthenticated);
+
+    let read_only = client
+        .ingest(with_token(ingest_request("{}"), READ_TOKEN))
+        .await
+        .unwrap_err();
+    assert_eq!(read_only.code(), Code::PermissionDenied);
+}
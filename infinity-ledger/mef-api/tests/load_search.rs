@@ -0,0 +1,171 @@
+//! Load test: search throughput while an index build is running
+//!
+//! Searches share a read lock on the index manager and index builds run on a
+//! snapshot, so a rebuild of a large collection must not stall queries
+//! against it. The test fills a collection, measures search throughput
+//! through the gRPC interface, then keeps searching while a rebuild job runs
+//! and reports the throughput observed during the build.
+
+use mef_api::grpc::{self, proto, proto::mef_client::MefClient};
+use mef_api::jobs::{JobKind, JobStatus};
+use mef_api::{ApiConfig, AppState};
+use mef_vector_db::VectorRecord;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tonic::{transport::Channel, Request};
+
+const TOKEN: &str = "load-test-token";
+const COLLECTION: &str = "load";
+const VECTORS: usize = 10_000;
+const DIMENSIONS: usize = 64;
+const CLIENTS: usize = 4;
+
+async fn start_server(dir: &tempfile::TempDir) -> (AppState, MefClient<Channel>) {
+    let config = ApiConfig {
+        api_token: TOKEN.to_string(),
+        store_path: dir.path().join("store"),
+        ledger_path: dir.path().join("ledger"),
+        ..ApiConfig::default()
+    };
+    let state = AppState::new(config).await.unwrap();
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(grpc::serve(state.clone(), listener));
+
+    let client = MefClient::connect(format!("http://{}", addr))
+        .await
+        .unwrap();
+    (state, client)
+}
+
+/// Deterministic pseudo-random unit-scale vector
+fn vector(seed: usize) -> Vec<f64> {
+    (0..DIMENSIONS)
+        .map(|i| (((seed * 31 + i * 17) % 97) as f64 / 97.0) - 0.5)
+        .collect()
+}
+
+async fn search(client: &mut MefClient<Channel>, seed: usize) {
+    let mut request = Request::new(proto::SearchRequest {
+        collection: COLLECTION.to_string(),
+        query_vector: vector(seed),
+        top_k: 10,
+    });
+    request.metadata_mut().insert(
+        "authorization",
+        format!("Bearer {}", TOKEN).parse().unwrap(),
+    );
+    let response = client.search(request).await.unwrap().into_inner();
+    assert_eq!(response.results.len(), 10);
+}
+
+/// Run `CLIENTS` search loops until `stop` is set; returns completed searches
+async fn search_until(client: &MefClient<Channel>, stop: Arc<AtomicBool>) -> usize {
+    let completed = Arc::new(AtomicUsize::new(0));
+    let loops: Vec<_> = (0..CLIENTS)
+        .map(|worker| {
+            let mut client = client.clone();
+            let stop = stop.clone();
+            let completed = completed.clone();
+            tokio::spawn(async move {
+                let mut seed = worker;
+                while !stop.load(Ordering::SeqCst) {
+                    search(&mut client, seed).await;
+                    // Only count searches that finished while still measuring
+                    if !stop.load(Ordering::SeqCst) {
+                        completed.fetch_add(1, Ordering::SeqCst);
+                    }
+                    seed += CLIENTS;
+                }
+            })
+        })
+        .collect();
+    for handle in loops {
+        handle.await.unwrap();
+    }
+    completed.load(Ordering::SeqCst)
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn test_search_throughput_during_index_build() {
+    let dir = tempfile::tempdir().unwrap();
+    let (state, client) = start_server(&dir).await;
+
+    let records = (0..VECTORS)
+        .map(|i| VectorRecord::new(format!("v{}", i), vector(i), HashMap::new(), Some(1)))
+        .collect();
+    state
+        .index_manager
+        .write()
+        .unwrap()
+        .upsert_vectors(COLLECTION, records, Some(1), None)
+        .unwrap();
+
+    // Warm up the provider, then measure a baseline
+    search(&mut client.clone(), 0).await;
+    let baseline_window = Duration::from_millis(500);
+    let stop = Arc::new(AtomicBool::new(false));
+    let timer = {
+        let stop = stop.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(baseline_window).await;
+            stop.store(true, Ordering::SeqCst);
+        })
+    };
+    let baseline = search_until(&client, stop).await;
+    timer.await.unwrap();
+    let baseline_rate = baseline as f64 / baseline_window.as_secs_f64();
+
+    // Search while a rebuild of the same collection is running
+    let job = state
+        .jobs
+        .submit(
+            JobKind::IndexBuild,
+            &serde_json::json!({ "collection": COLLECTION }),
+            1,
+        )
+        .unwrap();
+    while state.jobs.get(&job.id).unwrap().status == JobStatus::Queued {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    }
+
+    let started = Instant::now();
+    let stop = Arc::new(AtomicBool::new(false));
+    let watcher = {
+        let state = state.clone();
+        let stop = stop.clone();
+        let id = job.id.clone();
+        tokio::spawn(async move {
+            let job = state.jobs.wait(&id).await.unwrap();
+            stop.store(true, Ordering::SeqCst);
+            job
+        })
+    };
+    let during_build = search_until(&client, stop).await;
+    let build_time = started.elapsed();
+    let job = watcher.await.unwrap();
+    assert_eq!(job.status, JobStatus::Completed, "{:?}", job.error);
+
+    let during_rate = during_build as f64 / build_time.as_secs_f64();
+    println!(
+        "search throughput: {:.1}/s idle, {:.1}/s during a {:.0} ms index build \
+         ({} searches, {} clients, {} vectors of {} dimensions)",
+        baseline_rate,
+        during_rate,
+        build_time.as_secs_f64() * 1000.0,
+        during_build,
+        CLIENTS,
+        VECTORS,
+        DIMENSIONS,
+    );
+
+    assert!(baseline > 0);
+    assert!(
+        during_build > 0,
+        "no search completed during a {:?} index build",
+        build_time
+    );
+}
@@ -181,6 +181,15 @@ impl MEFAuditLogger {
         Ok(())
     }
 
+    /// Write buffered events to the event log
+    ///
+    /// Events are otherwise written once `buffer_size` of them are buffered;
+    /// call this before shutting down. Dropping the logger flushes as well,
+    /// ignoring errors.
+    pub fn flush(&mut self) -> Result<()> {
+        self.flush_event_buffer()
+    }
+
     /// Log snapshot creation event
     pub fn log_snapshot_creation(&mut self, snapshot: &serde_json::Value) -> Result<String> {
         let details = serde_json::json!({
@@ -318,6 +327,33 @@ impl MEFAuditLogger {
         Ok(events)
     }
 
+    /// Visit every logged event in order without loading them all
+    ///
+    /// # Arguments
+    /// * `visit` - Called once per event; an error stops the iteration
+    ///
+    /// # Returns
+    /// Number of events visited
+    pub fn for_each_event(
+        &mut self,
+        mut visit: impl FnMut(&AuditEvent) -> Result<()>,
+    ) -> Result<usize> {
+        self.flush_event_buffer()?;
+
+        let mut count = 0;
+        if self.event_log_file.exists() {
+            let reader = BufReader::new(File::open(&self.event_log_file)?);
+            for line in reader.lines() {
+                if let Ok(event) = serde_json::from_str::<AuditEvent>(&line?) {
+                    visit(&event)?;
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
     /// Generate comprehensive audit report
     ///
     /// # Returns
@@ -420,6 +456,14 @@ impl MEFAuditLogger {
     }
 }
 
+impl Drop for MEFAuditLogger {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush_event_buffer() {
+            log::error!("Failed to flush audit events: {}", e);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -505,6 +549,36 @@ mod tests {
         assert!(events.iter().all(|e| e.event_type == "EVENT_A"));
     }
 
+    #[test]
+    fn test_for_each_event_matches_get_events() {
+        let temp_dir = env::temp_dir().join(format!("test_audit_visit_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&temp_dir);
+        let mut logger = MEFAuditLogger::new(&temp_dir).unwrap();
+
+        for i in 0..3 {
+            logger
+                .log_event(
+                    "VISIT_EVENT",
+                    "TestComponent",
+                    serde_json::json!({"index": i}),
+                    EventSeverity::Info,
+                )
+                .unwrap();
+        }
+
+        // Buffered events are flushed before visiting
+        let mut visited = Vec::new();
+        let count = logger
+            .for_each_event(|event| {
+                visited.push(event.details["index"].clone());
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(visited, vec![0, 1, 2]);
+        assert_eq!(logger.get_events(None, None, 10).unwrap().len(), 3);
+    }
+
     #[test]
     fn test_generate_report() {
         let temp_dir = env::temp_dir().join("test_audit_report");
@@ -571,4 +645,30 @@ mod tests {
         let stats = logger.get_statistics().unwrap();
         assert!(stats["event_count"].as_u64().unwrap() >= 1);
     }
+
+    #[test]
+    fn test_flush_and_drop_write_buffered_events() {
+        let temp_dir = env::temp_dir().join(format!("test_audit_drop_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&temp_dir);
+        let read_events = || {
+            fs::read_to_string(temp_dir.join("events.jsonl"))
+                .map(|log| log.lines().count())
+                .unwrap_or(0)
+        };
+
+        let mut logger = MEFAuditLogger::new(&temp_dir).unwrap();
+        logger
+            .log_event("A", "Test", serde_json::json!({}), EventSeverity::Info)
+            .unwrap();
+        assert_eq!(read_events(), 0);
+        logger.flush().unwrap();
+        assert_eq!(read_events(), 1);
+
+        logger
+            .log_event("B", "Test", serde_json::json!({}), EventSeverity::Info)
+            .unwrap();
+        drop(logger);
+        assert_eq!(read_events(), 2);
+        let _ = fs::remove_dir_all(&temp_dir);
+    }
 }
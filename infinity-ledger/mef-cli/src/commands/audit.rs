@@ -32,7 +32,7 @@ pub fn execute(config: &CliConfig, start: usize, export: bool, local: bool) -> R
     } else {
         // Remote API
         let api_url = &config.api_url;
-        let client = reqwest::blocking::Client::new();
+        let client = config.http_client()?;
 
         let request = AuditRequest {
             start_index: start,
@@ -32,7 +32,7 @@ pub fn execute(
 
     // API call
     let api_url = &config.api_url;
-    let client = reqwest::blocking::Client::new();
+    let client = config.http_client()?;
 
     let response = client
         .post(format!("{}/acquisition", api_url))
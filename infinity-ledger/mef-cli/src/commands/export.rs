@@ -1,13 +1,21 @@
 use crate::config::CliConfig;
 /// Export command - export system data
 use anyhow::{Context, Result};
+use mef_ledger::{ArtifactStores, MEFLedger};
 use std::fs;
 use std::path::PathBuf;
 
 pub fn execute(config: &CliConfig, format: &str, output: Option<PathBuf>) -> Result<()> {
+    if format == "archive" {
+        return export_archive(config, output);
+    }
+
     let api_url = &config.api_url;
 
-    let response = reqwest::blocking::get(format!("{}/export/{}", api_url, format))
+    let response = config
+        .http_client()?
+        .get(format!("{}/export/{}", api_url, format))
+        .send()
         .context("Failed to send request to API")?;
 
     if response.status().is_success() {
@@ -37,3 +45,29 @@ pub fn execute(config: &CliConfig, format: &str, output: Option<PathBuf>) -> Res
         std::process::exit(1);
     }
 }
+
+/// Pack the local ledger and its TIC and snapshot artifacts into one archive
+///
+/// # Arguments
+/// * `output` - Archive file to write
+fn export_archive(config: &CliConfig, output: Option<PathBuf>) -> Result<()> {
+    let Some(output_path) = output else {
+        eprintln!("Error: --output is required for archive exports");
+        std::process::exit(1);
+    };
+
+    let ledger = MEFLedger::new(&config.ledger_dir).context("Failed to open local ledger")?;
+    let stores = ArtifactStores {
+        tic_store: Some(config.store_dir.clone()),
+        snapshot_store: Some(config.store_dir.clone()),
+    };
+    let manifest = ledger.export_archive(&output_path, &stores)?;
+
+    println!("✓ Exported to: {:?}", output_path);
+    println!("  Blocks:      {}", manifest.block_count);
+    println!("  Entries:     {}", manifest.entries.len());
+    println!("  Head:        {}", manifest.head_hash);
+    println!("  Merkle root: {}", manifest.merkle_root);
+    println!("  Root hash:   {}", manifest.root_hash);
+    Ok(())
+}
@@ -3,7 +3,7 @@ use crate::config::CliConfig;
 use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize)]
 struct IngestRequest {
@@ -26,21 +26,7 @@ pub fn execute(
     seed: &str,
     local: bool,
 ) -> Result<()> {
-    // Read file
-    let data = if data_type == "binary" {
-        let bytes = fs::read(&file_path)
-            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
-        serde_json::Value::String(base64::encode(&bytes))
-    } else {
-        let contents = fs::read_to_string(&file_path)
-            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
-
-        if data_type == "json" {
-            serde_json::from_str(&contents).with_context(|| "Failed to parse JSON file")?
-        } else {
-            serde_json::Value::String(contents)
-        }
-    };
+    let data = read_payload(&file_path, data_type)?;
 
     if local {
         // Local processing
@@ -53,7 +39,7 @@ pub fn execute(
     } else {
         // Remote API processing
         let api_url = &config.api_url;
-        let client = reqwest::blocking::Client::new();
+        let client = config.http_client()?;
 
         let request = IngestRequest {
             data,
@@ -81,6 +67,28 @@ pub fn execute(
     }
 }
 
+/// Read an input file as the JSON payload sent for ingestion
+///
+/// # Arguments
+/// * `file_path` - Input file
+/// * `data_type` - `binary` is base64-encoded, `json` parsed, anything else kept as text
+pub(crate) fn read_payload(file_path: &Path, data_type: &str) -> Result<serde_json::Value> {
+    if data_type == "binary" {
+        let bytes =
+            fs::read(file_path).with_context(|| format!("Failed to read file: {:?}", file_path))?;
+        Ok(serde_json::Value::String(base64::encode(&bytes)))
+    } else {
+        let contents = fs::read_to_string(file_path)
+            .with_context(|| format!("Failed to read file: {:?}", file_path))?;
+
+        if data_type == "json" {
+            serde_json::from_str(&contents).with_context(|| "Failed to parse JSON file")
+        } else {
+            Ok(serde_json::Value::String(contents))
+        }
+    }
+}
+
 // Base64 encoding helper (simple implementation)
 mod base64 {
     pub fn encode(data: &[u8]) -> String {
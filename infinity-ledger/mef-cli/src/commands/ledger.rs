@@ -1,7 +1,13 @@
 use crate::config::CliConfig;
 /// Ledger command - SPEC-002 ledger operations
 use anyhow::{Context, Result};
+use mef_ledger::{
+    apply_fast_forward, compare_ledgers, plan_reconciliation, ArtifactStores, BlockSource,
+    BlockSummary, FailureKind, HashScheme, KeyRegistry, LedgerRelation, LedgerSnapshot, MEFLedger,
+    MefBlock, SyncAction, VerifyOptions,
+};
 use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Serialize)]
 #[allow(dead_code)]
@@ -19,7 +25,7 @@ struct AppendResponse {
 
 pub fn append(config: &CliConfig, tic: &str, snapshot: &str) -> Result<()> {
     let api_url = &config.api_url;
-    let client = reqwest::blocking::Client::new();
+    let client = config.http_client()?;
 
     let response = client
         .post(format!("{}/ledger", api_url))
@@ -40,9 +46,395 @@ pub fn append(config: &CliConfig, tic: &str, snapshot: &str) -> Result<()> {
     }
 }
 
-pub fn verify(_config: &CliConfig) -> Result<()> {
-    // Local ledger verification
-    println!("Local ledger verification not yet fully implemented");
-    eprintln!("Error: Requires full MEF ledger implementation");
-    std::process::exit(1);
+/// Verify the local ledger and print every failing block
+///
+/// # Arguments
+/// * `json` - Print the report as JSON
+/// * `threads` - Worker threads (`0` uses all cores)
+pub fn verify(config: &CliConfig, json: bool, threads: usize) -> Result<()> {
+    let ledger = MEFLedger::new(&config.ledger_dir).context("Failed to open local ledger")?;
+    let options = VerifyOptions {
+        threads,
+        ..VerifyOptions::default()
+    };
+    let report = ledger.verify_chain_parallel(&options, |progress| {
+        if !json && progress.total > 0 {
+            eprint!(
+                "\r  Verified {}/{} blocks",
+                progress.checked, progress.total
+            );
+        }
+    })?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        if report.blocks_checked > 0 {
+            eprintln!();
+        }
+        if !report.failures.is_empty() {
+            println!("{:>8}  {:<24}  DETAIL", "INDEX", "FAILURE");
+            for failure in &report.failures {
+                println!(
+                    "{:>8}  {:<24}  {}",
+                    failure.index,
+                    failure_kind_name(failure.kind),
+                    failure.detail
+                );
+            }
+        }
+        if let Some(point) = &report.resume_point {
+            println!("  Last verified checkpoint: #{}", point.checkpoint_index);
+        }
+    }
+
+    if report.is_ok() {
+        if !json {
+            println!("✓ Ledger verified: {} blocks", report.blocks_checked);
+        }
+        Ok(())
+    } else {
+        eprintln!(
+            "Error: {} failures in {} blocks",
+            report.failures.len(),
+            report.blocks_checked
+        );
+        std::process::exit(1);
+    }
+}
+
+fn failure_kind_name(kind: FailureKind) -> String {
+    serde_json::to_value(kind)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", kind))
+}
+
+/// Print a single block of the local ledger
+///
+/// # Arguments
+/// * `index` - Block index
+pub fn show(config: &CliConfig, index: i32) -> Result<()> {
+    let ledger = LedgerSnapshot::open(&config.ledger_dir).context("Failed to open local ledger")?;
+    match ledger.get_block(index)? {
+        Some(block) => {
+            println!("{}", serde_json::to_string_pretty(&block)?);
+            Ok(())
+        }
+        None => {
+            eprintln!("Error: Block #{} not found", index);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// List block summaries of the local ledger
+///
+/// # Arguments
+/// * `from` - First index to list
+/// * `to` - Last index to list (inclusive)
+/// * `json` - Print the summaries as JSON
+pub fn list(config: &CliConfig, from: Option<i32>, to: Option<i32>, json: bool) -> Result<()> {
+    let ledger = LedgerSnapshot::open(&config.ledger_dir).context("Failed to open local ledger")?;
+    let blocks: Vec<&BlockSummary> = ledger
+        .index()
+        .blocks
+        .iter()
+        .filter(|b| from.is_none_or(|from| b.index >= from))
+        .filter(|b| to.is_none_or(|to| b.index <= to))
+        .collect();
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&blocks)?);
+        return Ok(());
+    }
+
+    println!(
+        "{:>8}  {:<16}  {:<28}  {:<24}  FLAGS",
+        "INDEX", "HASH", "TIMESTAMP", "TIC"
+    );
+    for block in &blocks {
+        let mut flags = Vec::new();
+        if let Some(up_to) = block.checkpoint {
+            flags.push(format!("checkpoint(..={})", up_to));
+        }
+        if block.pruned {
+            flags.push("pruned".to_string());
+        }
+        println!(
+            "{:>8}  {:<16}  {:<28}  {:<24}  {}",
+            block.index,
+            &block.hash[..block.hash.len().min(16)],
+            block.timestamp,
+            block.tic_id,
+            flags.join(",")
+        );
+    }
+    println!("  {} of {} blocks", blocks.len(), ledger.len());
+    Ok(())
+}
+
+/// Compare two ledger directories block by block
+///
+/// # Arguments
+/// * `a` - First ledger directory
+/// * `b` - Second ledger directory
+/// * `json` - Print the comparison as JSON
+pub fn diff(a: &Path, b: &Path, json: bool) -> Result<()> {
+    let left = LedgerSnapshot::open(a).with_context(|| format!("Failed to open ledger {:?}", a))?;
+    let right =
+        LedgerSnapshot::open(b).with_context(|| format!("Failed to open ledger {:?}", b))?;
+    let report = compare_ledgers(&left, &right)?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        println!("A height:      {}", report.local_height);
+        println!("B height:      {}", report.remote_height);
+        println!("Common prefix: {}", report.common_prefix_len);
+        if let Some(hash) = &report.common_hash {
+            println!("Common head:   {}", hash);
+        }
+        for (side, blocks) in [("A", &report.local_only), ("B", &report.remote_only)] {
+            for block in blocks {
+                println!(
+                    "  {} #{} {} ({})",
+                    side, block.index, block.hash, block.tic_id
+                );
+            }
+        }
+    }
+
+    match report.relation {
+        LedgerRelation::Identical => {
+            if !json {
+                println!("✓ Ledgers are identical");
+            }
+            Ok(())
+        }
+        LedgerRelation::LocalBehind | LedgerRelation::LocalAhead => {
+            let (shorter, longer) = if report.relation == LedgerRelation::LocalBehind {
+                ("A", "B")
+            } else {
+                ("B", "A")
+            };
+            eprintln!("Error: {} is a prefix of {}", shorter, longer);
+            std::process::exit(1);
+        }
+        LedgerRelation::Diverged => {
+            eprintln!(
+                "Error: Ledgers diverged at block #{}",
+                report.fork_index.unwrap_or_default()
+            );
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Re-verify every block hash of the local ledger under its recorded scheme
+///
+/// # Arguments
+/// * `json` - Print the report as JSON
+pub fn verify_hashes(config: &CliConfig, json: bool) -> Result<()> {
+    let ledger = MEFLedger::new(&config.ledger_dir).context("Failed to open local ledger")?;
+    let report = ledger.verify_hash_schemes()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for (version, count) in &report.blocks_by_version {
+            let name = HashScheme::from_version(*version)
+                .map(|scheme| format!("{:?}", scheme))
+                .unwrap_or_else(|_| "unsupported".to_string());
+            println!("  Scheme {} ({}): {} blocks", version, name, count);
+        }
+        if report.pruned > 0 {
+            println!("  Pruned (checked via checkpoints): {}", report.pruned);
+        }
+        for failure in &report.failures {
+            eprintln!(
+                "  #{} [scheme {}] {}",
+                failure.index, failure.version, failure.reason
+            );
+        }
+    }
+
+    if report.is_ok() {
+        if !json {
+            println!("✓ All block hashes reproduced");
+        }
+        Ok(())
+    } else {
+        eprintln!(
+            "Error: {} blocks failed hash verification",
+            report.failures.len()
+        );
+        std::process::exit(1);
+    }
+}
+
+/// Page of `GET /ledger/summaries`
+#[derive(Debug, Deserialize)]
+struct SummaryPage {
+    items: Vec<BlockSummary>,
+    next_cursor: Option<String>,
+}
+
+/// Ledger replica served by a running MEF API
+struct HttpBlockSource {
+    api_url: String,
+    client: reqwest::blocking::Client,
+}
+
+impl HttpBlockSource {
+    fn get(&self, path: &str) -> Result<reqwest::blocking::Response> {
+        self.client
+            .get(format!("{}{}", self.api_url.trim_end_matches('/'), path))
+            .send()
+            .context("Failed to send request to API")
+    }
+}
+
+impl BlockSource for HttpBlockSource {
+    fn summaries(&self) -> Result<Vec<BlockSummary>> {
+        let mut summaries = Vec::new();
+        let mut path = "/ledger/summaries?limit=1000".to_string();
+        loop {
+            let page: SummaryPage = self
+                .get(&path)?
+                .error_for_status()?
+                .json()
+                .context("Invalid block summaries")?;
+            summaries.extend(page.items);
+            match page.next_cursor {
+                Some(cursor) => path = format!("/ledger/summaries?limit=1000&cursor={}", cursor),
+                None => return Ok(summaries),
+            }
+        }
+    }
+
+    fn block(&self, index: i32) -> Result<Option<MefBlock>> {
+        let response = self.get(&format!("/ledger/{}", index))?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(
+            response
+                .error_for_status()?
+                .json()
+                .context("Invalid block")?,
+        ))
+    }
+
+    fn key_registry(&self) -> Result<KeyRegistry> {
+        let response = self.get("/ledger/keys")?;
+        response
+            .error_for_status()?
+            .json()
+            .context("Invalid key registry")
+    }
+}
+
+/// Compare the local ledger with another replica and fast-forward if possible
+///
+/// # Arguments
+/// * `source` - Ledger directory or API URL of the other replica
+/// * `dry_run` - Only print the reconciliation plan
+pub fn sync(config: &CliConfig, source: &str, dry_run: bool) -> Result<()> {
+    let mut local = MEFLedger::new(&config.ledger_dir).context("Failed to open local ledger")?;
+
+    let remote: Box<dyn BlockSource> =
+        if source.starts_with("http://") || source.starts_with("https://") {
+            Box::new(HttpBlockSource {
+                api_url: source.to_string(),
+                client: config.http_client()?,
+            })
+        } else {
+            Box::new(LedgerSnapshot::open(source).context("Failed to open source ledger")?)
+        };
+
+    let plan = plan_reconciliation(&local, remote.as_ref())?;
+    let report = &plan.report;
+    println!("Local height:  {}", report.local_height);
+    println!("Remote height: {}", report.remote_height);
+    println!("Common prefix: {}", report.common_prefix_len);
+
+    match plan.action {
+        SyncAction::None => {
+            println!("✓ Ledgers are identical");
+            Ok(())
+        }
+        SyncAction::FastForward {
+            from_index,
+            to_index,
+        } => {
+            if dry_run {
+                println!(
+                    "Fast-forward possible: blocks #{}..#{}",
+                    from_index, to_index
+                );
+                return Ok(());
+            }
+            let imported = apply_fast_forward(&mut local, remote.as_ref(), &plan)?;
+            println!("✓ Imported {} blocks", imported);
+            println!("  Head: {}", local.get_last_hash()?);
+            Ok(())
+        }
+        SyncAction::PushToRemote {
+            from_index,
+            to_index,
+        } => {
+            println!(
+                "Local ledger is ahead: blocks #{}..#{} are missing on the source",
+                from_index, to_index
+            );
+            Ok(())
+        }
+        SyncAction::ManualResolution { fork_index } => {
+            eprintln!("Error: Ledgers diverged at block #{}", fork_index);
+            if let Some(hash) = &report.common_hash {
+                eprintln!("  Last common hash: {}", hash);
+            }
+            for block in &report.local_only {
+                eprintln!(
+                    "  local  #{} {} ({})",
+                    block.index, block.hash, block.tic_id
+                );
+            }
+            for block in &report.remote_only {
+                eprintln!(
+                    "  remote #{} {} ({})",
+                    block.index, block.hash, block.tic_id
+                );
+            }
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Restore a ledger from an archive written by `mef export --format archive`
+///
+/// # Arguments
+/// * `archive` - Archive file
+/// * `dest` - Destination ledger directory (defaults to the configured one)
+pub fn import(config: &CliConfig, archive: &Path, dest: Option<PathBuf>) -> Result<()> {
+    let dest = dest.unwrap_or_else(|| config.ledger_dir.clone());
+    let stores = ArtifactStores {
+        tic_store: Some(config.store_dir.clone()),
+        snapshot_store: Some(config.store_dir.clone()),
+    };
+
+    match MEFLedger::import_archive(archive, &dest, &stores) {
+        Ok((_, manifest)) => {
+            println!("✓ Archive verified and imported to: {:?}", dest);
+            println!("  Blocks:    {}", manifest.block_count);
+            println!("  Head:      {}", manifest.head_hash);
+            println!("  Root hash: {}", manifest.root_hash);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("Error: Archive rejected: {:#}", e);
+            std::process::exit(1);
+        }
+    }
 }
@@ -6,5 +6,6 @@ pub mod ingest;
 pub mod ledger;
 pub mod ping;
 pub mod process;
+pub mod run;
 pub mod solve;
 pub mod validate;
@@ -13,7 +13,11 @@ struct PingResponse {
 pub fn execute(config: &CliConfig) -> Result<()> {
     let api_url = &config.api_url;
 
-    match reqwest::blocking::get(format!("{}/ping", api_url)) {
+    match config
+        .http_client()?
+        .get(format!("{}/ping", api_url))
+        .send()
+    {
         Ok(response) if response.status().is_success() => {
             let data: PingResponse = response.json()?;
             println!("✓ API server is operational");
@@ -29,7 +29,7 @@ pub fn execute(config: &CliConfig, snapshot_id: &str, commit: bool, local: bool)
     } else {
         // Remote API processing
         let api_url = &config.api_url;
-        let client = reqwest::blocking::Client::new();
+        let client = config.http_client()?;
 
         let request = ProcessRequest {
             snapshot_id: snapshot_id.to_string(),
@@ -0,0 +1,153 @@
+use crate::commands::ingest::read_payload;
+use crate::config::CliConfig;
+/// Run command - complete MEF pipeline in-process, without the API server
+use anyhow::{Context, Result};
+use mef_core::gates::merkaba_gate::{MerkabaGate, TICCandidate};
+use mef_ingestion::TritonCore;
+use mef_ledger::MEFLedger;
+use mef_solvecoagula::{SolveCoagula, SolveCoagulaConfig};
+use mef_spiral::{SpiralConfig, SpiralSnapshot};
+use mef_tic::{TICConfig, TICCrystallizer};
+use ndarray::Array1;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Operators applied by Solve-Coagula, in order
+const OPERATOR_SEQUENCE: [&str; 4] = ["DK", "SW", "PI", "WT"];
+
+/// Options for a local pipeline run
+pub struct RunOptions {
+    pub data_type: String,
+    pub seed: Option<String>,
+    pub store: Option<PathBuf>,
+    pub ledger: Option<PathBuf>,
+    pub force: bool,
+}
+
+pub fn execute(config: &CliConfig, file_path: &Path, options: RunOptions) -> Result<()> {
+    let seed = options.seed.unwrap_or_else(|| config.seed.clone());
+    let store_dir = options.store.unwrap_or_else(|| config.store_dir.clone());
+    let ledger_dir = options.ledger.unwrap_or_else(|| config.ledger_dir.clone());
+    let data = read_payload(file_path, &options.data_type)?;
+
+    // 1. Triton normalization
+    let triton = TritonCore::new(HashMap::from([(
+        "seed".to_string(),
+        serde_json::Value::String(seed.clone()),
+    )]));
+    let normalized = triton.normalize(&data, &options.data_type)?;
+    println!("✓ Normalized: {} bytes", normalized.metadata.size);
+    println!("  Hash: {}", normalized.metadata.hash);
+
+    // 2. Spiral snapshot
+    let spiral_config = SpiralConfig {
+        r: config.spiral.r,
+        a: config.spiral.a,
+        b: config.spiral.b,
+        c: config.spiral.c,
+        k: config.spiral.k,
+        step: config.spiral.step,
+        ..SpiralConfig::default()
+    };
+    let spiral = SpiralSnapshot::new(spiral_config, &store_dir)?;
+    let snapshot = spiral.create_snapshot(&normalized.normalized_payload, &seed, None)?;
+    spiral.save_snapshot(&snapshot)?;
+    let snapshot_json = serde_json::to_value(&snapshot)?;
+    println!("✓ Snapshot created: {}", snapshot.id);
+    println!("  Phase: {:.4}", snapshot.phase);
+    println!("  PoR: {}", snapshot.metrics.por);
+
+    // 3. Solve-Coagula fixpoint
+    let solver = SolveCoagula::new(SolveCoagulaConfig {
+        lambda: config.solvecoagula.lambda,
+        eps: config.solvecoagula.eps,
+        max_iter: config.solvecoagula.max_iter,
+        ..SolveCoagulaConfig::default()
+    })?;
+    let (fixpoint, info) =
+        solver.iterate_to_fixpoint(&Array1::from_vec(snapshot.coordinates.clone()), true)?;
+    println!("✓ Fixpoint: converged={}", info.converged);
+    println!("  Iterations: {}", info.iterations);
+
+    // 4. TIC crystallization
+    let crystallizer = TICCrystallizer::new(TICConfig::default(), &store_dir)?;
+    let tic = crystallizer.create_tic(
+        &fixpoint,
+        &snapshot.id,
+        &snapshot.seed,
+        &serde_json::to_value(&info)?,
+        &snapshot_json,
+    )?;
+    crystallizer.save_tic(&tic)?;
+    println!("✓ TIC created: {}", tic.tic_id);
+
+    // 5. Merkaba gate, with the Lyapunov history seeded from committed TICs
+    let mut ledger = MEFLedger::new(&ledger_dir).context("Failed to open local ledger")?;
+    let mut gate = MerkabaGate::new(store_dir.join("merkaba_audit.jsonl"));
+    gate.state_history = recent_fixpoints(&ledger, &crystallizer, gate.lyapunov_window - 1)?;
+    let event = gate.run_merkaba(
+        snapshot.id.clone(),
+        TICCandidate {
+            tic_id: tic.tic_id.clone(),
+            fixpoint: tic.fixpoint.clone(),
+            por_status: tic.proof.por.clone(),
+            operator_sequence: OPERATOR_SEQUENCE.iter().map(|op| op.to_string()).collect(),
+            timestamp: unix_timestamp(),
+            dual_fixpoint: None,
+        },
+        None,
+        None,
+        None,
+    );
+    println!("✓ Gate evaluated: {}", event.gate_id);
+    println!("  Phi: {:.4}", event.checks.phi);
+    println!("  ΔPI: {:.6}", event.checks.delta_pi);
+    println!("  ΔV: {:.6}", event.checks.delta_v);
+    println!("  Decision: {}", event.decision.reason);
+
+    if !event.decision.commit && !options.force {
+        eprintln!(
+            "Error: Merkaba gate rejected TIC {} ({}); artifacts kept in {:?}",
+            tic.tic_id, event.decision.reason, store_dir
+        );
+        std::process::exit(1);
+    }
+
+    // 6. Ledger append
+    let block = ledger.append_block(&serde_json::to_value(&tic)?, &snapshot_json)?;
+    println!("✓ Committed to ledger: Block #{}", block.index);
+    println!("{}", block.hash);
+    Ok(())
+}
+
+/// Load the fixpoints of the most recently committed TICs, oldest first
+///
+/// # Arguments
+/// * `limit` - Maximum number of fixpoints
+fn recent_fixpoints(
+    ledger: &MEFLedger,
+    crystallizer: &TICCrystallizer,
+    limit: usize,
+) -> Result<Vec<Array1<f64>>> {
+    let mut fixpoints = Vec::new();
+    for block in ledger.index().blocks.iter().rev() {
+        if fixpoints.len() >= limit {
+            break;
+        }
+        if block.checkpoint.is_some() {
+            continue;
+        }
+        if let Some(tic) = crystallizer.load_tic(&block.tic_id)? {
+            fixpoints.push(Array1::from_vec(tic.fixpoint));
+        }
+    }
+    fixpoints.reverse();
+    Ok(fixpoints)
+}
+
+fn unix_timestamp() -> f64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs_f64())
+        .unwrap_or(0.0)
+}
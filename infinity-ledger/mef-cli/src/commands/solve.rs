@@ -15,7 +15,8 @@ struct SolveResponse {
 pub fn execute(config: &CliConfig, snapshot: &str, output_file: Option<PathBuf>) -> Result<()> {
     let api_url = &config.api_url;
 
-    let response = reqwest::blocking::Client::new()
+    let response = config
+        .http_client()?
         .post(format!("{}/solve?snapshot_id={}", api_url, snapshot))
         .send()
         .context("Failed to send request to API")?;
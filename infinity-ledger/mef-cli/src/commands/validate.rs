@@ -35,7 +35,8 @@ pub fn execute(config: &CliConfig, snapshot_id: &str, local: bool) -> Result<()>
         // Remote API
         let api_url = &config.api_url;
 
-        let response = reqwest::blocking::Client::new()
+        let response = config
+            .http_client()?
             .post(format!("{}/validate/snapshot/{}", api_url, snapshot_id))
             .send()
             .context("Failed to send request to API")?;
@@ -13,6 +13,9 @@ pub struct CliConfig {
     pub solvecoagula: SolveCoagulaConfig,
     pub store_dir: PathBuf,
     pub ledger_dir: PathBuf,
+    /// Bearer token sent to the API server
+    #[serde(skip)]
+    pub api_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -100,6 +103,7 @@ impl CliConfig {
                 ledger_dir: std::env::var("MEF_LEDGER_DIR")
                     .map(PathBuf::from)
                     .unwrap_or(default_ledger),
+                api_token: None,
             })
         } else {
             eprintln!(
@@ -118,7 +122,23 @@ impl CliConfig {
                 ledger_dir: std::env::var("MEF_LEDGER_DIR")
                     .map(PathBuf::from)
                     .unwrap_or(default_ledger),
+                api_token: None,
             })
         }
     }
+
+    /// HTTP client that authenticates with the configured API token
+    pub fn http_client(&self) -> Result<reqwest::blocking::Client> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        if let Some(token) = &self.api_token {
+            let mut value = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
+                .context("Invalid API token")?;
+            value.set_sensitive(true);
+            headers.insert(reqwest::header::AUTHORIZATION, value);
+        }
+        reqwest::blocking::Client::builder()
+            .default_headers(headers)
+            .build()
+            .context("Failed to create HTTP client")
+    }
 }
@@ -28,6 +28,10 @@ struct Cli {
     #[arg(long, env = "MEF_API_URL", default_value = DEFAULT_API_URL)]
     api_url: String,
 
+    /// Bearer token for the API server
+    #[arg(long, env = "MEF_API_TOKEN", hide_env_values = true)]
+    api_token: Option<String>,
+
     #[command(subcommand)]
     command: Commands,
 }
@@ -93,8 +97,8 @@ enum Commands {
 
     /// Export system data
     Export {
-        /// Export format
-        #[arg(short, long, value_parser = ["json", "audit"], default_value = "json")]
+        /// Export format (`archive` packs the local ledger into one verifiable file)
+        #[arg(short, long, value_parser = ["json", "audit", "archive"], default_value = "json")]
         format: String,
 
         /// Output file path
@@ -128,6 +132,32 @@ enum Commands {
         output_file: Option<PathBuf>,
     },
 
+    /// Run the complete pipeline in-process and append the TIC to the local ledger
+    Run {
+        /// Input file
+        file_path: PathBuf,
+
+        /// Data type
+        #[arg(short = 't', long, value_parser = ["text", "json", "numeric", "binary", "raw"], default_value = "raw")]
+        data_type: String,
+
+        /// Deterministic seed (defaults to the configured seed)
+        #[arg(short, long)]
+        seed: Option<String>,
+
+        /// Store directory for snapshots, TICs and the gate audit log
+        #[arg(long)]
+        store: Option<PathBuf>,
+
+        /// Ledger directory
+        #[arg(long)]
+        ledger: Option<PathBuf>,
+
+        /// Append even if the Merkaba gate rejects the TIC
+        #[arg(long)]
+        force: bool,
+    },
+
     /// SPEC-002: Ledger commands
     Ledger {
         #[command(subcommand)]
@@ -152,15 +182,85 @@ enum LedgerCommands {
     },
 
     /// Verify ledger integrity
-    Verify,
+    Verify {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+
+        /// Worker threads (0 uses all cores)
+        #[arg(long, default_value_t = 0)]
+        threads: usize,
+    },
+
+    /// Print a single block
+    Show {
+        /// Block index
+        index: i32,
+    },
+
+    /// List block summaries
+    List {
+        /// First index to list
+        #[arg(long)]
+        from: Option<i32>,
+
+        /// Last index to list (inclusive)
+        #[arg(long)]
+        to: Option<i32>,
+
+        /// Print the summaries as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Compare two ledger directories
+    Diff {
+        /// First ledger directory
+        a: PathBuf,
+
+        /// Second ledger directory
+        b: PathBuf,
+
+        /// Print the comparison as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Recompute every block hash under its recorded hashing scheme
+    VerifyHashes {
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// Reconcile the local ledger with another replica
+    Sync {
+        /// Ledger directory or API URL of the other replica
+        source: String,
+
+        /// Only show the reconciliation plan
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Verify and restore a ledger archive
+    Import {
+        /// Archive written by `mef export --format archive`
+        archive: PathBuf,
+
+        /// Destination ledger directory (defaults to the configured ledger)
+        #[arg(long)]
+        dest: Option<PathBuf>,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     // Load configuration
-    let config =
+    let mut config =
         CliConfig::load(&cli.config, &cli.api_url).context("Failed to load configuration")?;
+    config.api_token = cli.api_token;
 
     // Execute command
     match cli.command {
@@ -200,11 +300,43 @@ fn main() -> Result<()> {
             output_file,
         } => commands::solve::execute(&config, &snapshot, output_file),
 
+        Commands::Run {
+            file_path,
+            data_type,
+            seed,
+            store,
+            ledger,
+            force,
+        } => commands::run::execute(
+            &config,
+            &file_path,
+            commands::run::RunOptions {
+                data_type,
+                seed,
+                store,
+                ledger,
+                force,
+            },
+        ),
         Commands::Ledger { subcommand } => match subcommand {
             LedgerCommands::Append { tic, snapshot } => {
                 commands::ledger::append(&config, &tic, &snapshot)
             }
-            LedgerCommands::Verify => commands::ledger::verify(&config),
+            LedgerCommands::Verify { json, threads } => {
+                commands::ledger::verify(&config, json, threads)
+            }
+            LedgerCommands::Show { index } => commands::ledger::show(&config, index),
+            LedgerCommands::List { from, to, json } => {
+                commands::ledger::list(&config, from, to, json)
+            }
+            LedgerCommands::Diff { a, b, json } => commands::ledger::diff(&a, &b, json),
+            LedgerCommands::VerifyHashes { json } => commands::ledger::verify_hashes(&config, json),
+            LedgerCommands::Sync { source, dry_run } => {
+                commands::ledger::sync(&config, &source, dry_run)
+            }
+            LedgerCommands::Import { archive, dest } => {
+                commands::ledger::import(&config, &archive, dest)
+            }
         },
 
         Commands::Ping => commands::ping::execute(&config),
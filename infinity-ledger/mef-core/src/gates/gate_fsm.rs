@@ -0,0 +1,378 @@
+/*!
+ * Gate Finite-State Machine
+ *
+ * Tracks the lifecycle of Merkaba gate evaluations:
+ *
+ * idle → evaluating → fire | hold → cooldown → idle
+ *
+ * The FSM is fed by `MerkabaGate::run_merkaba` through the `GateObserver`
+ * hooks and persisted as JSON after every transition, so the last decision
+ * and its checks survive restarts.
+ */
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use super::merkaba_gate::{GateEvent, GateObserver};
+
+/// State of the gate FSM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GateState {
+    /// No evaluation in progress
+    Idle,
+    /// Checks are being computed
+    Evaluating,
+    /// The last evaluation committed
+    Fire,
+    /// The last evaluation was rejected
+    Hold,
+    /// Settling after a decision
+    Cooldown,
+}
+
+/// A recorded state transition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GateTransition {
+    pub from: GateState,
+    pub to: GateState,
+    /// RFC 3339 timestamp
+    pub at: String,
+    pub reason: String,
+    /// Gate event that caused the transition, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gate_id: Option<String>,
+}
+
+/// Persisted FSM state
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GateFsmSnapshot {
+    pub state: GateState,
+    /// Time of the last transition (RFC 3339)
+    pub since: String,
+    /// End of the current cooldown (RFC 3339)
+    #[serde(default)]
+    pub cooldown_until: Option<String>,
+    /// Most recent decision with its PoR, ΔPI, Φ, ΔV and MCI checks
+    #[serde(default)]
+    pub last_decision: Option<GateEvent>,
+    /// HOLD decisions since the last FIRE
+    #[serde(default)]
+    pub consecutive_holds: u64,
+    /// Completed evaluations
+    #[serde(default)]
+    pub evaluations: u64,
+    /// Most recent transitions, oldest first
+    #[serde(default)]
+    pub history: VecDeque<GateTransition>,
+}
+
+impl GateFsmSnapshot {
+    fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            state: GateState::Idle,
+            since: now.to_rfc3339(),
+            cooldown_until: None,
+            last_decision: None,
+            consecutive_holds: 0,
+            evaluations: 0,
+            history: VecDeque::new(),
+        }
+    }
+}
+
+/// Gate FSM persisted to a JSON file
+#[derive(Debug)]
+pub struct GateFsm {
+    path: PathBuf,
+    cooldown: Duration,
+    history_limit: usize,
+    inner: Mutex<GateFsmSnapshot>,
+}
+
+impl GateFsm {
+    /// Default time spent in cooldown after a decision
+    pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(5);
+    /// Default number of retained transitions
+    pub const DEFAULT_HISTORY_LIMIT: usize = 256;
+
+    /// Open the FSM persisted at `path`, starting idle if it does not exist
+    ///
+    /// An evaluation interrupted by a restart is recorded as a transition
+    /// back to idle.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - JSON file holding the FSM state
+    /// * `cooldown` - Time spent in cooldown after each decision
+    pub fn open(path: impl AsRef<Path>, cooldown: Duration) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let now = Utc::now();
+        let snapshot = match std::fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse gate FSM state: {:?}", path))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => GateFsmSnapshot::new(now),
+            Err(e) => {
+                return Err(e).with_context(|| format!("Failed to read gate FSM state: {:?}", path))
+            }
+        };
+
+        let fsm = Self {
+            path,
+            cooldown,
+            history_limit: Self::DEFAULT_HISTORY_LIMIT,
+            inner: Mutex::new(snapshot),
+        };
+        {
+            let mut inner = fsm.lock();
+            if inner.state == GateState::Evaluating {
+                fsm.transition(
+                    &mut inner,
+                    GateState::Idle,
+                    now,
+                    "restarted during evaluation".to_string(),
+                    None,
+                );
+            }
+            fsm.persist(&inner)?;
+        }
+        Ok(fsm)
+    }
+
+    /// Current state, with an elapsed cooldown resolved to idle
+    pub fn snapshot(&self) -> GateFsmSnapshot {
+        let mut inner = self.lock();
+        self.expire_cooldown(&mut inner, Utc::now());
+        inner.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, GateFsmSnapshot> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn transition(
+        &self,
+        inner: &mut GateFsmSnapshot,
+        to: GateState,
+        at: DateTime<Utc>,
+        reason: String,
+        gate_id: Option<String>,
+    ) {
+        let at = at.to_rfc3339();
+        inner.history.push_back(GateTransition {
+            from: inner.state,
+            to,
+            at: at.clone(),
+            reason,
+            gate_id,
+        });
+        while inner.history.len() > self.history_limit {
+            inner.history.pop_front();
+        }
+        inner.state = to;
+        inner.since = at;
+        if to != GateState::Cooldown {
+            inner.cooldown_until = None;
+        }
+    }
+
+    /// Move from cooldown to idle once the cooldown has elapsed
+    fn expire_cooldown(&self, inner: &mut GateFsmSnapshot, now: DateTime<Utc>) {
+        if inner.state != GateState::Cooldown {
+            return;
+        }
+        let until = inner
+            .cooldown_until
+            .as_deref()
+            .and_then(|until| DateTime::parse_from_rfc3339(until).ok())
+            .map(|until| until.with_timezone(&Utc))
+            .unwrap_or(now);
+        if until <= now {
+            self.transition(
+                inner,
+                GateState::Idle,
+                until,
+                "cooldown elapsed".to_string(),
+                None,
+            );
+            let _ = self.persist(inner);
+        }
+    }
+
+    /// Write the state atomically next to the target file
+    fn persist(&self, inner: &GateFsmSnapshot) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("json.tmp");
+        std::fs::write(&tmp, serde_json::to_vec_pretty(inner)?)?;
+        std::fs::rename(&tmp, &self.path)
+            .with_context(|| format!("Failed to write gate FSM state: {:?}", self.path))
+    }
+}
+
+impl GateObserver for GateFsm {
+    fn evaluation_started(&self, snapshot_id: &str, tic_candidate_id: &str) {
+        let now = Utc::now();
+        let mut inner = self.lock();
+        self.expire_cooldown(&mut inner, now);
+        self.transition(
+            &mut inner,
+            GateState::Evaluating,
+            now,
+            format!(
+                "evaluating {} for snapshot {}",
+                tic_candidate_id, snapshot_id
+            ),
+            None,
+        );
+        let _ = self.persist(&inner);
+    }
+
+    fn gate_decided(&self, event: &GateEvent) {
+        let now = Utc::now();
+        let mut inner = self.lock();
+        let decision = if event.decision.commit {
+            inner.consecutive_holds = 0;
+            GateState::Fire
+        } else {
+            inner.consecutive_holds += 1;
+            GateState::Hold
+        };
+        inner.evaluations += 1;
+        inner.last_decision = Some(event.clone());
+        self.transition(
+            &mut inner,
+            decision,
+            now,
+            event.decision.reason.clone(),
+            Some(event.gate_id.clone()),
+        );
+
+        let until = now + chrono::Duration::from_std(self.cooldown).unwrap_or_default();
+        self.transition(
+            &mut inner,
+            GateState::Cooldown,
+            now,
+            format!("cooldown for {:?}", self.cooldown),
+            Some(event.gate_id.clone()),
+        );
+        inner.cooldown_until = Some(until.to_rfc3339());
+        let _ = self.persist(&inner);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gates::merkaba_gate::{GateChecks, GateDecision};
+
+    fn event(commit: bool) -> GateEvent {
+        GateEvent {
+            gate_id: uuid::Uuid::new_v4().to_string(),
+            snapshot_id: "snap".to_string(),
+            tic_candidate_id: "tic".to_string(),
+            checks: GateChecks {
+                por: "valid".to_string(),
+                delta_pi: 0.0,
+                phi: 0.8,
+                delta_v: -0.1,
+                mci: Some(0.9),
+            },
+            decision: GateDecision {
+                commit,
+                reason: if commit {
+                    "all checks passed"
+                } else {
+                    "phi below threshold"
+                }
+                .to_string(),
+            },
+            timestamp: Utc::now().to_rfc3339(),
+        }
+    }
+
+    fn states(snapshot: &GateFsmSnapshot) -> Vec<GateState> {
+        snapshot.history.iter().map(|t| t.to).collect()
+    }
+
+    #[test]
+    fn test_decision_transitions_and_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gate_fsm.json");
+
+        let fsm = GateFsm::open(&path, Duration::from_secs(3600)).unwrap();
+        assert_eq!(fsm.snapshot().state, GateState::Idle);
+
+        fsm.evaluation_started("snap", "tic");
+        assert_eq!(fsm.snapshot().state, GateState::Evaluating);
+        fsm.gate_decided(&event(false));
+
+        let snapshot = fsm.snapshot();
+        assert_eq!(snapshot.state, GateState::Cooldown);
+        assert_eq!(
+            states(&snapshot),
+            vec![GateState::Evaluating, GateState::Hold, GateState::Cooldown]
+        );
+        assert_eq!(snapshot.consecutive_holds, 1);
+        assert_eq!(snapshot.last_decision.unwrap().checks.phi, 0.8);
+
+        // A new instance sees the same state and history
+        let reopened = GateFsm::open(&path, Duration::from_secs(3600)).unwrap();
+        let snapshot = reopened.snapshot();
+        assert_eq!(snapshot.state, GateState::Cooldown);
+        assert_eq!(snapshot.history.len(), 3);
+        assert_eq!(snapshot.consecutive_holds, 1);
+    }
+
+    #[test]
+    fn test_cooldown_expires_and_fire_resets_holds() {
+        let dir = tempfile::tempdir().unwrap();
+        let fsm = GateFsm::open(dir.path().join("gate_fsm.json"), Duration::ZERO).unwrap();
+
+        fsm.evaluation_started("snap", "tic");
+        fsm.gate_decided(&event(false));
+        fsm.evaluation_started("snap", "tic");
+        fsm.gate_decided(&event(true));
+
+        let snapshot = fsm.snapshot();
+        assert_eq!(snapshot.state, GateState::Idle);
+        assert_eq!(snapshot.consecutive_holds, 0);
+        assert_eq!(snapshot.evaluations, 2);
+        assert_eq!(
+            states(&snapshot),
+            vec![
+                GateState::Evaluating,
+                GateState::Hold,
+                GateState::Cooldown,
+                GateState::Idle,
+                GateState::Evaluating,
+                GateState::Fire,
+                GateState::Cooldown,
+                GateState::Idle,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_restart_during_evaluation_returns_to_idle() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("gate_fsm.json");
+
+        GateFsm::open(&path, Duration::ZERO)
+            .unwrap()
+            .evaluation_started("snap", "tic");
+
+        let snapshot = GateFsm::open(&path, Duration::ZERO).unwrap().snapshot();
+        assert_eq!(snapshot.state, GateState::Idle);
+        assert_eq!(
+            snapshot.history.back().unwrap().reason,
+            "restarted during evaluation"
+        );
+    }
+}
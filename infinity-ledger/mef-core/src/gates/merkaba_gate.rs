@@ -13,6 +13,7 @@ use serde::{Deserialize, Serialize};
 use std::fs::OpenOptions;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use crate::cube::MetatronCube;
 use crate::mandorla::MandorlaField;
@@ -90,6 +91,15 @@ pub struct GateEvent {
     pub timestamp: String,
 }
 
+/// Receives every gate evaluation, e.g. to export metrics
+pub trait GateObserver: std::fmt::Debug + Send + Sync {
+    /// Called before the checks of an evaluation are computed
+    fn evaluation_started(&self, _snapshot_id: &str, _tic_candidate_id: &str) {}
+
+    /// Called after a gate event was written to the audit log
+    fn gate_decided(&self, event: &GateEvent);
+}
+
 /// Merkaba Gate implementation utilizing Metatron Cube's topological routing
 ///
 /// The gate validates states through multiple checks:
@@ -128,6 +138,8 @@ pub struct MerkabaGate {
     pub state_history: Vec<Array1<f64>>,
     /// Lyapunov window size
     pub lyapunov_window: usize,
+    /// Notified about every evaluation, in order
+    pub observers: Vec<Arc<dyn GateObserver>>,
 }
 
 impl MerkabaGate {
@@ -186,6 +198,7 @@ impl MerkabaGate {
             qdash: QDASHAgent::new(4, 0.5, 0.5),
             state_history: Vec::new(),
             lyapunov_window: Self::DEFAULT_LYAPUNOV_WINDOW,
+            observers: Vec::new(),
         }
     }
 
@@ -458,6 +471,10 @@ impl MerkabaGate {
         phi_star_override: Option<f64>,
         eta_override: Option<f64>,
     ) -> GateEvent {
+        for observer in &self.observers {
+            observer.evaluation_started(&snapshot_id, &tic_candidate.tic_id);
+        }
+
         // Use provided overrides or defaults
         let eps = epsilon_override.unwrap_or(self.epsilon);
         let phi_star = phi_star_override.unwrap_or(self.phi_star);
@@ -496,6 +513,9 @@ impl MerkabaGate {
 
         // Audit log
         let _ = self.audit_event(&gate_event);
+        for observer in &self.observers {
+            observer.gate_decided(&gate_event);
+        }
 
         gate_event
     }
@@ -4,8 +4,11 @@
  * Collection of gate implementations for MEF-Core processing pipeline.
  */
 
+pub mod gate_fsm;
 pub mod merkaba_gate;
 
+pub use gate_fsm::{GateFsm, GateFsmSnapshot, GateState, GateTransition};
 pub use merkaba_gate::{
-    validate_gate_event, GateChecks, GateDecision, GateEvent, MerkabaGate, TICCandidate,
+    validate_gate_event, GateChecks, GateDecision, GateEvent, GateObserver, MerkabaGate,
+    TICCandidate,
 };
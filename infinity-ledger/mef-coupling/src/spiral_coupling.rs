@@ -694,6 +694,14 @@ impl SpiralCouplingEngine {
         serde_json::to_value(&self.state).unwrap_or(serde_json::json!({}))
     }
 
+    /// Persist the state, including steps pending for the next TIC, and
+    /// sync it to disk
+    pub fn flush(&self) -> Result<()> {
+        self.persist_state()?;
+        fs::File::open(&self.state_path)?.sync_all()?;
+        Ok(())
+    }
+
     // Helper methods
 
     fn override_params(&self, override_params: Option<&HashMap<String, f64>>) -> SpiralParameters {
@@ -719,9 +727,13 @@ impl SpiralCouplingEngine {
         }
     }
 
+    /// Write the state to a temporary file and move it into place, so an
+    /// interrupted write never leaves a truncated state file
     fn persist_state(&self) -> Result<()> {
         let json = serde_json::to_string_pretty(&self.state)?;
-        fs::write(&self.state_path, json)?;
+        let tmp = self.state_path.with_extension("json.tmp");
+        fs::write(&tmp, json)?;
+        fs::rename(&tmp, &self.state_path)?;
         Ok(())
     }
 
@@ -1023,4 +1035,20 @@ mod tests {
         let results = result.unwrap();
         assert!(!results.is_empty());
     }
+
+    #[test]
+    fn test_flush_keeps_pending_steps() {
+        let dir = std::env::temp_dir().join(format!("coupling_test_flush_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let mut engine =
+            SpiralCouplingEngine::new(Some(dir.clone()), None, None, 0.001, 0.5).unwrap();
+        engine.inject_seed(&json!({"value": 1})).unwrap();
+        engine.flush().unwrap();
+
+        let reopened =
+            SpiralCouplingEngine::new(Some(dir.clone()), None, None, 0.001, 0.5).unwrap();
+        assert_eq!(reopened.state.pending_steps, engine.state.pending_steps);
+        assert_eq!(reopened.state.pending_steps.len(), 1);
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,951 @@
+//! Portable, verifiable ledger archives.
+//!
+//! An archive is a plain tar file holding everything an auditor needs to
+//! check a ledger offline: the ledger index, every block as JSON, the full
+//! payloads of pruned blocks kept in cold storage, and the TIC and snapshot
+//! artifacts the blocks refer to. Its first entry, `manifest.json`, lists the
+//! size and SHA-256 of every other entry together with the chain head and
+//! Merkle root, and a root hash binds the whole listing. Importing streams
+//! every entry to disk while checking its digest, rebuilds the ledger in a
+//! staging directory, verifies the full chain and checks every artifact
+//! against the block that committed it before the ledger is moved into
+//! place.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufReader, Read, Write};
+use std::path::{Component, Path, PathBuf};
+
+use crate::checkpoint::{self, COLD_DIR};
+use crate::hashing::{self, HashScheme};
+use crate::lock::LedgerSnapshot;
+use crate::mef_block::{LedgerIndex, MEFLedger, MefBlock};
+use crate::merkle::MerkleAccumulator;
+
+/// Name of the manifest entry
+pub const MANIFEST_ENTRY: &str = "manifest.json";
+
+/// Archive layout version written by this crate
+pub const ARCHIVE_FORMAT_VERSION: u32 = 1;
+
+const INDEX_ENTRY: &str = "index.json";
+
+/// Largest archive entry accepted on import
+const MAX_ENTRY_BYTES: u64 = 1 << 30;
+
+/// Kind of file stored in an archive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ArchiveEntryKind {
+    /// Ledger index of the exported ledger
+    Index,
+    /// Block as stored in the ledger
+    Block,
+    /// Full payload of a pruned block from cold storage
+    ColdBlock,
+    /// TIC artifact referenced by a block
+    Tic,
+    /// Snapshot artifact referenced by a TIC
+    Snapshot,
+}
+
+/// Manifest record for one archive entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub kind: ArchiveEntryKind,
+    pub size: u64,
+    /// Hex-encoded SHA-256 of the entry contents
+    pub sha256: String,
+}
+
+/// Table of contents of an archive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub format_version: u32,
+    pub created: String,
+    pub block_count: usize,
+    /// Hash of the last block, or the genesis hash for an empty ledger
+    pub head_hash: String,
+    /// Merkle root over all block hashes
+    pub merkle_root: String,
+    pub entries: Vec<ArchiveEntry>,
+    /// Hash over the fields above, see [`ArchiveManifest::compute_root_hash`]
+    pub root_hash: String,
+}
+
+impl ArchiveManifest {
+    /// Hash binding the chain head, Merkle root and every entry digest
+    pub fn compute_root_hash(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(
+            format!(
+                "mef-archive:{}\n{}\n{}\n{}\n",
+                self.format_version, self.block_count, self.head_hash, self.merkle_root
+            )
+            .as_bytes(),
+        );
+        for entry in &self.entries {
+            hasher.update(format!("{} {} {}\n", entry.sha256, entry.size, entry.path).as_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    /// Paths of entries of the given kind, in archive order
+    pub fn paths(&self, kind: ArchiveEntryKind) -> impl Iterator<Item = &str> {
+        self.entries
+            .iter()
+            .filter(move |e| e.kind == kind)
+            .map(|e| e.path.as_str())
+    }
+}
+
+/// Directories holding the artifacts referenced by ledger blocks
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactStores {
+    /// Directory of `{tic_id}.tic` files written by `TICCrystallizer::save_tic`
+    pub tic_store: Option<PathBuf>,
+    /// Directory of `{snapshot_id}.spiral` files written by
+    /// `SpiralSnapshot::save_snapshot`
+    pub snapshot_store: Option<PathBuf>,
+}
+
+fn block_entry(index: i32) -> String {
+    format!("blocks/block_{:06}.json", index)
+}
+
+fn cold_entry(index: i32) -> String {
+    format!("{}/{}", COLD_DIR, checkpoint::cold_file_name(index))
+}
+
+fn tic_entry(tic_id: &str) -> String {
+    format!("tics/{}.tic", tic_id)
+}
+
+fn snapshot_entry(snapshot_id: &str) -> String {
+    format!("snapshots/{}.spiral", snapshot_id)
+}
+
+/// Whether an artifact ID is safe to use as a file name
+fn is_artifact_id(id: &str) -> bool {
+    !id.is_empty()
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Whether a manifest path is relative and free of `..` or root components
+fn is_safe_entry_path(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_)))
+}
+
+fn entry_path<R: Read>(entry: &tar::Entry<'_, R>) -> Result<String> {
+    Ok(entry
+        .path()
+        .context("Invalid archive entry path")?
+        .to_string_lossy()
+        .into_owned())
+}
+
+fn read_json<T: DeserializeOwned>(path: &Path) -> Result<T> {
+    let file = File::open(path).with_context(|| format!("Failed to open {:?}", path))?;
+    serde_json::from_reader(BufReader::new(file))
+        .with_context(|| format!("Failed to parse {:?}", path))
+}
+
+/// Stream every entry of an archive into `dir` and check it against the
+/// manifest
+///
+/// The manifest must be the first entry. Every other entry is hashed while
+/// it is written out, so only one read buffer is held in memory; entries
+/// not listed in the manifest, larger than [`MAX_ENTRY_BYTES`] or longer than
+/// their listed size are refused before they are fully read.
+fn extract_verified(archive_path: &Path, dir: &Path) -> Result<ArchiveManifest> {
+    let file = File::open(archive_path)
+        .with_context(|| format!("Failed to open archive {:?}", archive_path))?;
+    let mut archive = tar::Archive::new(file);
+    let mut entries = archive.entries().context("Failed to read archive")?;
+
+    let mut first = entries
+        .next()
+        .ok_or_else(|| anyhow::anyhow!("Archive has no manifest"))?
+        .context("Failed to read archive entry")?;
+    if entry_path(&first)? != MANIFEST_ENTRY {
+        anyhow::bail!("Archive has no manifest");
+    }
+    if first.size() > MAX_ENTRY_BYTES {
+        anyhow::bail!("Archive manifest exceeds the entry size limit");
+    }
+    let mut manifest_json = Vec::new();
+    first
+        .read_to_end(&mut manifest_json)
+        .context("Failed to read archive manifest")?;
+    let manifest: ArchiveManifest =
+        serde_json::from_slice(&manifest_json).context("Failed to parse archive manifest")?;
+    if manifest.format_version != ARCHIVE_FORMAT_VERSION {
+        anyhow::bail!(
+            "Unsupported archive format version {}",
+            manifest.format_version
+        );
+    }
+    if manifest.root_hash != manifest.compute_root_hash() {
+        anyhow::bail!("Archive manifest root hash mismatch");
+    }
+
+    let mut listed = HashMap::new();
+    for entry in &manifest.entries {
+        if !is_safe_entry_path(&entry.path) || entry.path == MANIFEST_ENTRY {
+            anyhow::bail!("Invalid archive entry path {}", entry.path);
+        }
+        if entry.size > MAX_ENTRY_BYTES {
+            anyhow::bail!("Archive entry {} exceeds the entry size limit", entry.path);
+        }
+        if listed.insert(entry.path.as_str(), entry).is_some() {
+            anyhow::bail!("Duplicate archive entry {}", entry.path);
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut buf = vec![0u8; 64 * 1024];
+    for entry in entries {
+        let mut entry = entry.context("Failed to read archive entry")?;
+        if !entry.header().entry_type().is_file() {
+            anyhow::bail!("Archive contains a non-file entry");
+        }
+        let path = entry_path(&entry)?;
+        let expected = listed.get(path.as_str()).ok_or_else(|| {
+            anyhow::anyhow!("Archive contains entries not listed in the manifest")
+        })?;
+        if !seen.insert(path.clone()) {
+            anyhow::bail!("Duplicate archive entry {}", path);
+        }
+        if entry.size() != expected.size {
+            anyhow::bail!("Archive entry {} does not match the manifest", path);
+        }
+
+        let target = dir.join(&path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+        let mut out =
+            File::create(&target).with_context(|| format!("Failed to write {:?}", target))?;
+        let mut hasher = Sha256::new();
+        let mut written = 0u64;
+        loop {
+            let n = entry
+                .read(&mut buf)
+                .with_context(|| format!("Failed to read archive entry {}", path))?;
+            if n == 0 {
+                break;
+            }
+            written += n as u64;
+            if written > expected.size {
+                anyhow::bail!("Archive entry {} does not match the manifest", path);
+            }
+            hasher.update(&buf[..n]);
+            out.write_all(&buf[..n])
+                .with_context(|| format!("Failed to write {:?}", target))?;
+        }
+        if written != expected.size || format!("{:x}", hasher.finalize()) != expected.sha256 {
+            anyhow::bail!("Archive entry {} does not match the manifest", path);
+        }
+    }
+    if let Some(missing) = listed.keys().find(|path| !seen.contains(**path)) {
+        anyhow::bail!("Archive entry {} is missing", missing);
+    }
+
+    Ok(manifest)
+}
+
+/// Check an archived TIC against the block that committed it
+///
+/// The header fields kept after pruning are always compared; the full
+/// payload is compared when the block (or its cold copy) still carries it.
+fn check_tic_against_block(tic: &JsonValue, block: &MefBlock) -> Result<()> {
+    let compact = MEFLedger::compact_tic_data(tic)?;
+    let header_matches = compact.tic_id == block.data.tic_id
+        && compact.seed == block.data.seed
+        && compact.fixpoint_norm == block.data.fixpoint_norm;
+    let payload_matches = block.pruned
+        || (serde_json::to_value(&compact)? == serde_json::to_value(&block.data)?
+            && hashing::sort_keys(&tic["proof"]) == block.proof);
+    if !header_matches || !payload_matches {
+        anyhow::bail!(
+            "Archived TIC {} does not match block {}",
+            compact.tic_id,
+            block.index
+        );
+    }
+    Ok(())
+}
+
+/// Copy artifacts into a store, refusing to replace different contents
+fn write_artifacts(store: &Path, files: &[(String, PathBuf)]) -> Result<()> {
+    fs::create_dir_all(store).with_context(|| format!("Failed to create {:?}", store))?;
+    for (name, source) in files {
+        let path = store.join(name);
+        if path.exists() {
+            if fs::read(&path)? != fs::read(source)? {
+                anyhow::bail!("{:?} already exists with different contents", path);
+            }
+            continue;
+        }
+        fs::copy(source, &path).with_context(|| format!("Failed to write {:?}", path))?;
+    }
+    Ok(())
+}
+
+/// Verified artifacts of an extracted archive, ready to be copied to stores
+#[derive(Default)]
+struct StagedArtifacts {
+    tics: Vec<(String, PathBuf)>,
+    snapshots: Vec<(String, PathBuf)>,
+}
+
+impl MEFLedger {
+    /// Pack the ledger and its referenced artifacts into a single archive
+    ///
+    /// The archive reflects the ledger as currently stored on disk. TICs are
+    /// looked up by the `tic_id` of each block and snapshots by the
+    /// `source_snapshot` of each TIC; artifacts missing from the stores are
+    /// left out.
+    ///
+    /// # Arguments
+    /// * `archive_path` - Output tar file
+    /// * `artifacts` - Stores to collect TIC and snapshot artifacts from
+    pub fn export_archive(
+        &self,
+        archive_path: impl AsRef<Path>,
+        artifacts: &ArtifactStores,
+    ) -> Result<ArchiveManifest> {
+        let archive_path = archive_path.as_ref();
+        let snapshot = LedgerSnapshot::open(self.path())?;
+        let index = snapshot.index();
+
+        let mut files: Vec<(String, ArchiveEntryKind, Vec<u8>)> = vec![(
+            INDEX_ENTRY.to_string(),
+            ArchiveEntryKind::Index,
+            serde_json::to_vec_pretty(index).context("Failed to serialize index")?,
+        )];
+
+        let mut tic_ids = BTreeSet::new();
+        for summary in &index.blocks {
+            let block = snapshot
+                .get_block(summary.index)?
+                .ok_or_else(|| anyhow::anyhow!("Missing block at index {}", summary.index))?;
+            files.push((
+                block_entry(block.index),
+                ArchiveEntryKind::Block,
+                serde_json::to_vec_pretty(&block).context("Failed to serialize block")?,
+            ));
+
+            if block.pruned {
+                let cold = self
+                    .path()
+                    .join(COLD_DIR)
+                    .join(checkpoint::cold_file_name(block.index));
+                if cold.exists() {
+                    files.push((
+                        cold_entry(block.index),
+                        ArchiveEntryKind::ColdBlock,
+                        fs::read(&cold).context("Failed to read archived block")?,
+                    ));
+                }
+            }
+            if block.checkpoint.is_none() && is_artifact_id(&block.tic_id) {
+                tic_ids.insert(block.tic_id);
+            }
+        }
+
+        let mut snapshot_ids = BTreeSet::new();
+        if let Some(store) = &artifacts.tic_store {
+            for tic_id in &tic_ids {
+                let path = store.join(format!("{}.tic", tic_id));
+                if !path.exists() {
+                    continue;
+                }
+                let contents =
+                    fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+                let tic: JsonValue = serde_json::from_slice(&contents)
+                    .with_context(|| format!("Failed to parse {:?}", path))?;
+                if let Some(id) = tic["source_snapshot"]
+                    .as_str()
+                    .filter(|id| is_artifact_id(id))
+                {
+                    snapshot_ids.insert(id.to_string());
+                }
+                files.push((tic_entry(tic_id), ArchiveEntryKind::Tic, contents));
+            }
+        }
+        if let Some(store) = &artifacts.snapshot_store {
+            for snapshot_id in &snapshot_ids {
+                let path = store.join(format!("{}.spiral", snapshot_id));
+                if path.exists() {
+                    let contents =
+                        fs::read(&path).with_context(|| format!("Failed to read {:?}", path))?;
+                    files.push((
+                        snapshot_entry(snapshot_id),
+                        ArchiveEntryKind::Snapshot,
+                        contents,
+                    ));
+                }
+            }
+        }
+
+        let merkle =
+            MerkleAccumulator::from_block_hashes(index.blocks.iter().map(|b| b.hash.as_str()));
+        let mut manifest = ArchiveManifest {
+            format_version: ARCHIVE_FORMAT_VERSION,
+            created: Utc::now().format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string(),
+            block_count: index.blocks.len(),
+            head_hash: index
+                .blocks
+                .last()
+                .map(|b| b.hash.clone())
+                .unwrap_or_else(|| "0".repeat(64)),
+            merkle_root: merkle.root(),
+            entries: files
+                .iter()
+                .map(|(path, kind, contents)| ArchiveEntry {
+                    path: path.clone(),
+                    kind: *kind,
+                    size: contents.len() as u64,
+                    sha256: sha256_hex(contents),
+                })
+                .collect(),
+            root_hash: String::new(),
+        };
+        manifest.root_hash = manifest.compute_root_hash();
+        let manifest_json =
+            serde_json::to_vec_pretty(&manifest).context("Failed to serialize manifest")?;
+
+        // Write next to the target and rename, so a failed export never
+        // leaves a truncated archive behind
+        let tmp_path = archive_path.with_extension("tmp");
+        {
+            let file = File::create(&tmp_path)
+                .with_context(|| format!("Failed to create {:?}", tmp_path))?;
+            let mut builder = tar::Builder::new(file);
+            let mtime = Utc::now().timestamp().max(0) as u64;
+            let manifest_entry = (MANIFEST_ENTRY, &manifest_json[..]);
+            let entries = files
+                .iter()
+                .map(|(path, _, contents)| (path.as_str(), &contents[..]));
+            for (path, contents) in std::iter::once(manifest_entry).chain(entries) {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(contents.len() as u64);
+                header.set_mode(0o644);
+                header.set_mtime(mtime);
+                header.set_cksum();
+                builder
+                    .append_data(&mut header, path, contents)
+                    .with_context(|| format!("Failed to add {} to archive", path))?;
+            }
+            builder
+                .into_inner()
+                .context("Failed to finish archive")?
+                .sync_all()
+                .context("Failed to sync archive")?;
+        }
+        fs::rename(&tmp_path, archive_path)
+            .with_context(|| format!("Failed to move archive to {:?}", archive_path))?;
+
+        Ok(manifest)
+    }
+
+    /// Restore a ledger from an archive written by [`MEFLedger::export_archive`]
+    ///
+    /// Every entry is streamed to disk and checked against the manifest, the
+    /// ledger is rebuilt in a staging directory and the full chain (hashes,
+    /// signatures under the archived key registry, linkage and checkpoints)
+    /// is verified, and every artifact is checked against the block that
+    /// committed it, before the ledger is moved to `ledger_path` and the
+    /// artifacts are written to the stores. Nothing is written to `ledger_path` if any check fails. Callers
+    /// that trust a specific set of signer keys should additionally check the
+    /// result with [`MEFLedger::verify_chain_against`].
+    ///
+    /// # Arguments
+    /// * `archive_path` - Archive file
+    /// * `ledger_path` - Destination directory; must not exist or be empty
+    /// * `artifacts` - Stores to restore TIC and snapshot artifacts into
+    pub fn import_archive(
+        archive_path: impl AsRef<Path>,
+        ledger_path: impl AsRef<Path>,
+        artifacts: &ArtifactStores,
+    ) -> Result<(Self, ArchiveManifest)> {
+        let ledger_path = ledger_path.as_ref();
+        if ledger_path.exists()
+            && fs::read_dir(ledger_path)
+                .with_context(|| format!("Failed to read {:?}", ledger_path))?
+                .next()
+                .is_some()
+        {
+            anyhow::bail!("Import destination {:?} is not empty", ledger_path);
+        }
+
+        let file_name = ledger_path
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid import destination {:?}", ledger_path))?
+            .to_string_lossy()
+            .into_owned();
+        let extracted = ledger_path.with_file_name(format!(".{}.extracting", file_name));
+        let staging = ledger_path.with_file_name(format!(".{}.importing", file_name));
+        for dir in [&extracted, &staging] {
+            if dir.exists() {
+                fs::remove_dir_all(dir).context("Failed to clear staging directory")?;
+            }
+        }
+
+        let staged = Self::stage_archive(archive_path.as_ref(), &extracted, &staging).and_then(
+            |(manifest, staged)| {
+                if let Some(store) = &artifacts.tic_store {
+                    write_artifacts(store, &staged.tics)?;
+                }
+                if let Some(store) = &artifacts.snapshot_store {
+                    write_artifacts(store, &staged.snapshots)?;
+                }
+                Ok(manifest)
+            },
+        );
+        let _ = fs::remove_dir_all(&extracted);
+        let manifest = match staged {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging);
+                return Err(e);
+            }
+        };
+
+        if ledger_path.exists() {
+            fs::remove_dir(ledger_path)
+                .with_context(|| format!("Failed to replace {:?}", ledger_path))?;
+        }
+        fs::rename(&staging, ledger_path)
+            .with_context(|| format!("Failed to move imported ledger to {:?}", ledger_path))?;
+
+        Ok((MEFLedger::new(ledger_path)?, manifest))
+    }
+
+    /// Extract an archive and rebuild and verify its ledger in `staging`
+    ///
+    /// Returns the manifest and the TIC and snapshot artifacts, each checked
+    /// against the verified chain: a TIC must match the `CompactTic` of the
+    /// block that committed it and a snapshot must hash, under that block's
+    /// scheme, to the block's `snapshot_hash`.
+    fn stage_archive(
+        archive_path: &Path,
+        extracted: &Path,
+        staging: &Path,
+    ) -> Result<(ArchiveManifest, StagedArtifacts)> {
+        let manifest = extract_verified(archive_path, extracted)?;
+
+        let index_path = extracted.join(INDEX_ENTRY);
+        if !index_path.exists() {
+            anyhow::bail!("Archive has no ledger index");
+        }
+        let index: LedgerIndex =
+            read_json(&index_path).context("Failed to parse archived index")?;
+        if index.blocks.len() != manifest.block_count {
+            anyhow::bail!("Archived index does not match the manifest block count");
+        }
+
+        let mut ledger = MEFLedger::new(staging)?;
+        ledger.adopt_key_registry(index.metadata.key_registry.clone())?;
+        ledger.restore_blocks(index.blocks.iter().map(|summary| {
+            let path = extracted.join(block_entry(summary.index));
+            if !path.exists() {
+                anyhow::bail!("Archive entry {} is missing", block_entry(summary.index));
+            }
+            let block: MefBlock = read_json(&path)?;
+            if block.index != summary.index || block.hash != summary.hash {
+                anyhow::bail!("Block {} does not match the archived index", summary.index);
+            }
+            Ok(block)
+        }))?;
+        if !ledger.verify_chain_integrity(0)? {
+            anyhow::bail!("Archived chain failed verification");
+        }
+        let root = ledger.merkle_root()?;
+        if root.root != manifest.merkle_root || root.last_block_hash != manifest.head_hash {
+            anyhow::bail!("Archived chain does not match the manifest root");
+        }
+
+        let cold_paths: Vec<&str> = manifest.paths(ArchiveEntryKind::ColdBlock).collect();
+        if !cold_paths.is_empty() {
+            let cold_dir = staging.join(COLD_DIR);
+            fs::create_dir_all(&cold_dir).context("Failed to create cold storage")?;
+            for path in cold_paths {
+                let source = extracted.join(path);
+                let block: MefBlock = read_json(&source)?;
+                if path != cold_entry(block.index) {
+                    anyhow::bail!("Archived payload {} is misnamed", path);
+                }
+                fs::copy(
+                    &source,
+                    cold_dir.join(checkpoint::cold_file_name(block.index)),
+                )
+                .context("Failed to restore archived block payload")?;
+                ledger.get_archived_block(block.index)?;
+            }
+        }
+
+        // Artifacts must be the ones the verified chain committed to
+        let tic_blocks: HashMap<&str, i32> = ledger
+            .index()
+            .blocks
+            .iter()
+            .filter(|b| b.checkpoint.is_none())
+            .map(|b| (b.tic_id.as_str(), b.index))
+            .collect();
+        let full_block = |index: i32| -> Result<MefBlock> {
+            match ledger.get_archived_block(index)? {
+                Some(block) => Ok(block),
+                None => ledger
+                    .get_block(index)?
+                    .ok_or_else(|| anyhow::anyhow!("Missing block at index {}", index)),
+            }
+        };
+
+        let mut artifacts = StagedArtifacts::default();
+        let mut snapshot_blocks: HashMap<String, Vec<MefBlock>> = HashMap::new();
+        for path in manifest.paths(ArchiveEntryKind::Tic) {
+            let source = extracted.join(path);
+            let tic: JsonValue = read_json(&source)?;
+            let tic_id = tic["tic_id"].as_str().unwrap_or_default();
+            let block_index = match tic_blocks.get(tic_id) {
+                Some(&index) if is_artifact_id(tic_id) && path == tic_entry(tic_id) => index,
+                _ => anyhow::bail!("Archived TIC {} is not referenced by the ledger", path),
+            };
+            let block = full_block(block_index)?;
+            check_tic_against_block(&tic, &block)?;
+            if let Some(id) = tic["source_snapshot"].as_str() {
+                snapshot_blocks
+                    .entry(id.to_string())
+                    .or_default()
+                    .push(block);
+            }
+            artifacts.tics.push((format!("{}.tic", tic_id), source));
+        }
+        for path in manifest.paths(ArchiveEntryKind::Snapshot) {
+            let source = extracted.join(path);
+            let snapshot: JsonValue = read_json(&source)?;
+            let id = snapshot["id"].as_str().unwrap_or_default();
+            let blocks = match snapshot_blocks.get(id) {
+                Some(blocks) if is_artifact_id(id) && path == snapshot_entry(id) => blocks,
+                _ => anyhow::bail!("Archived snapshot {} is not referenced by the ledger", path),
+            };
+            for block in blocks {
+                let scheme = block
+                    .hash_scheme
+                    .map(HashScheme::from_version)
+                    .transpose()?
+                    .unwrap_or(HashScheme::Legacy);
+                if scheme.hash_value(&snapshot) != block.snapshot_hash {
+                    anyhow::bail!(
+                        "Archived snapshot {} does not match block {}",
+                        id,
+                        block.index
+                    );
+                }
+            }
+            artifacts.snapshots.push((format!("{}.spiral", id), source));
+        }
+
+        Ok((manifest, artifacts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signing::LedgerSigner;
+    use crate::LedgerConfig;
+    use serde_json::json;
+    use std::io::Write;
+
+    fn tic(i: usize) -> JsonValue {
+        json!({
+            "tic_id": format!("tic-{}", i),
+            "seed": "SEED",
+            "fixpoint": [0.1, 0.2, 0.3],
+            "invariants": {"variance": 0.1},
+            "sigma_bar": {"psi": 0.5},
+            "window": ["snap-a"],
+            "proof": {"por": "valid"},
+            "source_snapshot": format!("snap-{}", i)
+        })
+    }
+
+    /// Ledger with artifacts, a checkpoint and archived pruned payloads
+    fn populated(dir: &Path) -> (MEFLedger, ArtifactStores) {
+        let stores = ArtifactStores {
+            tic_store: Some(dir.join("tics")),
+            snapshot_store: Some(dir.join("spiral")),
+        };
+        fs::create_dir_all(dir.join("tics")).unwrap();
+        fs::create_dir_all(dir.join("spiral")).unwrap();
+
+        let config = LedgerConfig {
+            signer: Some(LedgerSigner::generate("key-1")),
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(dir.join("ledger"), config).unwrap();
+        for i in 0..4 {
+            let snapshot = json!({"id": format!("snap-{}", i), "phase": i});
+            ledger.append_block(&tic(i), &snapshot).unwrap();
+            fs::write(dir.join(format!("tics/tic-{}.tic", i)), tic(i).to_string()).unwrap();
+            fs::write(
+                dir.join(format!("spiral/snap-{}.spiral", i)),
+                snapshot.to_string(),
+            )
+            .unwrap();
+        }
+        ledger.create_checkpoint().unwrap();
+        ledger.prune_payloads(1, true).unwrap();
+        (ledger, stores)
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ledger, stores) = populated(temp_dir.path());
+        let archive = temp_dir.path().join("ledger.tar");
+
+        let manifest = ledger.export_archive(&archive, &stores).unwrap();
+        assert_eq!(manifest.block_count, 5);
+        assert_eq!(manifest.paths(ArchiveEntryKind::Tic).count(), 4);
+        assert_eq!(manifest.paths(ArchiveEntryKind::Snapshot).count(), 4);
+        assert_eq!(manifest.paths(ArchiveEntryKind::ColdBlock).count(), 2);
+
+        let target = ArtifactStores {
+            tic_store: Some(temp_dir.path().join("audit/tics")),
+            snapshot_store: Some(temp_dir.path().join("audit/spiral")),
+        };
+        let (imported, imported_manifest) =
+            MEFLedger::import_archive(&archive, temp_dir.path().join("audit/ledger"), &target)
+                .unwrap();
+        assert_eq!(imported_manifest, manifest);
+        assert!(imported.verify_chain_integrity(0).unwrap());
+        assert_eq!(imported.merkle_root().unwrap().root, manifest.merkle_root);
+        assert_eq!(imported.key_registry(), ledger.key_registry());
+        assert!(imported.get_block(1).unwrap().unwrap().pruned);
+        assert!(!imported.get_archived_block(1).unwrap().unwrap().pruned);
+        assert!(temp_dir.path().join("audit/tics/tic-2.tic").exists());
+        assert!(temp_dir.path().join("audit/spiral/snap-3.spiral").exists());
+    }
+
+    #[test]
+    fn test_import_rejects_tampered_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ledger, stores) = populated(temp_dir.path());
+        let archive = temp_dir.path().join("ledger.tar");
+        ledger.export_archive(&archive, &stores).unwrap();
+
+        // Rewrite one block entry without updating the manifest
+        let (manifest, mut files) = read_raw(&archive);
+        let entry = block_entry(2);
+        let mut block: MefBlock = serde_json::from_slice(&files[&entry]).unwrap();
+        block.data.seed = "FORGED".to_string();
+        files.insert(entry, serde_json::to_vec_pretty(&block).unwrap());
+        write_raw(&archive, &manifest, &files);
+
+        let destination = temp_dir.path().join("audit");
+        let err = MEFLedger::import_archive(&archive, &destination, &ArtifactStores::default())
+            .map(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match the manifest"));
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    fn test_import_rejects_forged_chain_with_consistent_manifest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ledger, stores) = populated(temp_dir.path());
+        let archive = temp_dir.path().join("ledger.tar");
+        ledger.export_archive(&archive, &stores).unwrap();
+
+        // Forge a block and recompute every digest, the manifest and the hash
+        let (mut manifest, mut files) = read_raw(&archive);
+        let entry = block_entry(3);
+        let mut block: MefBlock = serde_json::from_slice(&files[&entry]).unwrap();
+        block.data.seed = "FORGED".to_string();
+        let forged = serde_json::to_vec_pretty(&block).unwrap();
+        forge_entry(&mut manifest, &mut files, &entry, forged);
+        write_raw(&archive, &manifest, &files);
+
+        let destination = temp_dir.path().join("audit");
+        let err = MEFLedger::import_archive(&archive, &destination, &ArtifactStores::default())
+            .map(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().contains("failed verification"));
+        assert!(!destination.exists());
+        assert!(!temp_dir.path().join(".audit.importing").exists());
+    }
+
+    #[test]
+    fn test_import_rejects_forged_artifacts_with_consistent_manifest() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ledger, stores) = populated(temp_dir.path());
+        let archive = temp_dir.path().join("ledger.tar");
+        ledger.export_archive(&archive, &stores).unwrap();
+        let (manifest, files) = read_raw(&archive);
+
+        // A TIC whose seed differs from its committed block
+        let mut forged_tic = tic(2);
+        forged_tic["seed"] = json!("FORGED");
+        let (mut bad_manifest, mut bad_files) = (manifest.clone(), files.clone());
+        forge_entry(
+            &mut bad_manifest,
+            &mut bad_files,
+            &tic_entry("tic-2"),
+            forged_tic.to_string().into_bytes(),
+        );
+        write_raw(&archive, &bad_manifest, &bad_files);
+
+        let target = ArtifactStores {
+            tic_store: Some(temp_dir.path().join("audit/tics")),
+            snapshot_store: Some(temp_dir.path().join("audit/spiral")),
+        };
+        let destination = temp_dir.path().join("audit/ledger");
+        let err = MEFLedger::import_archive(&archive, &destination, &target)
+            .map(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match block"), "{}", err);
+
+        // A snapshot whose hash differs from the block's snapshot_hash
+        let (mut bad_manifest, mut bad_files) = (manifest, files);
+        forge_entry(
+            &mut bad_manifest,
+            &mut bad_files,
+            &snapshot_entry("snap-3"),
+            json!({"id": "snap-3", "phase": 99})
+                .to_string()
+                .into_bytes(),
+        );
+        write_raw(&archive, &bad_manifest, &bad_files);
+        let err = MEFLedger::import_archive(&archive, &destination, &target)
+            .map(|_| ())
+            .unwrap_err();
+        assert!(err.to_string().contains("does not match block"), "{}", err);
+
+        assert!(!destination.exists());
+        assert!(!temp_dir.path().join("audit/tics").exists());
+        assert!(!temp_dir.path().join("audit/.ledger.extracting").exists());
+    }
+
+    #[test]
+    fn test_import_rejects_unsafe_entry_paths() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ledger, stores) = populated(temp_dir.path());
+        let archive = temp_dir.path().join("ledger.tar");
+        ledger.export_archive(&archive, &stores).unwrap();
+
+        let (mut manifest, _) = read_raw(&archive);
+        manifest.entries[0].path = "../escape.json".to_string();
+        manifest.root_hash = manifest.compute_root_hash();
+        let mut builder = tar::Builder::new(File::create(&archive).unwrap());
+        let manifest_json = serde_json::to_vec_pretty(&manifest).unwrap();
+        let mut header = tar::Header::new_gnu();
+        header.set_size(manifest_json.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, MANIFEST_ENTRY, &manifest_json[..])
+            .unwrap();
+        builder.finish().unwrap();
+
+        let err = MEFLedger::import_archive(
+            &archive,
+            temp_dir.path().join("audit"),
+            &ArtifactStores::default(),
+        )
+        .map(|_| ())
+        .unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid archive entry path"),
+            "{}",
+            err
+        );
+        assert!(!temp_dir.path().join("escape.json").exists());
+    }
+
+    #[test]
+    fn test_import_requires_empty_destination() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let (ledger, stores) = populated(temp_dir.path());
+        let archive = temp_dir.path().join("ledger.tar");
+        ledger.export_archive(&archive, &stores).unwrap();
+
+        let destination = temp_dir.path().join("audit");
+        fs::create_dir_all(&destination).unwrap();
+        fs::File::create(destination.join("existing"))
+            .unwrap()
+            .write_all(b"x")
+            .unwrap();
+        assert!(
+            MEFLedger::import_archive(&archive, &destination, &ArtifactStores::default()).is_err()
+        );
+    }
+
+    /// Read every archive entry into memory, without any checks
+    fn read_raw(path: &Path) -> (ArchiveManifest, HashMap<String, Vec<u8>>) {
+        let mut archive = tar::Archive::new(File::open(path).unwrap());
+        let mut files = HashMap::new();
+        for entry in archive.entries().unwrap() {
+            let mut entry = entry.unwrap();
+            let name = entry_path(&entry).unwrap();
+            let mut contents = Vec::new();
+            entry.read_to_end(&mut contents).unwrap();
+            files.insert(name, contents);
+        }
+        let manifest = serde_json::from_slice(&files.remove(MANIFEST_ENTRY).unwrap()).unwrap();
+        (manifest, files)
+    }
+
+    /// Replace an entry and recompute its digest and the manifest root hash
+    fn forge_entry(
+        manifest: &mut ArchiveManifest,
+        files: &mut HashMap<String, Vec<u8>>,
+        path: &str,
+        contents: Vec<u8>,
+    ) {
+        for e in manifest.entries.iter_mut().filter(|e| e.path == path) {
+            e.size = contents.len() as u64;
+            e.sha256 = sha256_hex(&contents);
+        }
+        manifest.root_hash = manifest.compute_root_hash();
+        files.insert(path.to_string(), contents);
+    }
+
+    fn write_raw(path: &Path, manifest: &ArchiveManifest, files: &HashMap<String, Vec<u8>>) {
+        let mut builder = tar::Builder::new(File::create(path).unwrap());
+        let manifest_json = serde_json::to_vec_pretty(manifest).unwrap();
+        let mut append = |name: &str, contents: &[u8]| {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append_data(&mut header, name, contents).unwrap();
+        };
+        append(MANIFEST_ENTRY, &manifest_json);
+        for entry in &manifest.entries {
+            append(&entry.path, &files[&entry.path]);
+        }
+        builder.finish().unwrap();
+    }
+}
@@ -0,0 +1,87 @@
+//! Checkpoint blocks and payload pruning.
+//!
+//! A checkpoint is an ordinary chained (and, with a signer, signed) block
+//! whose hash also covers two Merkle roots over blocks `0..=up_to_index`:
+//! one over the block hashes and one over the block headers, i.e. every
+//! field that survives pruning. Once a checkpoint covers a block, its bulky
+//! TIC payload (invariants, sigma bar, window and proof) may be dropped. A
+//! pruned block can no longer be re-hashed, so verification checks its
+//! header and hash against the roots of a later checkpoint instead.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+
+use crate::mef_block::MefBlock;
+
+/// Directory pruned payloads are archived to
+pub const COLD_DIR: &str = "cold";
+
+/// Commitment to the ledger state up to a block index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Checkpoint {
+    /// Last block index covered by the checkpoint
+    pub up_to_index: i32,
+    /// Merkle root over the hashes of blocks `0..=up_to_index`
+    pub merkle_root: String,
+    /// Merkle root over the header digests of blocks `0..=up_to_index`
+    pub header_root: String,
+    /// Hash of block `up_to_index`
+    pub last_block_hash: String,
+}
+
+impl Checkpoint {
+    /// Number of blocks covered by the checkpoint
+    pub fn tree_size(&self) -> u64 {
+        (self.up_to_index + 1) as u64
+    }
+}
+
+/// Digest of the block fields that are kept when the payload is pruned
+pub fn header_digest(block: &MefBlock) -> String {
+    // A positional array keeps the digest independent of map key ordering
+    let header = serde_json::json!([
+        block.index,
+        block.previous_hash,
+        block.timestamp,
+        block.tic_id,
+        block.snapshot_hash,
+        block.data.tic_id,
+        block.data.seed,
+        block.data.fixpoint_norm,
+        block.signer,
+        block.hash,
+    ]);
+    let mut hasher = Sha256::new();
+    hasher.update(header.to_string().as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Strip the TIC payload from a block, keeping its header and hash
+pub fn prune_block(block: &MefBlock) -> MefBlock {
+    let mut pruned = block.clone();
+    pruned.data.invariants = JsonValue::Null;
+    pruned.data.sigma_bar = JsonValue::Null;
+    pruned.data.window = Vec::new();
+    pruned.proof = JsonValue::Null;
+    pruned.pruned = true;
+    pruned
+}
+
+/// Whether a pruned block carries exactly what [`prune_block`] leaves
+///
+/// The payload of a pruned block is covered neither by its hash nor by the
+/// checkpoint header digest, so anything left in it would be unverified.
+pub fn is_pruned_form(block: &MefBlock) -> bool {
+    block.pruned
+        && block.data.invariants.is_null()
+        && block.data.sigma_bar.is_null()
+        && block.data.window.is_empty()
+        && block.proof.is_null()
+        && block.checkpoint.is_none()
+}
+
+/// File name of an archived block payload
+pub fn cold_file_name(index: i32) -> String {
+    format!("block_{:06}.json", index)
+}
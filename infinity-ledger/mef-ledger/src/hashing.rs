@@ -0,0 +1,465 @@
+//! Versioned canonical hashing of ledger blocks.
+//!
+//! A block hash is the SHA-256 of a canonical serialization of the block
+//! without its `hash`, `signature` and `pruned` fields. The canonical form
+//! is versioned and the version is recorded in each block's `hash_scheme`
+//! field, so verification always recomputes a hash the way it was produced:
+//!
+//! * Scheme 1 (legacy) sorts object keys and replaces every float with a
+//!   `{:.16e}` string before serializing with `serde_json`. Blocks written
+//!   before schemes were versioned carry no `hash_scheme` field and use it.
+//! * Scheme 2 follows RFC 8785 (JSON Canonicalization Scheme): keys sorted
+//!   by UTF-16 code units, minimal string escaping and ECMAScript number
+//!   formatting. Numbers are rendered from their shortest round-trip digits,
+//!   so the output does not depend on platform float formatting.
+//!
+//! Both schemes rely on stored floats parsing back to the exact values that
+//! were hashed, which is why this crate enables `serde_json`'s
+//! `float_roundtrip` feature. Schemes are never changed once released; a new
+//! canonical form gets a new version.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use crate::mef_block::MEFLedger;
+
+/// Canonical serialization used to hash a block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashScheme {
+    /// Sorted keys with floats rendered as `{:.16e}` strings (version 1)
+    Legacy,
+    /// RFC 8785 JSON Canonicalization Scheme (version 2)
+    Jcs,
+}
+
+impl Default for HashScheme {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+impl HashScheme {
+    /// Scheme used for new blocks unless configured otherwise
+    pub const CURRENT: HashScheme = HashScheme::Jcs;
+
+    /// Version number recorded in blocks
+    pub fn version(self) -> u32 {
+        match self {
+            Self::Legacy => 1,
+            Self::Jcs => 2,
+        }
+    }
+
+    /// Look up a scheme by its recorded version
+    ///
+    /// # Arguments
+    /// * `version` - Value of a block's `hash_scheme` field
+    pub fn from_version(version: u32) -> Result<Self> {
+        match version {
+            1 => Ok(Self::Legacy),
+            2 => Ok(Self::Jcs),
+            _ => anyhow::bail!("Unsupported hash scheme version {}", version),
+        }
+    }
+
+    /// Scheme recorded in a serialized block
+    ///
+    /// Blocks without a `hash_scheme` field use the legacy scheme.
+    pub fn of_block(block: &JsonValue) -> Result<Self> {
+        match block.get("hash_scheme") {
+            None | Some(JsonValue::Null) => Ok(Self::Legacy),
+            Some(value) => {
+                let version = value
+                    .as_u64()
+                    .and_then(|v| u32::try_from(v).ok())
+                    .ok_or_else(|| anyhow::anyhow!("Invalid hash scheme {}", value))?;
+                Self::from_version(version)
+            }
+        }
+    }
+
+    /// Hash a serialized block, ignoring its `hash`, `signature` and `pruned`
+    /// fields
+    pub fn hash_block(self, block: &JsonValue) -> String {
+        let mut block_data = block.clone();
+        if let Some(obj) = block_data.as_object_mut() {
+            obj.remove("hash");
+            obj.remove("signature");
+            obj.remove("pruned");
+        }
+
+        let canonical = match self {
+            Self::Legacy => {
+                let mut sorted = sort_keys(&block_data);
+                normalize_floats(&mut sorted);
+                serde_json::to_string(&sorted).unwrap()
+            }
+            Self::Jcs => jcs_string(&block_data),
+        };
+        sha256_hex(canonical.as_bytes())
+    }
+
+    /// Hash an arbitrary JSON document, such as a block's snapshot
+    pub fn hash_value(self, value: &JsonValue) -> String {
+        let canonical = match self {
+            Self::Legacy => serde_json::to_string(&sort_keys(value)).unwrap(),
+            Self::Jcs => jcs_string(value),
+        };
+        sha256_hex(canonical.as_bytes())
+    }
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(bytes);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Recursively sort all object keys
+pub(crate) fn sort_keys(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(map) => {
+            let mut sorted_map = serde_json::Map::new();
+            let mut keys: Vec<_> = map.keys().collect();
+            keys.sort();
+            for key in keys {
+                sorted_map.insert(key.clone(), sort_keys(&map[key]));
+            }
+            JsonValue::Object(sorted_map)
+        }
+        JsonValue::Array(arr) => JsonValue::Array(arr.iter().map(sort_keys).collect()),
+        _ => value.clone(),
+    }
+}
+
+/// Replace floats with `{:.16e}` strings (legacy scheme)
+fn normalize_floats(value: &mut JsonValue) {
+    match value {
+        JsonValue::Object(map) => {
+            for (_key, val) in map.iter_mut() {
+                normalize_floats(val);
+            }
+        }
+        JsonValue::Array(arr) => {
+            for val in arr.iter_mut() {
+                normalize_floats(val);
+            }
+        }
+        JsonValue::Number(n) => {
+            // Only normalize if it's a float, not an integer
+            if let Some(f) = n.as_f64() {
+                if !n.is_i64() && !n.is_u64() {
+                    *value = JsonValue::String(format!("{:.16e}", f));
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Serialize JSON in RFC 8785 canonical form
+pub fn jcs_string(value: &JsonValue) -> String {
+    let mut out = String::new();
+    write_jcs(value, &mut out);
+    out
+}
+
+fn write_jcs(value: &JsonValue, out: &mut String) {
+    match value {
+        JsonValue::Null => out.push_str("null"),
+        JsonValue::Bool(b) => out.push_str(if *b { "true" } else { "false" }),
+        JsonValue::Number(n) => {
+            // serde_json numbers are always finite
+            out.push_str(&format_es_number(n.as_f64().unwrap_or(0.0)))
+        }
+        JsonValue::String(s) => write_jcs_string(s, out),
+        JsonValue::Array(arr) => {
+            out.push('[');
+            for (i, item) in arr.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_jcs(item, out);
+            }
+            out.push(']');
+        }
+        JsonValue::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_jcs_string(key, out);
+                out.push(':');
+                write_jcs(item, out);
+            }
+            out.push('}');
+        }
+    }
+}
+
+fn write_jcs_string(s: &str, out: &mut String) {
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\u{08}' => out.push_str("\\b"),
+            '\u{09}' => out.push_str("\\t"),
+            '\u{0a}' => out.push_str("\\n"),
+            '\u{0c}' => out.push_str("\\f"),
+            '\u{0d}' => out.push_str("\\r"),
+            c if c < '\u{20}' => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Format a finite double like ECMAScript `Number.prototype.toString`
+///
+/// Rust's `{:e}` yields the shortest digit string that round-trips, which is
+/// exactly the digit selection ECMAScript requires; only the placement of
+/// the decimal point and exponent differs.
+fn format_es_number(value: f64) -> String {
+    if value == 0.0 {
+        // Also covers negative zero
+        return "0".to_string();
+    }
+
+    let sci = format!("{:e}", value.abs());
+    let (mantissa, exponent) = sci.split_once('e').expect("`{:e}` output has an exponent");
+    let digits: String = mantissa.chars().filter(|c| *c != '.').collect();
+    let exponent: i32 = exponent.parse().expect("`{:e}` exponent is an integer");
+
+    // value = 0.digits * 10^n
+    let k = digits.len() as i32;
+    let n = exponent + 1;
+
+    let mut out = String::new();
+    if value < 0.0 {
+        out.push('-');
+    }
+    if k <= n && n <= 21 {
+        out.push_str(&digits);
+        out.extend(std::iter::repeat_n('0', (n - k) as usize));
+    } else if 0 < n && n <= 21 {
+        out.push_str(&digits[..n as usize]);
+        out.push('.');
+        out.push_str(&digits[n as usize..]);
+    } else if -6 < n && n <= 0 {
+        out.push_str("0.");
+        out.extend(std::iter::repeat_n('0', (-n) as usize));
+        out.push_str(&digits);
+    } else {
+        out.push_str(&digits[..1]);
+        if k > 1 {
+            out.push('.');
+            out.push_str(&digits[1..]);
+        }
+        let _ = write!(
+            out,
+            "e{}{}",
+            if n - 1 < 0 { '-' } else { '+' },
+            (n - 1).abs()
+        );
+    }
+    out
+}
+
+/// Block whose stored hash could not be reproduced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashSchemeFailure {
+    pub index: i32,
+    /// Recorded scheme version
+    pub version: u32,
+    pub reason: String,
+}
+
+/// Result of re-verifying every block hash under its recorded scheme
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HashSchemeReport {
+    /// Number of blocks per recorded scheme version
+    pub blocks_by_version: BTreeMap<u32, usize>,
+    /// Pruned blocks, whose hashes are vouched for by checkpoints instead
+    pub pruned: usize,
+    pub failures: Vec<HashSchemeFailure>,
+}
+
+impl HashSchemeReport {
+    /// Whether every block hash was reproduced
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+impl MEFLedger {
+    /// Recompute every block hash under the scheme recorded in the block
+    ///
+    /// Unlike [`MEFLedger::verify_chain_integrity`] this does not stop at the
+    /// first problem, so it shows which blocks are affected by a change in
+    /// canonicalization, e.g. float formatting drift under the legacy scheme.
+    pub fn verify_hash_schemes(&self) -> Result<HashSchemeReport> {
+        let mut report = HashSchemeReport::default();
+
+        for i in 0..=self.index().current_index {
+            let block = self
+                .get_block(i)?
+                .ok_or_else(|| anyhow::anyhow!("Missing block at index {}", i))?;
+            let version = block.hash_scheme.unwrap_or(1);
+            *report.blocks_by_version.entry(version).or_default() += 1;
+            if block.pruned {
+                report.pruned += 1;
+                continue;
+            }
+
+            let block_json = serde_json::to_value(&block)?;
+            let reason = match HashScheme::of_block(&block_json) {
+                Ok(scheme) if scheme.hash_block(&block_json) == block.hash => continue,
+                Ok(scheme) => format!(
+                    "Hash does not match under scheme {} ({:?})",
+                    scheme.version(),
+                    scheme
+                ),
+                Err(e) => e.to_string(),
+            };
+            report.failures.push(HashSchemeFailure {
+                index: i,
+                version,
+                reason,
+            });
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{LedgerConfig, MefBlock};
+    use serde_json::json;
+
+    #[test]
+    fn test_es_number_formatting() {
+        let cases = [
+            (0.0, "0"),
+            (-0.0, "0"),
+            (1.0, "1"),
+            (-1.5, "-1.5"),
+            (4.35, "4.35"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (333333333.3333333, "333333333.3333333"),
+            (1e20, "100000000000000000000"),
+            (1e21, "1e+21"),
+            (0.000001, "0.000001"),
+            (1e-7, "1e-7"),
+            (5e-324, "5e-324"),
+            (1.7976931348623157e308, "1.7976931348623157e+308"),
+            (9007199254740992.0, "9007199254740992"),
+            (123.456e-30, "1.23456e-28"),
+        ];
+        for (value, expected) in cases {
+            assert_eq!(format_es_number(value), expected, "formatting {:e}", value);
+        }
+    }
+
+    #[test]
+    fn test_jcs_rfc8785_example() {
+        // RFC 8785, section 3.2.2
+        let input: JsonValue = serde_json::from_str(
+            r#"{
+                "numbers": [333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001],
+                "string": "\u20ac$\u000F\u000aA'\u0042\u0022\u005c\\\"\/",
+                "literals": [null, true, false]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            jcs_string(&input),
+            r#"{"literals":[null,true,false],"numbers":[333333333.3333333,1e+30,4.5,0.002,1e-27],"string":"€$\u000f\nA'B\"\\\\\"/"}"#
+        );
+    }
+
+    #[test]
+    fn test_jcs_sorts_keys_by_utf16() {
+        // U+FB33 sorts before U+1F600 in UTF-8 order but after it in UTF-16
+        let input = json!({"\u{fb33}": 1, "\u{1f600}": 2, "a": 3});
+        assert_eq!(
+            jcs_string(&input),
+            "{\"a\":3,\"\u{1f600}\":2,\"\u{fb33}\":1}"
+        );
+    }
+
+    #[test]
+    fn test_scheme_dispatch() {
+        let legacy = json!({"index": 0, "value": 0.5});
+        let mut jcs = legacy.clone();
+        jcs["hash_scheme"] = json!(2);
+
+        assert_eq!(HashScheme::of_block(&legacy).unwrap(), HashScheme::Legacy);
+        assert_eq!(HashScheme::of_block(&jcs).unwrap(), HashScheme::Jcs);
+        assert_eq!(
+            MEFLedger::compute_block_hash(&jcs),
+            HashScheme::Jcs.hash_block(&jcs)
+        );
+        assert_ne!(
+            HashScheme::Legacy.hash_block(&legacy),
+            HashScheme::Jcs.hash_block(&legacy)
+        );
+
+        let mut unknown = legacy.clone();
+        unknown["hash_scheme"] = json!(99);
+        assert!(HashScheme::of_block(&unknown).is_err());
+    }
+
+    #[test]
+    fn test_mixed_scheme_ledger_reverifies() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let tic = |i: usize| {
+            json!({
+                "tic_id": format!("tic-{}", i),
+                "seed": "SEED",
+                "fixpoint": [0.1, 0.2, 0.3],
+                "invariants": {"variance": 0.1},
+                "sigma_bar": {"psi": 1.0 / 3.0},
+                "window": [],
+                "proof": null
+            })
+        };
+
+        let legacy = LedgerConfig {
+            hash_scheme: HashScheme::Legacy,
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), legacy).unwrap();
+        let first = ledger.append_block(&tic(0), &json!({"id": 0})).unwrap();
+        assert_eq!(first.hash_scheme, None);
+        drop(ledger);
+
+        let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        let second = ledger.append_block(&tic(1), &json!({"id": 1})).unwrap();
+        assert_eq!(second.hash_scheme, Some(2));
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+
+        let report = ledger.verify_hash_schemes().unwrap();
+        assert!(report.is_ok());
+        assert_eq!(report.blocks_by_version, BTreeMap::from([(1, 1), (2, 1)]));
+
+        // Relabelling a block with another scheme breaks its hash
+        let mut relabelled: MefBlock = second.clone();
+        relabelled.hash_scheme = Some(1);
+        assert!(!ledger.verify_block_hash(&relabelled));
+        relabelled.hash_scheme = Some(99);
+        assert!(!ledger.verify_block_hash(&relabelled));
+    }
+}
@@ -4,9 +4,34 @@
 //!
 //! Migrated from: MEF-Core_v1.0/src/ledger/
 
+pub mod archive;
+pub mod checkpoint;
+pub mod hashing;
+pub mod lock;
 pub mod mef_block;
+pub mod merkle;
+pub mod query;
+pub mod segment;
+pub mod signing;
+pub mod sync;
+pub mod verify;
 
+pub use archive::{ArchiveEntry, ArchiveEntryKind, ArchiveManifest, ArtifactStores};
+pub use checkpoint::Checkpoint;
+pub use hashing::{HashScheme, HashSchemeFailure, HashSchemeReport};
+pub use lock::{AppendConflict, LedgerLock, LedgerSnapshot};
 pub use mef_block::{
-    BlockSummary, ChainStatistics, CompactTic, LedgerIndex, LedgerMetadata, MEFLedger, MefBlock,
-    TimeRange,
+    BlockSummary, ChainStatistics, CompactTic, LedgerConfig, LedgerIndex, LedgerMetadata,
+    LedgerObserver, MEFLedger, MefBlock, TimeRange,
+};
+pub use merkle::{CompactRange, InclusionProof, LedgerRoot, MerkleAccumulator};
+pub use query::{LedgerQuery, SecondaryIndex};
+pub use segment::{RecordLocation, SegmentStore};
+pub use signing::{KeyRegistry, KeyStatus, LedgerSigner, SignerKey};
+pub use sync::{
+    apply_fast_forward, compare_ledgers, plan_reconciliation, BlockSource, ForkReport,
+    LedgerRelation, ReconciliationPlan, SyncAction,
+};
+pub use verify::{
+    BlockFailure, FailureKind, ResumePoint, VerificationProgress, VerificationReport, VerifyOptions,
 };
@@ -0,0 +1,351 @@
+//! Multi-process coordination for a ledger directory.
+//!
+//! Writers serialize on an advisory lock on `ledger.lock` and re-read the
+//! on-disk index under the lock before appending, so the API server and a CLI
+//! can share one ledger. Readers never take the lock: a [`LedgerSnapshot`]
+//! pins one index generation together with open handles on its segment
+//! files, which stay valid while writers append or prune.
+
+use anyhow::{Context, Result};
+use fs2::FileExt;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+use std::sync::Mutex;
+
+use crate::mef_block::{BlockSummary, LedgerIndex, MefBlock};
+use crate::segment::SegmentStore;
+
+/// Lock file name inside the ledger directory
+pub const LOCK_FILE: &str = "ledger.lock";
+
+/// Held exclusive advisory lock on a ledger directory
+///
+/// The lock is released when the guard is dropped or the process exits.
+#[derive(Debug)]
+pub struct LedgerLock {
+    file: File,
+}
+
+impl LedgerLock {
+    /// Open the lock file of a ledger directory without locking it
+    pub(crate) fn open_file(ledger_path: &Path) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .read(true)
+            .write(true)
+            .open(ledger_path.join(LOCK_FILE))
+            .context("Failed to open ledger lock file")
+    }
+
+    /// Block until the exclusive lock is acquired
+    ///
+    /// # Arguments
+    /// * `ledger_path` - Ledger directory
+    pub fn acquire(ledger_path: &Path) -> Result<Self> {
+        Self::acquire_file(Self::open_file(ledger_path)?)
+    }
+
+    /// Acquire the lock if no other writer holds it
+    ///
+    /// # Arguments
+    /// * `ledger_path` - Ledger directory
+    pub fn try_acquire(ledger_path: &Path) -> Result<Option<Self>> {
+        let file = Self::open_file(ledger_path)?;
+        match file.try_lock_exclusive() {
+            Ok(()) => Ok(Some(Self { file })),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => Ok(None),
+            Err(e) => Err(e).context("Failed to lock ledger"),
+        }
+    }
+
+    /// Lock an already opened lock file handle
+    pub(crate) fn acquire_file(file: File) -> Result<Self> {
+        file.lock_exclusive().context("Failed to lock ledger")?;
+        Ok(Self { file })
+    }
+}
+
+impl Drop for LedgerLock {
+    fn drop(&mut self) {
+        let _ = self.file.unlock();
+    }
+}
+
+/// Returned by [`MEFLedger::compare_and_append`](crate::MEFLedger::compare_and_append)
+/// when another writer appended first
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+#[error(
+    "Ledger head moved: expected next index {expected_index} after {expected_previous_hash}, \
+     found next index {actual_index} after {actual_previous_hash}"
+)]
+pub struct AppendConflict {
+    pub expected_index: i32,
+    pub expected_previous_hash: String,
+    pub actual_index: i32,
+    pub actual_previous_hash: String,
+}
+
+/// Consistent read-only view of a ledger at one point in time
+///
+/// Opening a snapshot never blocks or is blocked by writers.
+pub struct LedgerSnapshot {
+    index: LedgerIndex,
+    files: Mutex<HashMap<u32, File>>,
+}
+
+impl LedgerSnapshot {
+    /// Capture the current state of a ledger directory
+    ///
+    /// # Arguments
+    /// * `ledger_path` - Ledger directory
+    pub fn open(ledger_path: impl AsRef<Path>) -> Result<Self> {
+        let ledger_path = ledger_path.as_ref();
+        let store_dir = ledger_path.join(crate::mef_block::SEGMENTS_DIR);
+
+        // The index is replaced atomically, but a pruning writer may swap a
+        // segment between reading the index and opening the files; retry
+        // until both come from the same generation.
+        for _ in 0..8 {
+            let index =
+                crate::MEFLedger::load_index(&ledger_path.join(crate::mef_block::INDEX_FILE))?;
+            let mut files = HashMap::new();
+            for summary in &index.blocks {
+                if let std::collections::hash_map::Entry::Vacant(entry) =
+                    files.entry(summary.segment)
+                {
+                    let path = store_dir.join(SegmentStore::segment_file_name(summary.segment));
+                    entry.insert(
+                        File::open(&path).with_context(|| format!("Failed to open {:?}", path))?,
+                    );
+                }
+            }
+
+            let snapshot = Self {
+                index,
+                files: Mutex::new(files),
+            };
+            if snapshot.tails_match()? {
+                return Ok(snapshot);
+            }
+        }
+        anyhow::bail!("Ledger kept changing while taking a snapshot")
+    }
+
+    /// Check the last record of each segment against the index
+    fn tails_match(&self) -> Result<bool> {
+        let mut tails = HashMap::new();
+        for summary in &self.index.blocks {
+            tails.insert(summary.segment, summary);
+        }
+        for summary in tails.values() {
+            match self.read(summary) {
+                Ok(block) if block.hash == summary.hash => {}
+                _ => return Ok(false),
+            }
+        }
+        Ok(true)
+    }
+
+    pub(crate) fn read(&self, summary: &BlockSummary) -> Result<MefBlock> {
+        let mut files = self
+            .files
+            .lock()
+            .map_err(|_| anyhow::anyhow!("Snapshot file lock poisoned"))?;
+        let file = files
+            .get_mut(&summary.segment)
+            .ok_or_else(|| anyhow::anyhow!("Segment {} not in snapshot", summary.segment))?;
+        SegmentStore::read_from(file, summary.offset)
+    }
+
+    /// Index captured by the snapshot
+    pub fn index(&self) -> &LedgerIndex {
+        &self.index
+    }
+
+    /// Number of blocks in the snapshot
+    pub fn len(&self) -> usize {
+        self.index.blocks.len()
+    }
+
+    /// Whether the snapshot holds no blocks
+    pub fn is_empty(&self) -> bool {
+        self.index.blocks.is_empty()
+    }
+
+    /// Retrieve a block by index
+    ///
+    /// # Arguments
+    /// * `index` - Block index
+    pub fn get_block(&self, index: i32) -> Result<Option<MefBlock>> {
+        if index < 0 {
+            return Ok(None);
+        }
+        let summary = match self.index.blocks.get(index as usize) {
+            Some(summary) => summary,
+            None => return Ok(None),
+        };
+        let block = self.read(summary)?;
+        if block.index != index || block.hash != summary.hash {
+            anyhow::bail!(
+                "Snapshot record for block {} does not match its index",
+                index
+            );
+        }
+        Ok(Some(block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MEFLedger;
+    use serde_json::json;
+
+    fn tic(i: usize) -> serde_json::Value {
+        json!({
+            "tic_id": format!("tic-{}", i),
+            "seed": "SEED",
+            "fixpoint": [0.1, 0.2, 0.3],
+            "invariants": {"variance": 0.1},
+            "sigma_bar": {},
+            "window": [],
+            "proof": null
+        })
+    }
+
+    #[test]
+    fn test_lock_excludes_other_writers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let held = LedgerLock::acquire(temp_dir.path()).unwrap();
+        assert!(LedgerLock::try_acquire(temp_dir.path()).unwrap().is_none());
+        drop(held);
+        assert!(LedgerLock::try_acquire(temp_dir.path()).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_handles_see_each_others_appends() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut a = MEFLedger::new(temp_dir.path()).unwrap();
+        let mut b = MEFLedger::new(temp_dir.path()).unwrap();
+
+        a.append_block(&tic(0), &json!({"id": 0})).unwrap();
+        let second = b.append_block(&tic(1), &json!({"id": 1})).unwrap();
+        assert_eq!(second.index, 1);
+        let third = a.append_block(&tic(2), &json!({"id": 2})).unwrap();
+        assert_eq!(third.previous_hash, second.hash);
+
+        let reopened = MEFLedger::new(temp_dir.path()).unwrap();
+        assert_eq!(reopened.index().current_index, 2);
+        assert!(reopened.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_append_after_uses_current_head() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut a = MEFLedger::new(temp_dir.path()).unwrap();
+        let mut b = MEFLedger::new(temp_dir.path()).unwrap();
+
+        let first = a.append_block(&tic(0), &json!({"id": 0})).unwrap();
+
+        // `b` has not seen block 0 yet, but the caller has
+        let second = b
+            .append_after(&first.hash, &tic(1), &json!({"id": 1}))
+            .unwrap();
+        assert_eq!(second.index, 1);
+        assert_eq!(second.previous_hash, first.hash);
+
+        let err = a
+            .append_after(&first.hash, &tic(2), &json!({"id": 2}))
+            .unwrap_err();
+        let conflict = err.downcast_ref::<AppendConflict>().unwrap();
+        assert_eq!(conflict.actual_index, 2);
+        assert_eq!(conflict.actual_previous_hash, second.hash);
+    }
+
+    #[test]
+    fn test_compare_and_append_conflict() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut a = MEFLedger::new(temp_dir.path()).unwrap();
+        let mut b = MEFLedger::new(temp_dir.path()).unwrap();
+        let genesis = a.get_last_hash().unwrap();
+
+        let first = a
+            .compare_and_append(0, &genesis, &tic(0), &json!({"id": 0}))
+            .unwrap();
+
+        // `b` still believes the ledger is empty
+        let err = b
+            .compare_and_append(0, &genesis, &tic(1), &json!({"id": 1}))
+            .unwrap_err();
+        let conflict = err.downcast_ref::<AppendConflict>().unwrap();
+        assert_eq!(conflict.actual_index, 1);
+        assert_eq!(conflict.actual_previous_hash, first.hash);
+
+        b.compare_and_append(1, &first.hash, &tic(1), &json!({"id": 1}))
+            .unwrap();
+        assert_eq!(
+            MEFLedger::new(temp_dir.path())
+                .unwrap()
+                .index()
+                .blocks
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_concurrent_writers() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        MEFLedger::new(temp_dir.path()).unwrap();
+
+        let writers: Vec<_> = (0..3)
+            .map(|w| {
+                let path = temp_dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let mut ledger = MEFLedger::new(&path).unwrap();
+                    for i in 0..5 {
+                        ledger
+                            .append_block(&tic(w * 10 + i), &json!({"id": i}))
+                            .unwrap();
+                    }
+                })
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        let ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        assert_eq!(ledger.index().blocks.len(), 15);
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_snapshot_is_stable_across_writes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        for i in 0..3 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+
+        let snapshot = LedgerSnapshot::open(temp_dir.path()).unwrap();
+        let before = snapshot.get_block(1).unwrap().unwrap();
+
+        // Appends and a pruning rewrite do not disturb the open snapshot
+        ledger.create_checkpoint().unwrap();
+        ledger.prune_payloads(2, false).unwrap();
+        ledger.append_block(&tic(9), &json!({"id": 9})).unwrap();
+
+        assert_eq!(snapshot.len(), 3);
+        let after = snapshot.get_block(1).unwrap().unwrap();
+        assert_eq!(after.hash, before.hash);
+        assert!(!after.pruned);
+        assert!(snapshot.get_block(3).unwrap().is_none());
+
+        let fresh = LedgerSnapshot::open(temp_dir.path()).unwrap();
+        assert_eq!(fresh.len(), 5);
+        assert!(fresh.get_block(1).unwrap().unwrap().pruned);
+    }
+}
@@ -7,8 +7,34 @@ use anyhow::{Context, Result};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use sha2::{Digest, Sha256};
+use std::fs::File;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use crate::checkpoint::{self, Checkpoint, COLD_DIR};
+use crate::hashing::{self, HashScheme};
+use crate::lock::{AppendConflict, LedgerLock};
+use crate::merkle::{InclusionProof, LedgerRoot, MerkleAccumulator};
+use crate::query::{LedgerQuery, SecondaryIndex};
+use crate::segment::{
+    self as segment_store, RecordLocation, SegmentStore, DEFAULT_MAX_SEGMENT_BYTES,
+};
+use crate::signing::{KeyRegistry, LedgerSigner};
+
+/// Index file name inside the ledger directory
+pub(crate) const INDEX_FILE: &str = "ledger_index.json";
+
+/// Key registry file inside the ledger directory
+///
+/// Unlike the index, the registry cannot be rebuilt from the segments, so it
+/// is kept in its own durably written file.
+pub(crate) const REGISTRY_FILE: &str = "key_registry.json";
+
+/// Directory holding the append-only segment files
+pub(crate) const SEGMENTS_DIR: &str = "segments";
+
+/// Directory legacy per-block JSON files are moved to after migration
+const LEGACY_DIR: &str = "legacy";
 
 /// Ledger index metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,6 +42,12 @@ pub struct LedgerMetadata {
     pub created: String,
     pub last_updated: String,
     pub version: String,
+    /// Signer keys trusted to sign blocks of this ledger
+    #[serde(default)]
+    pub key_registry: KeyRegistry,
+    /// Incremented on every index write so other processes notice changes
+    #[serde(default)]
+    pub generation: u64,
 }
 
 impl Default for LedgerMetadata {
@@ -25,6 +57,8 @@ impl Default for LedgerMetadata {
             created: now.clone(),
             last_updated: now,
             version: "1.0.0".to_string(),
+            key_registry: KeyRegistry::default(),
+            generation: 0,
         }
     }
 }
@@ -37,6 +71,33 @@ pub struct BlockSummary {
     pub tic_id: String,
     pub timestamp: String,
     pub file: String,
+    /// Segment number holding the block record
+    #[serde(default)]
+    pub segment: u32,
+    /// Byte offset of the block record within its segment
+    #[serde(default)]
+    pub offset: u64,
+    /// Seed of the committed TIC
+    #[serde(default)]
+    pub seed: Option<String>,
+    /// Fixpoint norm of the committed TIC
+    #[serde(default)]
+    pub fixpoint_norm: Option<f64>,
+    /// Last index covered, if this is a checkpoint block
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<i32>,
+    /// Whether the block payload has been pruned
+    #[serde(default)]
+    pub pruned: bool,
+}
+
+impl BlockSummary {
+    pub(crate) fn location(&self) -> RecordLocation {
+        RecordLocation {
+            segment: self.segment,
+            offset: self.offset,
+        }
+    }
 }
 
 /// Ledger index structure
@@ -80,6 +141,22 @@ pub struct MefBlock {
     pub data: CompactTic,
     pub proof: JsonValue,
     pub hash: String,
+    /// Key ID of the signer (covered by the block hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    /// Hex-encoded Ed25519 signature over the block hash
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+    /// State commitment carried by checkpoint blocks (covered by the block hash)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checkpoint: Option<Checkpoint>,
+    /// Set once the TIC payload was pruned (not covered by the block hash)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub pruned: bool,
+    /// Version of the [`HashScheme`] the block hash was computed with
+    /// (covered by the block hash); absent for legacy scheme 1 blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hash_scheme: Option<u32>,
 }
 
 /// Chain statistics
@@ -101,12 +178,55 @@ pub struct TimeRange {
     pub last: Option<String>,
 }
 
+/// Ledger storage configuration
+#[derive(Debug, Clone)]
+pub struct LedgerConfig {
+    /// Segment size at which appends rotate to a new segment file
+    pub max_segment_bytes: u64,
+    /// Key used to sign new blocks (unsigned ledger if `None`)
+    pub signer: Option<LedgerSigner>,
+    /// Canonical hashing scheme for new blocks
+    pub hash_scheme: HashScheme,
+}
+
+impl Default for LedgerConfig {
+    fn default() -> Self {
+        Self {
+            max_segment_bytes: DEFAULT_MAX_SEGMENT_BYTES,
+            signer: None,
+            hash_scheme: HashScheme::CURRENT,
+        }
+    }
+}
+
+/// Receives notifications about ledger changes, e.g. to export metrics
+pub trait LedgerObserver: Send + Sync {
+    /// Called after a block was durably appended
+    ///
+    /// # Arguments
+    /// * `block` - The appended block
+    /// * `height` - Number of blocks in the ledger, including `block`
+    fn block_appended(&self, block: &MefBlock, height: usize);
+}
+
 /// MEF Ledger system implementing hash-chained blocks
 /// B_i = H(tic_i, snapshot_i, B_{i-1})
+///
+/// Blocks are persisted in append-only segment files; `ledger_index.json` is
+/// a cache that is rebuilt from the segments whenever it falls out of sync.
+/// Mutations take the directory's advisory write lock, so several processes
+/// may open the same ledger.
 pub struct MEFLedger {
     ledger_path: PathBuf,
     index: LedgerIndex,
     genesis_hash: String,
+    merkle: MerkleAccumulator,
+    secondary: SecondaryIndex,
+    segments: SegmentStore,
+    signer: Option<LedgerSigner>,
+    hash_scheme: HashScheme,
+    lock_file: File,
+    observer: Option<Arc<dyn LedgerObserver>>,
 }
 
 impl MEFLedger {
@@ -115,22 +235,410 @@ impl MEFLedger {
     /// # Arguments
     /// * `ledger_path` - Directory for ledger storage
     pub fn new(ledger_path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_config(ledger_path, LedgerConfig::default())
+    }
+
+    /// Create a new MEF Ledger with explicit storage configuration
+    ///
+    /// Opening a directory in the legacy per-block JSON layout migrates it
+    /// to segment files first.
+    ///
+    /// # Arguments
+    /// * `ledger_path` - Directory for ledger storage
+    /// * `config` - Storage configuration
+    pub fn with_config(ledger_path: impl AsRef<Path>, config: LedgerConfig) -> Result<Self> {
         let ledger_path = ledger_path.as_ref().to_path_buf();
         std::fs::create_dir_all(&ledger_path).context("Failed to create ledger directory")?;
 
-        let index_file = ledger_path.join("ledger_index.json");
+        // Opening may truncate a torn tail or rebuild the index, so it must
+        // not race with another process appending
+        let lock_file = LedgerLock::open_file(&ledger_path)?;
+        let _lock = LedgerLock::acquire_file(lock_file.try_clone()?)?;
+
+        let index_file = ledger_path.join(INDEX_FILE);
         let index = Self::load_index(&index_file)?;
         let genesis_hash = "0".repeat(64);
+        let segments =
+            SegmentStore::open(ledger_path.join(SEGMENTS_DIR), config.max_segment_bytes)?;
 
-        Ok(Self {
+        let mut ledger = Self {
             ledger_path,
             index,
             genesis_hash,
+            merkle: MerkleAccumulator::new(),
+            secondary: SecondaryIndex::default(),
+            segments,
+            signer: None,
+            hash_scheme: config.hash_scheme,
+            lock_file,
+            observer: None,
+        };
+
+        ledger.migrate_legacy_layout_locked()?;
+        if !ledger.load_key_registry()? && !ledger.key_registry().keys.is_empty() {
+            // Ledgers written before the registry had its own file
+            ledger.save_key_registry()?;
+        }
+        if !ledger.index_matches_segments()? {
+            ledger.rebuild_index_locked()?;
+        }
+        ledger.merkle = MerkleAccumulator::from_block_hashes(
+            ledger.index.blocks.iter().map(|b| b.hash.as_str()),
+        );
+        ledger.secondary = SecondaryIndex::build(&ledger.index.blocks);
+
+        if let Some(signer) = config.signer {
+            ledger.install_signer(signer)?;
+        }
+
+        Ok(ledger)
+    }
+
+    /// Check a configured signer against the key registry
+    ///
+    /// The first signer of a ledger is registered automatically; afterwards a
+    /// new key must be introduced through [`MEFLedger::rotate_signer`]. A
+    /// ledger that already holds signed blocks but has lost its registry is
+    /// refused rather than handed to whichever key is configured.
+    fn install_signer(&mut self, signer: LedgerSigner) -> Result<()> {
+        let registry = &mut self.index.metadata.key_registry;
+        match registry.get(signer.key_id()) {
+            Some(key) => {
+                if key.public_key != signer.public_key_hex() {
+                    anyhow::bail!(
+                        "Signer {} does not match its registered public key",
+                        signer.key_id()
+                    );
+                }
+                if registry.active().map(|k| k.key_id.as_str()) != Some(signer.key_id()) {
+                    anyhow::bail!("Signer {} is not the active key", signer.key_id());
+                }
+            }
+            None => {
+                if let Some(active) = registry.active() {
+                    anyhow::bail!(
+                        "Signer {} is not registered (active key is {}); rotate to it first",
+                        signer.key_id(),
+                        active.key_id
+                    );
+                }
+                if let Some(index) = self.first_signed_block()? {
+                    anyhow::bail!(
+                        "Block {} is signed but the ledger has no key registry; restore {} \
+                         before configuring a signer",
+                        index,
+                        REGISTRY_FILE
+                    );
+                }
+                self.index.metadata.key_registry.register(
+                    signer.key_id(),
+                    &signer.public_key_hex(),
+                    self.index.current_index + 1,
+                )?;
+                self.save_key_registry()?;
+                self.save_index()?;
+            }
+        }
+
+        self.signer = Some(signer);
+        Ok(())
+    }
+
+    /// Get the signer key registry
+    pub fn key_registry(&self) -> &KeyRegistry {
+        &self.index.metadata.key_registry
+    }
+
+    /// Rotate to a new signer key for all subsequent blocks
+    ///
+    /// # Arguments
+    /// * `signer` - New signing key
+    pub fn rotate_signer(&mut self, signer: LedgerSigner) -> Result<()> {
+        self.with_write_lock(|ledger| {
+            let from_index = ledger.index.current_index + 1;
+            ledger.index.metadata.key_registry.rotate(
+                signer.key_id(),
+                &signer.public_key_hex(),
+                from_index,
+            )?;
+            ledger.signer = Some(signer);
+            ledger.save_key_registry()?;
+            ledger.save_index()
         })
     }
 
+    /// Revoke a signer key
+    ///
+    /// # Arguments
+    /// * `key_id` - Key to revoke
+    /// * `from_index` - First block whose signature becomes invalid (`None`
+    ///   invalidates every block signed by the key)
+    pub fn revoke_signer_key(&mut self, key_id: &str, from_index: Option<i32>) -> Result<()> {
+        self.with_write_lock(|ledger| {
+            ledger
+                .index
+                .metadata
+                .key_registry
+                .revoke(key_id, from_index)?;
+            if ledger.signer.as_ref().map(|s| s.key_id()) == Some(key_id) {
+                ledger.signer = None;
+            }
+            ledger.save_key_registry()?;
+            ledger.save_index()
+        })
+    }
+
+    /// Get the ledger directory
+    pub fn path(&self) -> &Path {
+        &self.ledger_path
+    }
+
+    /// Get the in-memory ledger index
+    pub fn index(&self) -> &LedgerIndex {
+        &self.index
+    }
+
+    /// Segment storage holding the block records
+    pub(crate) fn segments(&self) -> &SegmentStore {
+        &self.segments
+    }
+
+    /// Previous hash of the first block
+    pub(crate) fn genesis_hash(&self) -> &str {
+        &self.genesis_hash
+    }
+
+    /// Check that the index describes exactly the records in the segments
+    fn index_matches_segments(&self) -> Result<bool> {
+        let last = match self.index.blocks.last() {
+            Some(last) => last,
+            None => return Ok(self.segments.is_empty() && self.index.current_index == -1),
+        };
+
+        // Indexes written before secondary indexing lack the query fields
+        if self.index.blocks.iter().any(|b| b.seed.is_none()) {
+            return Ok(false);
+        }
+
+        if self.index.blocks.len() as i32 != self.index.current_index + 1
+            || last.index != self.index.current_index
+            || last.file != SegmentStore::segment_file_name(last.segment)
+        {
+            return Ok(false);
+        }
+
+        // The last record of every segment must sit where the index says;
+        // rewritten (pruned) segments shift their records
+        let mut segment_tails = std::collections::BTreeMap::new();
+        for summary in &self.index.blocks {
+            segment_tails.insert(summary.segment, summary);
+        }
+        for summary in segment_tails.values() {
+            match self.segments.read(summary.location()) {
+                Ok(block) if block.hash == summary.hash && block.index == summary.index => {}
+                _ => return Ok(false),
+            }
+        }
+
+        self.segments.is_tail(last.location())
+    }
+
+    /// Rebuild the ledger index by scanning every segment record
+    pub fn rebuild_index(&mut self) -> Result<()> {
+        let _lock = LedgerLock::acquire_file(self.lock_file.try_clone()?)?;
+        self.rebuild_index_locked()
+    }
+
+    /// Rebuild the index while the caller holds the write lock
+    fn rebuild_index_locked(&mut self) -> Result<()> {
+        let mut blocks = Vec::new();
+        for (location, block) in self.segments.scan()? {
+            if block.index != blocks.len() as i32 {
+                anyhow::bail!(
+                    "Segment records out of order: expected block {}, found {}",
+                    blocks.len(),
+                    block.index
+                );
+            }
+            blocks.push(Self::summarize(&block, location));
+        }
+
+        self.index.current_index = blocks.len() as i32 - 1;
+        self.index.blocks = blocks;
+        self.merkle =
+            MerkleAccumulator::from_block_hashes(self.index.blocks.iter().map(|b| b.hash.as_str()));
+        self.secondary = SecondaryIndex::build(&self.index.blocks);
+        self.save_index()
+    }
+
+    /// Index summary for a stored block
+    fn summarize(block: &MefBlock, location: RecordLocation) -> BlockSummary {
+        BlockSummary {
+            index: block.index,
+            hash: block.hash.clone(),
+            tic_id: block.tic_id.clone(),
+            timestamp: block.timestamp.clone(),
+            file: SegmentStore::segment_file_name(location.segment),
+            segment: location.segment,
+            offset: location.offset,
+            seed: Some(block.data.seed.clone()),
+            fixpoint_norm: Some(block.data.fixpoint_norm),
+            checkpoint: block.checkpoint.as_ref().map(|c| c.up_to_index),
+            pruned: block.pruned,
+        }
+    }
+
+    /// Migrate legacy `block_{:06}.mef` files into segment storage
+    ///
+    /// Each legacy block is verified (hash and linkage) before it is copied.
+    /// The migration resumes where it stopped if interrupted, and the legacy
+    /// files are moved to `legacy/` once every block is in the segments.
+    /// Returns the number of blocks migrated.
+    pub fn migrate_legacy_layout(&mut self) -> Result<usize> {
+        let _lock = LedgerLock::acquire_file(self.lock_file.try_clone()?)?;
+        self.migrate_legacy_layout_locked()
+    }
+
+    /// Migrate legacy blocks while the caller holds the write lock
+    fn migrate_legacy_layout_locked(&mut self) -> Result<usize> {
+        let legacy_files = self.legacy_block_files()?;
+        if legacy_files.is_empty() {
+            return Ok(0);
+        }
+
+        let existing = self.segments.scan()?;
+        let mut prev_hash = existing
+            .last()
+            .map(|(_, b)| b.hash.clone())
+            .unwrap_or_else(|| self.genesis_hash.clone());
+        let mut migrated = 0;
+
+        for i in existing.len().. {
+            let block_file = self.ledger_path.join(format!("block_{:06}.mef", i));
+            if !block_file.exists() {
+                break;
+            }
+            let contents =
+                std::fs::read_to_string(&block_file).context("Failed to read legacy block")?;
+            let block: MefBlock =
+                serde_json::from_str(&contents).context("Failed to parse legacy block")?;
+
+            if block.index != i as i32
+                || block.previous_hash != prev_hash
+                || !self.verify_block_hash(&block)
+            {
+                anyhow::bail!("Legacy block {} failed verification; migration aborted", i);
+            }
+
+            self.segments.append(&block)?;
+            prev_hash = block.hash;
+            migrated += 1;
+        }
+
+        let legacy_dir = self.ledger_path.join(LEGACY_DIR);
+        std::fs::create_dir_all(&legacy_dir).context("Failed to create legacy directory")?;
+        for file in legacy_files {
+            if let Some(name) = file.file_name() {
+                std::fs::rename(&file, legacy_dir.join(name))
+                    .context("Failed to move legacy block file")?;
+            }
+        }
+
+        self.rebuild_index_locked()?;
+        Ok(migrated)
+    }
+
+    /// Legacy per-block JSON files in the ledger directory
+    fn legacy_block_files(&self) -> Result<Vec<PathBuf>> {
+        let mut files = Vec::new();
+        for entry in std::fs::read_dir(&self.ledger_path).context("Failed to read ledger dir")? {
+            let path = entry?.path();
+            let is_block = path
+                .file_name()
+                .map(|n| n.to_string_lossy())
+                .map(|n| n.starts_with("block_") && n.ends_with(".mef"))
+                .unwrap_or(false);
+            if is_block && path.is_file() {
+                files.push(path);
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+
+    /// Run a mutation while holding the ledger write lock
+    ///
+    /// Changes other processes made since this handle last wrote are loaded
+    /// first, so the mutation always applies to the current head.
+    fn with_write_lock<T>(&mut self, op: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+        let _lock = LedgerLock::acquire_file(self.lock_file.try_clone()?)?;
+        self.refresh()?;
+        op(self)
+    }
+
+    /// Load the on-disk state written by other processes
+    ///
+    /// Must be called while holding the write lock.
+    fn refresh(&mut self) -> Result<()> {
+        let disk = Self::load_index(&self.ledger_path.join(INDEX_FILE))?;
+        self.segments.refresh()?;
+        if disk.metadata.generation != self.index.metadata.generation {
+            self.adopt_index(disk);
+        }
+        self.load_key_registry()?;
+
+        if !self.index_matches_segments()? {
+            // A writer died between its segment write and its index update
+            self.segments.recover()?;
+            self.rebuild_index_locked()?;
+        }
+        Ok(())
+    }
+
+    /// Replace the in-memory index with a newer one from disk
+    fn adopt_index(&mut self, disk: LedgerIndex) {
+        let known = self.index.blocks.len();
+        let extends = disk.blocks.len() >= known
+            && (known == 0 || disk.blocks[known - 1].hash == self.index.blocks[known - 1].hash);
+
+        if extends {
+            for summary in &disk.blocks[known..] {
+                self.merkle.push(&summary.hash);
+                self.secondary.insert(summary);
+            }
+        } else {
+            self.merkle =
+                MerkleAccumulator::from_block_hashes(disk.blocks.iter().map(|b| b.hash.as_str()));
+            self.secondary = SecondaryIndex::build(&disk.blocks);
+        }
+        self.index = disk;
+    }
+
+    /// Fail if another process rotated or revoked this handle's signer, or if
+    /// the ledger requires signed blocks and no signer is installed
+    fn ensure_signer_active(&self) -> Result<()> {
+        let registry = &self.index.metadata.key_registry;
+        match &self.signer {
+            Some(signer) => {
+                if registry.active().map(|k| k.key_id.as_str()) != Some(signer.key_id()) {
+                    anyhow::bail!("Signer {} is no longer the active key", signer.key_id());
+                }
+            }
+            None => {
+                if let Some(from) = registry.signed_from_index() {
+                    anyhow::bail!(
+                        "Ledger requires signed blocks since index {}; configure the active \
+                         signer (MEF_LEDGER_SIGNER_KEY_ID / MEF_LEDGER_SIGNER_SECRET) to append",
+                        from
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+
     /// Load ledger index from disk
-    fn load_index(index_file: &Path) -> Result<LedgerIndex> {
+    pub(crate) fn load_index(index_file: &Path) -> Result<LedgerIndex> {
         if index_file.exists() {
             let contents =
                 std::fs::read_to_string(index_file).context("Failed to read ledger index")?;
@@ -143,108 +651,94 @@ impl MEFLedger {
     }
 
     /// Save ledger index to disk
+    ///
+    /// The index is written to a temporary file and renamed into place so
+    /// readers never observe a partially written index.
     fn save_index(&mut self) -> Result<()> {
         self.index.metadata.last_updated = Utc::now().format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string();
+        self.index.metadata.generation += 1;
 
-        let index_file = self.ledger_path.join("ledger_index.json");
+        let index_file = self.ledger_path.join(INDEX_FILE);
+        let tmp_file = self.ledger_path.join(format!("{}.tmp", INDEX_FILE));
         let json = serde_json::to_string_pretty(&self.index)
             .context("Failed to serialize ledger index")?;
-        std::fs::write(&index_file, json).context("Failed to write ledger index")?;
+        std::fs::write(&tmp_file, json).context("Failed to write ledger index")?;
+        std::fs::rename(&tmp_file, &index_file).context("Failed to replace ledger index")?;
 
         Ok(())
     }
 
-    /// Compute SHA256 hash of block data
+    /// Replace the registry copy held in the index with the registry file
     ///
-    /// # Arguments
-    /// * `block` - Block data (without hash field)
-    ///
-    /// Canonicalize JSON by recursively sorting all object keys
-    fn canonicalize_json(value: &JsonValue) -> JsonValue {
-        match value {
-            JsonValue::Object(map) => {
-                let mut sorted_map = serde_json::Map::new();
-                let mut keys: Vec<_> = map.keys().collect();
-                keys.sort();
-                for key in keys {
-                    sorted_map.insert(key.clone(), Self::canonicalize_json(&map[key]));
-                }
-                JsonValue::Object(sorted_map)
-            }
-            JsonValue::Array(arr) => {
-                JsonValue::Array(arr.iter().map(Self::canonicalize_json).collect())
-            }
-            _ => value.clone(),
+    /// Returns `false` if the ledger has no registry file yet.
+    fn load_key_registry(&mut self) -> Result<bool> {
+        let path = self.ledger_path.join(REGISTRY_FILE);
+        if !path.exists() {
+            return Ok(false);
         }
+        let contents = std::fs::read_to_string(&path).context("Failed to read key registry")?;
+        self.index.metadata.key_registry =
+            serde_json::from_str(&contents).context("Failed to parse key registry")?;
+        Ok(true)
     }
 
-    /// Normalize floating point numbers in JSON for deterministic hashing
-    fn normalize_floats_in_json(value: &mut JsonValue) {
-        match value {
-            JsonValue::Object(map) => {
-                for (_key, val) in map.iter_mut() {
-                    Self::normalize_floats_in_json(val);
-                }
-            }
-            JsonValue::Array(arr) => {
-                for val in arr.iter_mut() {
-                    Self::normalize_floats_in_json(val);
-                }
-            }
-            JsonValue::Number(n) => {
-                // Only normalize if it's a float, not an integer
-                if let Some(f) = n.as_f64() {
-                    if !n.is_i64() && !n.is_u64() {
-                        // It's a float, normalize it
-                        let formatted = format!("{:.16e}", f);
-                        *value = JsonValue::String(formatted);
-                    }
-                }
+    /// Durably write the key registry file
+    ///
+    /// The registry is fsynced under a temporary name and renamed into
+    /// place, and the directory is synced so the rename survives a crash.
+    fn save_key_registry(&self) -> Result<()> {
+        let json = serde_json::to_vec_pretty(&self.index.metadata.key_registry)
+            .context("Failed to serialize key registry")?;
+        Self::write_synced(&self.ledger_path.join(REGISTRY_FILE), &json)
+            .context("Failed to write key registry")?;
+        segment_store::sync_dir(&self.ledger_path)
+    }
+
+    /// Replace `path` with `contents` through a synced temporary file
+    ///
+    /// The caller syncs the directory once all its files are renamed.
+    fn write_synced(path: &Path, contents: &[u8]) -> Result<()> {
+        let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+        tmp_name.push(".tmp");
+        let tmp_path = path.with_file_name(tmp_name);
+        {
+            let mut file = File::create(&tmp_path)?;
+            std::io::Write::write_all(&mut file, contents)?;
+            file.sync_all()?;
+        }
+        std::fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Index of the first block carrying a signer, if any
+    fn first_signed_block(&self) -> Result<Option<i32>> {
+        for summary in &self.index.blocks {
+            let block = self.segments.read(summary.location())?;
+            if block.signer.is_some() {
+                return Ok(Some(block.index));
             }
-            _ => {}
         }
+        Ok(None)
     }
 
+    /// Compute SHA256 hash of block data
+    ///
+    /// The canonical form is chosen by the block's `hash_scheme` field (see
+    /// [`HashScheme`]). A block with an unsupported scheme yields an empty
+    /// hash, which never verifies.
+    ///
+    /// # Arguments
+    /// * `block` - Block data (hash, signature and pruned flag are ignored)
     pub fn compute_block_hash(block: &JsonValue) -> String {
-        // Remove hash field if present
-        let mut block_data = block.clone();
-        if let Some(obj) = block_data.as_object_mut() {
-            obj.remove("hash");
+        match HashScheme::of_block(block) {
+            Ok(scheme) => scheme.hash_block(block),
+            Err(_) => String::new(),
         }
-
-        // Canonicalize JSON to ensure deterministic serialization
-        let mut canonical_block = Self::canonicalize_json(&block_data);
-
-        // Normalize all floating point numbers to strings for determinism
-        Self::normalize_floats_in_json(&mut canonical_block);
-
-        // Create deterministic string representation
-        let block_str = serde_json::to_string(&canonical_block).unwrap();
-
-        // Compute SHA256
-        let mut hasher = Sha256::new();
-        hasher.update(block_str.as_bytes());
-        format!("{:x}", hasher.finalize())
     }
 
     /// Get the most recent block in the ledger
     pub fn get_last_block(&self) -> Result<Option<MefBlock>> {
-        if self.index.current_index < 0 {
-            return Ok(None);
-        }
-
-        let block_file = self
-            .ledger_path
-            .join(format!("block_{:06}.mef", self.index.current_index));
-
-        if !block_file.exists() {
-            return Ok(None);
-        }
-
-        let contents = std::fs::read_to_string(&block_file).context("Failed to read block file")?;
-        let block: MefBlock = serde_json::from_str(&contents).context("Failed to parse block")?;
-
-        Ok(Some(block))
+        self.get_block(self.index.current_index)
     }
 
     /// Get hash of the last block
@@ -299,8 +793,8 @@ impl MEFLedger {
             tic_id,
             seed,
             fixpoint_norm,
-            invariants: Self::canonicalize_json(&tic["invariants"]),
-            sigma_bar: Self::canonicalize_json(&tic["sigma_bar"]),
+            invariants: hashing::sort_keys(&tic["invariants"]),
+            sigma_bar: hashing::sort_keys(&tic["sigma_bar"]),
             window,
         })
     }
@@ -311,6 +805,16 @@ impl MEFLedger {
     /// * `tic` - TIC data as JSON
     /// * `snapshot` - Snapshot data as JSON
     pub fn create_block(&self, tic: &JsonValue, snapshot: &JsonValue) -> Result<MefBlock> {
+        self.create_block_with(tic, snapshot, None)
+    }
+
+    /// Create a new MEF block, optionally carrying a checkpoint commitment
+    fn create_block_with(
+        &self,
+        tic: &JsonValue,
+        snapshot: &JsonValue,
+        checkpoint: Option<&Checkpoint>,
+    ) -> Result<MefBlock> {
         // Get next index
         let next_index = self.index.current_index + 1;
 
@@ -318,12 +822,7 @@ impl MEFLedger {
         let previous_hash = self.get_last_hash()?;
 
         // Compute snapshot hash with canonical JSON
-        let canonical_snapshot = Self::canonicalize_json(snapshot);
-        let snapshot_str =
-            serde_json::to_string(&canonical_snapshot).context("Failed to serialize snapshot")?;
-        let mut hasher = Sha256::new();
-        hasher.update(snapshot_str.as_bytes());
-        let snapshot_hash = format!("{:x}", hasher.finalize());
+        let snapshot_hash = self.hash_scheme.hash_value(snapshot);
 
         let tic_id = tic["tic_id"]
             .as_str()
@@ -338,13 +837,28 @@ impl MEFLedger {
             "tic_id": tic_id,
             "snapshot_hash": snapshot_hash,
             "data": Self::compact_tic_data(tic)?,
-            "proof": Self::canonicalize_json(&tic["proof"]),
+            "proof": hashing::sort_keys(&tic["proof"]),
         });
+        if self.hash_scheme != HashScheme::Legacy {
+            block_json["hash_scheme"] = serde_json::json!(self.hash_scheme.version());
+        }
+        if let Some(signer) = &self.signer {
+            block_json["signer"] = serde_json::json!(signer.key_id());
+        }
+        if let Some(checkpoint) = checkpoint {
+            block_json["checkpoint"] =
+                serde_json::to_value(checkpoint).context("Failed to serialize checkpoint")?;
+        }
 
         // Compute block hash
         let hash = Self::compute_block_hash(&block_json);
         block_json["hash"] = serde_json::json!(hash);
 
+        // Sign the block hash
+        if let Some(signer) = &self.signer {
+            block_json["signature"] = serde_json::json!(signer.sign(hash.as_bytes()));
+        }
+
         // Deserialize to MefBlock
         let block: MefBlock =
             serde_json::from_value(block_json).context("Failed to create block")?;
@@ -354,41 +868,242 @@ impl MEFLedger {
 
     /// Append a new block to the ledger
     ///
+    /// The existing chain is not re-verified, so appends stay O(1); use
+    /// [`MEFLedger::verify_chain_integrity`] to audit it.
+    ///
     /// # Arguments
     /// * `tic` - TIC data as JSON
     /// * `snapshot` - Snapshot data as JSON
     pub fn append_block(&mut self, tic: &JsonValue, snapshot: &JsonValue) -> Result<MefBlock> {
-        // Create new block
-        let block = self.create_block(tic, snapshot)?;
+        self.with_write_lock(|ledger| {
+            ledger.ensure_signer_active()?;
+
+            // Create new block
+            let block = ledger.create_block(tic, snapshot)?;
 
-        // Verify chain integrity before appending
-        if !self.verify_chain_integrity(0)? {
-            anyhow::bail!("Chain integrity check failed");
+            // Persist the block record; it is durable once this returns
+            let location = ledger.segments.append(&block)?;
+
+            // Update index
+            ledger.record_appended(&block, location)?;
+
+            Ok(block)
+        })
+    }
+
+    /// Append a block only if the ledger head is still where the caller saw it
+    ///
+    /// Fails with [`AppendConflict`] (downcastable from the returned error)
+    /// when another writer appended in the meantime, instead of silently
+    /// appending after the other writer's block.
+    ///
+    /// # Arguments
+    /// * `expected_index` - Index the new block must receive
+    /// * `expected_previous_hash` - Hash of the block the new block must follow
+    /// * `tic` - TIC data as JSON
+    /// * `snapshot` - Snapshot data as JSON
+    pub fn compare_and_append(
+        &mut self,
+        expected_index: i32,
+        expected_previous_hash: &str,
+        tic: &JsonValue,
+        snapshot: &JsonValue,
+    ) -> Result<MefBlock> {
+        self.with_write_lock(|ledger| {
+            ledger.append_if_head(Some(expected_index), expected_previous_hash, tic, snapshot)
+        })
+    }
+
+    /// Append a block only if it follows `expected_previous_hash`
+    ///
+    /// Like [`MEFLedger::compare_and_append`], but the next index is read
+    /// under the write lock, after picking up appends by other processes,
+    /// so callers only need the hash of the head they saw.
+    ///
+    /// # Arguments
+    /// * `expected_previous_hash` - Hash of the block the new block must follow
+    /// * `tic` - TIC data as JSON
+    /// * `snapshot` - Snapshot data as JSON
+    pub fn append_after(
+        &mut self,
+        expected_previous_hash: &str,
+        tic: &JsonValue,
+        snapshot: &JsonValue,
+    ) -> Result<MefBlock> {
+        self.with_write_lock(|ledger| {
+            ledger.append_if_head(None, expected_previous_hash, tic, snapshot)
+        })
+    }
+
+    /// Append under the write lock if the head matches the expectation
+    fn append_if_head(
+        &mut self,
+        expected_index: Option<i32>,
+        expected_previous_hash: &str,
+        tic: &JsonValue,
+        snapshot: &JsonValue,
+    ) -> Result<MefBlock> {
+        let actual_index = self.index.current_index + 1;
+        let actual_previous_hash = self.get_last_hash()?;
+        let expected_index = expected_index.unwrap_or(actual_index);
+        if actual_index != expected_index || actual_previous_hash != expected_previous_hash {
+            return Err(AppendConflict {
+                expected_index,
+                expected_previous_hash: expected_previous_hash.to_string(),
+                actual_index,
+                actual_previous_hash,
+            }
+            .into());
         }
+        self.ensure_signer_active()?;
 
-        // Save block to disk in standard format (not normalized)
-        let block_file = self
-            .ledger_path
-            .join(format!("block_{:06}.mef", block.index));
-        let json = serde_json::to_string_pretty(&block).context("Failed to serialize block")?;
-        std::fs::write(&block_file, json).context("Failed to write block file")?;
+        let block = self.create_block(tic, snapshot)?;
+        let location = self.segments.append(&block)?;
+        self.record_appended(&block, location)?;
+        Ok(block)
+    }
 
-        // Update index
-        self.index.blocks.push(BlockSummary {
-            index: block.index,
-            hash: block.hash.clone(),
-            tic_id: block.tic_id.clone(),
-            timestamp: block.timestamp.clone(),
-            file: block_file
-                .file_name()
-                .unwrap()
-                .to_string_lossy()
-                .to_string(),
-        });
+    /// Append a block produced by another replica of this ledger
+    ///
+    /// The block must be the next index, link to the current last hash, and
+    /// carry a valid hash and signature under the local key registry.
+    ///
+    /// # Arguments
+    /// * `block` - Block copied from another replica
+    pub fn import_block(&mut self, block: MefBlock) -> Result<()> {
+        self.with_write_lock(|ledger| {
+            let next_index = ledger.index.current_index + 1;
+            if block.index != next_index {
+                anyhow::bail!(
+                    "Cannot import block {}: next index is {}",
+                    block.index,
+                    next_index
+                );
+            }
+            if block.previous_hash != ledger.get_last_hash()? {
+                anyhow::bail!("Block {} does not link to the local chain", block.index);
+            }
+            if !ledger.verify_block_hash(&block) {
+                anyhow::bail!("Invalid hash for block {}", block.index);
+            }
+            if !ledger.verify_block_signature(&block) {
+                anyhow::bail!("Invalid signature for block {}", block.index);
+            }
+
+            let location = ledger.segments.append(&block)?;
+            ledger.record_appended(&block, location)
+        })
+    }
+
+    /// Append blocks restored from an archive
+    ///
+    /// Only indices and linkage are checked here: pruned blocks can only be
+    /// vouched for by a later checkpoint, so callers must verify the whole
+    /// chain afterwards.
+    ///
+    /// # Arguments
+    /// * `blocks` - Blocks in chain order, continuing the current chain; they
+    ///   are read one at a time and the first error stops the restore
+    pub(crate) fn restore_blocks(
+        &mut self,
+        blocks: impl IntoIterator<Item = Result<MefBlock>>,
+    ) -> Result<()> {
+        self.with_write_lock(|ledger| {
+            for block in blocks {
+                let block = block?;
+                let next_index = ledger.index.current_index + 1;
+                if block.index != next_index {
+                    anyhow::bail!(
+                        "Cannot restore block {}: next index is {}",
+                        block.index,
+                        next_index
+                    );
+                }
+                if block.previous_hash != ledger.get_last_hash()? {
+                    anyhow::bail!("Block {} does not link to the restored chain", block.index);
+                }
+
+                let location = ledger.segments.append(&block)?;
+                let summary = Self::summarize(&block, location);
+                ledger.secondary.insert(&summary);
+                ledger.index.blocks.push(summary);
+                ledger.index.current_index = block.index;
+                ledger.merkle.push(&block.hash);
+                ledger.notify_appended(&block);
+            }
+            ledger.save_index()
+        })
+    }
+
+    /// Update the index, Merkle accumulator and secondary indexes for a
+    /// block that was just written to the segments
+    fn record_appended(&mut self, block: &MefBlock, location: RecordLocation) -> Result<()> {
+        let summary = Self::summarize(block, location);
+        self.secondary.insert(&summary);
+        self.index.blocks.push(summary);
         self.index.current_index = block.index;
+        self.merkle.push(&block.hash);
         self.save_index()?;
+        self.notify_appended(block);
+        Ok(())
+    }
 
-        Ok(block)
+    fn notify_appended(&self, block: &MefBlock) {
+        if let Some(observer) = &self.observer {
+            observer.block_appended(block, self.index.blocks.len());
+        }
+    }
+
+    /// Notify `observer` about every block appended from now on
+    pub fn set_observer(&mut self, observer: Arc<dyn LedgerObserver>) {
+        self.observer = Some(observer);
+    }
+
+    /// Find blocks matching a query using the secondary indexes
+    ///
+    /// # Arguments
+    /// * `query` - Filter criteria; an empty query matches every block
+    pub fn query(&self, query: &LedgerQuery) -> Result<Vec<BlockSummary>> {
+        Ok(self
+            .query_indices(query)?
+            .into_iter()
+            .filter_map(|i| self.index.blocks.get(i as usize).cloned())
+            .collect())
+    }
+
+    /// Indices of the blocks matching a query, without copying summaries
+    ///
+    /// # Arguments
+    /// * `query` - Filter criteria; an empty query matches every block
+    pub fn query_indices(&self, query: &LedgerQuery) -> Result<Vec<i32>> {
+        self.secondary.query(query)
+    }
+
+    /// Replace the key registry with one received from another replica
+    ///
+    /// The other registry may only add keys for future blocks, rotate or
+    /// revoke; see [`KeyRegistry::check_successor`]. A replica can never swap
+    /// a key, revive a revoked key or widen the blocks a key may sign.
+    ///
+    /// # Arguments
+    /// * `registry` - Key registry of the other replica
+    pub fn adopt_key_registry(&mut self, registry: KeyRegistry) -> Result<()> {
+        self.with_write_lock(|ledger| {
+            ledger
+                .index
+                .metadata
+                .key_registry
+                .check_successor(&registry, ledger.index.current_index)?;
+            if let Some(signer) = &ledger.signer {
+                if registry.active().map(|k| k.key_id.as_str()) != Some(signer.key_id()) {
+                    anyhow::bail!("Signer {} is not the active key", signer.key_id());
+                }
+            }
+
+            ledger.index.metadata.key_registry = registry;
+            ledger.save_key_registry()?;
+            ledger.save_index()
+        })
     }
 
     /// Retrieve a block by index
@@ -396,14 +1111,18 @@ impl MEFLedger {
     /// # Arguments
     /// * `index` - Block index
     pub fn get_block(&self, index: i32) -> Result<Option<MefBlock>> {
-        let block_file = self.ledger_path.join(format!("block_{:06}.mef", index));
-
-        if !block_file.exists() {
+        if index < 0 {
             return Ok(None);
         }
+        let summary = match self.index.blocks.get(index as usize) {
+            Some(summary) => summary,
+            None => return Ok(None),
+        };
 
-        let contents = std::fs::read_to_string(&block_file).context("Failed to read block file")?;
-        let block: MefBlock = serde_json::from_str(&contents).context("Failed to parse block")?;
+        let block = self.segments.read(summary.location())?;
+        if block.index != index {
+            anyhow::bail!("Index points to block {} for index {}", block.index, index);
+        }
 
         Ok(Some(block))
     }
@@ -418,11 +1137,45 @@ impl MEFLedger {
         block.hash == computed_hash
     }
 
+    /// Verify a block's signature against the ledger's key registry
+    ///
+    /// # Arguments
+    /// * `block` - Block data
+    pub fn verify_block_signature(&self, block: &MefBlock) -> bool {
+        Self::check_block_signature(&self.index.metadata.key_registry, block)
+    }
+
+    /// Check a block's signature against a key registry
+    ///
+    /// Unsigned blocks are accepted only before the first registered key took
+    /// effect.
+    pub(crate) fn check_block_signature(registry: &KeyRegistry, block: &MefBlock) -> bool {
+        match (&block.signer, &block.signature) {
+            (Some(key_id), Some(signature)) => {
+                registry.verify(key_id, block.index, block.hash.as_bytes(), signature)
+            }
+            (None, None) => registry
+                .signed_from_index()
+                .map(|from| block.index < from)
+                .unwrap_or(true),
+            _ => false,
+        }
+    }
+
     /// Verify integrity of the entire chain or from a specific index
     ///
     /// # Arguments
     /// * `start_index` - Starting block index for verification
     pub fn verify_chain_integrity(&self, start_index: i32) -> Result<bool> {
+        self.verify_chain_against(start_index, &self.index.metadata.key_registry)
+    }
+
+    /// Verify chain integrity using an externally pinned key registry
+    ///
+    /// # Arguments
+    /// * `start_index` - Starting block index for verification
+    /// * `registry` - Trusted signer keys
+    pub fn verify_chain_against(&self, start_index: i32, registry: &KeyRegistry) -> Result<bool> {
         if self.index.current_index < 0 {
             // Empty ledger is valid
             return Ok(true);
@@ -434,6 +1187,29 @@ impl MEFLedger {
             None
         };
 
+        // Checkpoint roots cover the whole prefix, so blocks before
+        // `start_index` are accumulated when a later checkpoint must be checked
+        let mut hashes = MerkleAccumulator::new();
+        let mut headers = MerkleAccumulator::new();
+        let checkpoint_ahead = self
+            .index
+            .blocks
+            .iter()
+            .skip(start_index.max(0) as usize)
+            .any(|b| b.checkpoint.is_some());
+        if checkpoint_ahead {
+            for i in 0..start_index {
+                let block = self
+                    .get_block(i)?
+                    .ok_or_else(|| anyhow::anyhow!("Missing block at index {}", i))?;
+                hashes.push(&block.hash);
+                headers.push(&checkpoint::header_digest(&block));
+            }
+        }
+
+        // Pruned blocks not yet covered by a verified checkpoint
+        let mut uncovered_pruned = Vec::new();
+
         for i in start_index..=self.index.current_index {
             let block = self.get_block(i)?;
 
@@ -444,12 +1220,29 @@ impl MEFLedger {
 
             let block = block.unwrap();
 
-            // Verify block hash
-            if !self.verify_block_hash(&block) {
+            // Verify block hash under its recorded scheme; pruned blocks are
+            // vouched for by a checkpoint
+            if let Err(e) = block.hash_scheme.map(HashScheme::from_version).transpose() {
+                eprintln!("Block {}: {}", i, e);
+                return Ok(false);
+            }
+            if block.pruned {
+                if !checkpoint::is_pruned_form(&block) {
+                    eprintln!("Pruned block {} still carries payload data", i);
+                    return Ok(false);
+                }
+                uncovered_pruned.push(i);
+            } else if !self.verify_block_hash(&block) {
                 eprintln!("Invalid hash for block {}", i);
                 return Ok(false);
             }
 
+            // Verify signer signature
+            if !Self::check_block_signature(registry, &block) {
+                eprintln!("Invalid signature for block {}", i);
+                return Ok(false);
+            }
+
             // Verify chain linkage
             if let Some(ref expected_prev) = prev_hash {
                 if block.previous_hash != *expected_prev {
@@ -458,12 +1251,267 @@ impl MEFLedger {
                 }
             }
 
+            hashes.push(&block.hash);
+            headers.push(&checkpoint::header_digest(&block));
+
+            // Verify checkpoint commitments
+            if let Some(checkpoint) = &block.checkpoint {
+                if !Self::checkpoint_matches(checkpoint, i, &hashes, &headers) {
+                    eprintln!("Invalid checkpoint in block {}", i);
+                    return Ok(false);
+                }
+                uncovered_pruned.retain(|&p| p > checkpoint.up_to_index);
+            }
+
             prev_hash = Some(block.hash.clone());
         }
 
+        if let Some(first) = uncovered_pruned.first() {
+            eprintln!("Pruned block {} is not covered by a checkpoint", first);
+            return Ok(false);
+        }
+
         Ok(true)
     }
 
+    /// Check a checkpoint carried by block `index` against the accumulated
+    /// block hashes and header digests
+    fn checkpoint_matches(
+        checkpoint: &Checkpoint,
+        index: i32,
+        hashes: &MerkleAccumulator,
+        headers: &MerkleAccumulator,
+    ) -> bool {
+        if checkpoint.up_to_index < 0 || checkpoint.up_to_index >= index {
+            return false;
+        }
+        let size = checkpoint.tree_size();
+        hashes.root_at(size).as_deref() == Some(checkpoint.merkle_root.as_str())
+            && headers.root_at(size).as_deref() == Some(checkpoint.header_root.as_str())
+            && hashes.leaf(size - 1)
+                == Some(crate::merkle::leaf_hash(&checkpoint.last_block_hash).as_str())
+    }
+
+    /// Append a checkpoint block committing to every block so far
+    ///
+    /// The checkpoint is signed like any other block when a signer is
+    /// configured. The blocks it covers may afterwards be pruned with
+    /// [`MEFLedger::prune_payloads`].
+    pub fn create_checkpoint(&mut self) -> Result<MefBlock> {
+        self.with_write_lock(|ledger| {
+            ledger.ensure_signer_active()?;
+
+            let up_to_index = ledger.index.current_index;
+            if up_to_index < 0 {
+                anyhow::bail!("Cannot checkpoint an empty ledger");
+            }
+            if !ledger.verify_chain_integrity(0)? {
+                anyhow::bail!("Chain integrity check failed");
+            }
+
+            let mut headers = MerkleAccumulator::new();
+            for i in 0..=up_to_index {
+                let block = ledger
+                    .get_block(i)?
+                    .ok_or_else(|| anyhow::anyhow!("Missing block at index {}", i))?;
+                headers.push(&checkpoint::header_digest(&block));
+            }
+
+            let checkpoint = Checkpoint {
+                up_to_index,
+                merkle_root: ledger.merkle.root(),
+                header_root: headers.root(),
+                last_block_hash: ledger.get_last_hash()?,
+            };
+            let tic = serde_json::json!({
+                "tic_id": format!("checkpoint_{}", up_to_index),
+                "seed": "checkpoint",
+                "fixpoint": [],
+                "invariants": {},
+                "sigma_bar": {},
+                "window": [],
+                "proof": null,
+            });
+            let snapshot =
+                serde_json::to_value(&checkpoint).context("Failed to serialize checkpoint")?;
+
+            let block = ledger.create_block_with(&tic, &snapshot, Some(&checkpoint))?;
+            let location = ledger.segments.append(&block)?;
+            ledger.record_appended(&block, location)?;
+
+            Ok(block)
+        })
+    }
+
+    /// Drop the TIC payloads of blocks covered by a checkpoint
+    ///
+    /// Block headers and hashes are kept, so chain verification still passes
+    /// across the pruned range. Checkpoint blocks are never pruned.
+    /// Returns the number of blocks pruned.
+    ///
+    /// # Arguments
+    /// * `up_to_index` - Last block to prune; must be covered by a checkpoint
+    /// * `archive` - Copy the full blocks to `cold/` before pruning
+    pub fn prune_payloads(&mut self, up_to_index: i32, archive: bool) -> Result<usize> {
+        self.with_write_lock(|ledger| {
+            let covered = ledger
+                .index
+                .blocks
+                .iter()
+                .filter_map(|b| b.checkpoint)
+                .max();
+            if covered.map(|c| up_to_index > c).unwrap_or(true) {
+                anyhow::bail!("Block {} is not covered by a checkpoint", up_to_index);
+            }
+
+            let is_target =
+                |b: &BlockSummary| b.index <= up_to_index && !b.pruned && b.checkpoint.is_none();
+            let segments: std::collections::BTreeSet<u32> = ledger
+                .index
+                .blocks
+                .iter()
+                .filter(|b| is_target(b))
+                .map(|b| b.segment)
+                .collect();
+
+            let cold_dir = ledger.ledger_path.join(COLD_DIR);
+            if archive {
+                std::fs::create_dir_all(&cold_dir).context("Failed to create cold storage")?;
+                segment_store::sync_dir(&ledger.ledger_path)?;
+            }
+
+            let mut pruned = 0;
+            for segment in segments {
+                let positions: Vec<usize> = (0..ledger.index.blocks.len())
+                    .filter(|&p| ledger.index.blocks[p].segment == segment)
+                    .collect();
+
+                let mut blocks = Vec::with_capacity(positions.len());
+                for &pos in &positions {
+                    let summary = &ledger.index.blocks[pos];
+                    let prune = is_target(summary);
+                    let mut block = ledger.segments.read(summary.location())?;
+                    if prune {
+                        if !ledger.verify_block_hash(&block) {
+                            anyhow::bail!(
+                                "Invalid hash for block {}; refusing to prune",
+                                block.index
+                            );
+                        }
+                        if archive {
+                            let json = serde_json::to_vec_pretty(&block)
+                                .context("Failed to serialize block")?;
+                            Self::write_synced(
+                                &cold_dir.join(checkpoint::cold_file_name(block.index)),
+                                &json,
+                            )
+                            .context("Failed to archive block payload")?;
+                        }
+                        block = checkpoint::prune_block(&block);
+                        pruned += 1;
+                    }
+                    blocks.push(block);
+                }
+
+                // The archived copies must be durable before the segment
+                // loses the only other copy of their payloads
+                if archive {
+                    segment_store::sync_dir(&cold_dir)?;
+                }
+                let locations = ledger.segments.rewrite_segment(segment, &blocks)?;
+                for ((&pos, block), location) in positions.iter().zip(&blocks).zip(locations) {
+                    ledger.index.blocks[pos] = Self::summarize(block, location);
+                }
+                ledger.save_index()?;
+            }
+
+            Ok(pruned)
+        })
+    }
+
+    /// Load the full version of a pruned block from cold storage
+    ///
+    /// # Arguments
+    /// * `index` - Block index
+    pub fn get_archived_block(&self, index: i32) -> Result<Option<MefBlock>> {
+        let path = self
+            .ledger_path
+            .join(COLD_DIR)
+            .join(checkpoint::cold_file_name(index));
+        if !path.exists() {
+            return Ok(None);
+        }
+
+        let contents = std::fs::read_to_string(&path).context("Failed to read archived block")?;
+        let block: MefBlock =
+            serde_json::from_str(&contents).context("Failed to parse archived block")?;
+        let expected = self
+            .index
+            .blocks
+            .get(index as usize)
+            .map(|b| b.hash.as_str());
+        if block.index != index || !self.verify_block_hash(&block) || expected != Some(&block.hash)
+        {
+            anyhow::bail!("Archived block {} does not match the ledger", index);
+        }
+
+        Ok(Some(block))
+    }
+
+    /// Get the Merkle root commitment over all blocks
+    ///
+    /// The root is signed when the ledger has a signer configured.
+    pub fn merkle_root(&self) -> Result<LedgerRoot> {
+        let mut root = LedgerRoot {
+            tree_size: self.merkle.len(),
+            root: self.merkle.root(),
+            last_block_hash: self.get_last_hash()?,
+            timestamp: Utc::now().format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string(),
+            signer: None,
+            signature: None,
+        };
+        if let Some(signer) = &self.signer {
+            root.signer = Some(signer.key_id().to_string());
+            root.signature = Some(signer.sign(root.signing_payload().as_bytes()));
+        }
+        Ok(root)
+    }
+
+    /// Build an inclusion proof for a block against the current root
+    ///
+    /// # Arguments
+    /// * `index` - Block index
+    pub fn inclusion_proof(&self, index: i32) -> Result<Option<InclusionProof>> {
+        self.inclusion_proof_at(index, self.merkle.len())
+    }
+
+    /// Build an inclusion proof for a block against an earlier root
+    ///
+    /// # Arguments
+    /// * `index` - Block index
+    /// * `tree_size` - Number of blocks covered by the root being proven against
+    pub fn inclusion_proof_at(&self, index: i32, tree_size: u64) -> Result<Option<InclusionProof>> {
+        if index < 0 {
+            return Ok(None);
+        }
+        let audit_path = match self.merkle.audit_path(index as u64, tree_size) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        let summary = self
+            .index
+            .blocks
+            .get(index as usize)
+            .ok_or_else(|| anyhow::anyhow!("Block {} missing from index", index))?;
+
+        Ok(Some(InclusionProof {
+            block_index: index,
+            block_hash: summary.hash.clone(),
+            tree_size,
+            audit_path,
+        }))
+    }
+
     /// Get ledger chain statistics
     pub fn get_chain_statistics(&self) -> Result<ChainStatistics> {
         let total_blocks = if self.index.current_index >= 0 {
@@ -473,13 +1521,7 @@ impl MEFLedger {
         };
 
         // Calculate chain file size
-        let mut total_size = 0u64;
-        for block_info in &self.index.blocks {
-            let block_file = self.ledger_path.join(&block_info.file);
-            if let Ok(metadata) = block_file.metadata() {
-                total_size += metadata.len();
-            }
-        }
+        let total_size = self.segments.total_size()?;
 
         // Get time range
         let time_range = if total_blocks > 0 {
@@ -597,6 +1639,490 @@ mod tests {
         assert_eq!(ledger.index.current_index, 9);
     }
 
+    #[test]
+    fn test_inclusion_proof() {
+        let temp_dir = std::env::temp_dir().join("test_ledger_inclusion");
+        let _ = std::fs::remove_dir_all(&temp_dir);
+        let mut ledger = MEFLedger::new(&temp_dir).unwrap();
+
+        for i in 0..5 {
+            let tic = json!({
+                "tic_id": format!("tic-{}", i),
+                "seed": "SEED",
+                "fixpoint": [0.1, 0.2, 0.3],
+                "invariants": {},
+                "sigma_bar": {},
+                "window": [],
+                "proof": null
+            });
+            ledger.append_block(&tic, &json!({"id": i})).unwrap();
+        }
+
+        let root = ledger.merkle_root().unwrap();
+        assert_eq!(root.tree_size, 5);
+        assert_eq!(root.last_block_hash, ledger.get_last_hash().unwrap());
+
+        let proof = ledger.inclusion_proof(2).unwrap().unwrap();
+        assert_eq!(proof.block_hash, ledger.get_block(2).unwrap().unwrap().hash);
+        assert!(proof.verify(&root));
+        assert!(ledger.inclusion_proof(5).unwrap().is_none());
+
+        // Proofs against an older root keep verifying after further appends
+        let old_proof = ledger.inclusion_proof_at(1, 3).unwrap().unwrap();
+
+        // Accumulator is rebuilt from the index on reopen
+        let reopened = MEFLedger::new(&temp_dir).unwrap();
+        assert_eq!(reopened.merkle_root().unwrap().root, root.root);
+        assert_eq!(old_proof.compute_root(), reopened.merkle.root_at(3));
+    }
+
+    fn sample_tic(i: usize) -> JsonValue {
+        json!({
+            "tic_id": format!("tic-{}", i),
+            "seed": "SEED",
+            "fixpoint": [0.1, 0.2, 0.3],
+            "invariants": {"variance": 0.1},
+            "sigma_bar": {"psi": 0.5},
+            "window": ["2025-10-15T00:00:00", "2025-10-15T01:00:00"],
+            "proof": {"merkle_root": format!("root_{}", i)}
+        })
+    }
+
+    #[test]
+    fn test_index_rebuilt_after_crash() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let stale_index;
+        {
+            let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+            ledger
+                .append_block(&sample_tic(0), &json!({"id": 0}))
+                .unwrap();
+            stale_index = std::fs::read_to_string(temp_dir.path().join(INDEX_FILE)).unwrap();
+            ledger
+                .append_block(&sample_tic(1), &json!({"id": 1}))
+                .unwrap();
+        }
+
+        // Crash between the segment write and the index write
+        std::fs::write(temp_dir.path().join(INDEX_FILE), stale_index).unwrap();
+
+        let ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        assert_eq!(ledger.index.current_index, 1);
+        assert_eq!(ledger.get_block(1).unwrap().unwrap().tic_id, "tic-1");
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+
+        // Missing index is rebuilt as well
+        std::fs::remove_file(temp_dir.path().join(INDEX_FILE)).unwrap();
+        let ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        assert_eq!(ledger.index.blocks.len(), 2);
+    }
+
+    #[test]
+    fn test_checkpoint_and_prune() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = LedgerConfig {
+            max_segment_bytes: 2048,
+            signer: Some(LedgerSigner::generate("k1")),
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config.clone()).unwrap();
+        for i in 0..5 {
+            ledger
+                .append_block(&sample_tic(i), &json!({"id": i}))
+                .unwrap();
+        }
+        assert!(ledger.prune_payloads(2, false).is_err());
+
+        let checkpoint = ledger.create_checkpoint().unwrap();
+        assert_eq!(checkpoint.checkpoint.as_ref().unwrap().up_to_index, 4);
+        assert!(ledger.verify_block_signature(&checkpoint));
+        for i in 6..8 {
+            ledger
+                .append_block(&sample_tic(i), &json!({"id": i}))
+                .unwrap();
+        }
+
+        // Only blocks covered by the checkpoint can be pruned
+        assert!(ledger.prune_payloads(6, false).is_err());
+        assert_eq!(ledger.prune_payloads(4, true).unwrap(), 5);
+        assert_eq!(ledger.prune_payloads(4, true).unwrap(), 0);
+
+        let pruned = ledger.get_block(2).unwrap().unwrap();
+        assert!(pruned.pruned);
+        assert!(pruned.proof.is_null());
+        assert!(!ledger.verify_block_hash(&pruned));
+        assert_eq!(ledger.get_block(6).unwrap().unwrap().tic_id, "tic-6");
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+        assert!(ledger.verify_chain_integrity(3).unwrap());
+
+        let archived = ledger.get_archived_block(2).unwrap().unwrap();
+        assert_eq!(archived.hash, pruned.hash);
+        assert_eq!(archived.proof, json!({"merkle_root": "root_2"}));
+
+        // Appends keep working and the pruned layout survives a reopen
+        ledger
+            .append_block(&sample_tic(8), &json!({"id": 8}))
+            .unwrap();
+        let ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        assert!(ledger.get_block(0).unwrap().unwrap().pruned);
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_tampered_pruned_block_detected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        for i in 0..3 {
+            ledger
+                .append_block(&sample_tic(i), &json!({"id": i}))
+                .unwrap();
+        }
+        ledger.create_checkpoint().unwrap();
+        ledger
+            .append_block(&sample_tic(4), &json!({"id": 4}))
+            .unwrap();
+        ledger.prune_payloads(1, false).unwrap();
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+
+        let rewrite = |ledger: &mut MEFLedger, edit: &dyn Fn(&mut MefBlock)| {
+            let mut blocks: Vec<MefBlock> = ledger
+                .segments
+                .scan()
+                .unwrap()
+                .into_iter()
+                .map(|(_, b)| b)
+                .collect();
+            edit(&mut blocks[1]);
+            ledger.segments.rewrite_segment(0, &blocks).unwrap();
+            ledger.rebuild_index().unwrap();
+        };
+
+        // Header fields of a pruned block are covered by the checkpoint
+        rewrite(&mut ledger, &|b| b.tic_id = "forged".to_string());
+        assert!(!ledger.verify_chain_integrity(0).unwrap());
+
+        // Marking an uncovered block as pruned does not skip its hash check
+        rewrite(&mut ledger, &|b| b.tic_id = "tic-1".to_string());
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+
+        // A pruned block cannot smuggle in a payload nothing hashes
+        rewrite(&mut ledger, &|b| {
+            b.data.invariants = json!({"variance": 9.9});
+            b.data.window = vec!["forged".to_string()];
+        });
+        assert!(!ledger.verify_chain_integrity(0).unwrap());
+        let report = ledger
+            .verify_chain_parallel(&crate::verify::VerifyOptions::default(), |_| {})
+            .unwrap();
+        assert!(report
+            .failures
+            .iter()
+            .any(|f| f.index == 1 && f.kind == crate::verify::FailureKind::HashMismatch));
+        rewrite(&mut ledger, &|b| *b = crate::checkpoint::prune_block(b));
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+        let mut blocks: Vec<MefBlock> = ledger
+            .segments
+            .scan()
+            .unwrap()
+            .into_iter()
+            .map(|(_, b)| b)
+            .collect();
+        blocks[4] = crate::checkpoint::prune_block(&blocks[4]);
+        ledger.segments.rewrite_segment(0, &blocks).unwrap();
+        ledger.rebuild_index().unwrap();
+        assert!(!ledger.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_query_secondary_indexes() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        {
+            let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+            for i in 0..4 {
+                ledger
+                    .append_block(&sample_tic(i % 2), &json!({"id": i}))
+                    .unwrap();
+            }
+
+            let query = LedgerQuery {
+                tic_id: Some("tic-1".to_string()),
+                ..LedgerQuery::default()
+            };
+            let found: Vec<i32> = ledger
+                .query(&query)
+                .unwrap()
+                .iter()
+                .map(|b| b.index)
+                .collect();
+            assert_eq!(found, vec![1, 3]);
+        }
+
+        // Indexes written before secondary indexing are rebuilt on open
+        let index_file = temp_dir.path().join(INDEX_FILE);
+        let mut old: JsonValue =
+            serde_json::from_str(&std::fs::read_to_string(&index_file).unwrap()).unwrap();
+        for block in old["blocks"].as_array_mut().unwrap() {
+            let block = block.as_object_mut().unwrap();
+            block.remove("seed");
+            block.remove("fixpoint_norm");
+        }
+        std::fs::write(&index_file, old.to_string()).unwrap();
+
+        let ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        let seed = ledger.index.blocks[0].seed.clone().unwrap();
+        let query = LedgerQuery {
+            seed: Some(seed),
+            from: Some("2000-01-01".to_string()),
+            ..LedgerQuery::default()
+        };
+        assert_eq!(ledger.query(&query).unwrap().len(), 4);
+
+        let query = LedgerQuery {
+            to: Some("2000-01-01".to_string()),
+            ..LedgerQuery::default()
+        };
+        assert!(ledger.query(&query).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_segment_rotation_across_blocks() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = LedgerConfig {
+            max_segment_bytes: 1024,
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config.clone()).unwrap();
+        for i in 0..8 {
+            ledger
+                .append_block(&sample_tic(i), &json!({"id": i}))
+                .unwrap();
+        }
+
+        let segments = SegmentStore::list_segments(&temp_dir.path().join(SEGMENTS_DIR)).unwrap();
+        assert!(segments.len() > 1);
+
+        let reopened = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        assert!(reopened.verify_chain_integrity(0).unwrap());
+        assert_eq!(reopened.get_block(7).unwrap().unwrap().tic_id, "tic-7");
+    }
+
+    #[test]
+    fn test_migrate_legacy_layout() {
+        let source = tempfile::tempdir().unwrap();
+        let legacy = tempfile::tempdir().unwrap();
+
+        // Write a ledger in the legacy per-block JSON layout
+        let mut ledger = MEFLedger::new(source.path()).unwrap();
+        for i in 0..3 {
+            let block = ledger
+                .append_block(&sample_tic(i), &json!({"id": i}))
+                .unwrap();
+            std::fs::write(
+                legacy.path().join(format!("block_{:06}.mef", i)),
+                serde_json::to_string_pretty(&block).unwrap(),
+            )
+            .unwrap();
+        }
+
+        let mut migrated = MEFLedger::new(legacy.path()).unwrap();
+        assert_eq!(migrated.index.current_index, 2);
+        assert!(migrated.verify_chain_integrity(0).unwrap());
+        assert_eq!(
+            migrated.get_last_hash().unwrap(),
+            ledger.get_last_hash().unwrap()
+        );
+        assert!(!legacy.path().join("block_000000.mef").exists());
+        assert!(legacy
+            .path()
+            .join(LEGACY_DIR)
+            .join("block_000000.mef")
+            .exists());
+
+        // Appending continues the migrated chain
+        migrated
+            .append_block(&sample_tic(3), &json!({"id": 3}))
+            .unwrap();
+        assert!(migrated.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_rejects_tampered_legacy_block() {
+        let source = tempfile::tempdir().unwrap();
+        let legacy = tempfile::tempdir().unwrap();
+
+        let mut ledger = MEFLedger::new(source.path()).unwrap();
+        let mut block = ledger
+            .append_block(&sample_tic(0), &json!({"id": 0}))
+            .unwrap();
+        block.tic_id = "forged".to_string();
+        std::fs::write(
+            legacy.path().join("block_000000.mef"),
+            serde_json::to_string_pretty(&block).unwrap(),
+        )
+        .unwrap();
+
+        assert!(MEFLedger::new(legacy.path()).is_err());
+        assert!(legacy.path().join("block_000000.mef").exists());
+    }
+
+    #[test]
+    fn test_signed_blocks_and_rotation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let k1 = LedgerSigner::generate("k1");
+        let config = LedgerConfig {
+            signer: Some(k1.clone()),
+            ..LedgerConfig::default()
+        };
+
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        let block = ledger
+            .append_block(&sample_tic(0), &json!({"id": 0}))
+            .unwrap();
+        assert_eq!(block.signer.as_deref(), Some("k1"));
+        assert!(ledger.verify_block_signature(&block));
+
+        let k2 = LedgerSigner::generate("k2");
+        ledger.rotate_signer(k2.clone()).unwrap();
+        let block = ledger
+            .append_block(&sample_tic(1), &json!({"id": 1}))
+            .unwrap();
+        assert_eq!(block.signer.as_deref(), Some("k2"));
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+
+        // Retired key can no longer be used as the configured signer
+        let config = LedgerConfig {
+            signer: Some(k1),
+            ..LedgerConfig::default()
+        };
+        assert!(MEFLedger::with_config(temp_dir.path(), config).is_err());
+
+        // Reopening with the active key keeps signing
+        let config = LedgerConfig {
+            signer: Some(k2),
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        ledger
+            .append_block(&sample_tic(2), &json!({"id": 2}))
+            .unwrap();
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+
+        let root = ledger.merkle_root().unwrap();
+        assert!(root.verify_signature(ledger.key_registry()));
+
+        // Revoking k1 invalidates the block it signed
+        ledger.revoke_signer_key("k1", None).unwrap();
+        assert!(!ledger.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_key_registry_survives_index_loss() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let k1 = LedgerSigner::generate("k1");
+        let config = LedgerConfig {
+            signer: Some(k1.clone()),
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        ledger
+            .append_block(&sample_tic(0), &json!({"id": 0}))
+            .unwrap();
+        drop(ledger);
+
+        // The rebuilt index takes the registry from its own file
+        std::fs::remove_file(temp_dir.path().join(INDEX_FILE)).unwrap();
+        let ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        assert!(ledger.key_registry().get("k1").is_some());
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+        drop(ledger);
+
+        let attacker = LedgerSigner::generate("attacker");
+        let config = LedgerConfig {
+            signer: Some(attacker.clone()),
+            ..LedgerConfig::default()
+        };
+        assert!(MEFLedger::with_config(temp_dir.path(), config).is_err());
+
+        // Without any registry the signed blocks are not handed to a new key
+        std::fs::remove_file(temp_dir.path().join(INDEX_FILE)).unwrap();
+        std::fs::remove_file(temp_dir.path().join(REGISTRY_FILE)).unwrap();
+        let config = LedgerConfig {
+            signer: Some(attacker),
+            ..LedgerConfig::default()
+        };
+        let err = MEFLedger::with_config(temp_dir.path(), config)
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("no key registry"), "{}", err);
+    }
+
+    #[test]
+    fn test_signed_ledger_refuses_unsigned_appends() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = LedgerConfig {
+            signer: Some(LedgerSigner::generate("k1")),
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        ledger
+            .append_block(&sample_tic(0), &json!({"id": 0}))
+            .unwrap();
+
+        // Opened without a signer
+        let mut unsigned = MEFLedger::new(temp_dir.path()).unwrap();
+        let err = unsigned
+            .append_block(&sample_tic(1), &json!({"id": 1}))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("requires signed blocks"),
+            "{}",
+            err
+        );
+        let head = unsigned.get_last_hash().unwrap();
+        assert!(unsigned
+            .compare_and_append(1, &head, &sample_tic(1), &json!({"id": 1}))
+            .is_err());
+
+        // Revoking the active key leaves no signer
+        ledger.revoke_signer_key("k1", Some(1)).unwrap();
+        assert!(ledger
+            .append_block(&sample_tic(1), &json!({"id": 1}))
+            .is_err());
+        assert_eq!(ledger.index().current_index, 0);
+        assert!(ledger.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_rehashed_forgery_detected() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config = LedgerConfig {
+            signer: Some(LedgerSigner::generate("k1")),
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        let block = ledger
+            .append_block(&sample_tic(0), &json!({"id": 0}))
+            .unwrap();
+
+        // Rewrite history and recompute the hash without the signing key
+        let mut forged = block.clone();
+        forged.tic_id = "forged".to_string();
+        let forged_json = serde_json::to_value(&forged).unwrap();
+        forged.hash = MEFLedger::compute_block_hash(&forged_json);
+        assert!(ledger.verify_block_hash(&forged));
+        assert!(!ledger.verify_block_signature(&forged));
+
+        // Stripping the signature is rejected as well
+        forged.signer = None;
+        forged.signature = None;
+        assert!(!ledger.verify_block_signature(&forged));
+
+        // Pinned registry without the key rejects the genuine chain
+        assert!(!ledger
+            .verify_chain_against(0, &KeyRegistry::default())
+            .unwrap());
+    }
+
     #[test]
     fn test_deterministic_hash_golden() {
         // Golden test: Verify that hash computation is deterministic
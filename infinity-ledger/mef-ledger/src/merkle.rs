@@ -0,0 +1,473 @@
+//! Merkle accumulator over ledger block hashes.
+//!
+//! The tree follows the RFC 6962 layout: leaves are hashed with a `0x00`
+//! prefix and interior nodes with a `0x01` prefix, and a tree of `n` leaves is
+//! split at the largest power of two smaller than `n`. This keeps inclusion
+//! proofs stable while the ledger grows and lets a light verifier check that
+//! block N belongs to a ledger root without reading any other block.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::signing::KeyRegistry;
+
+const LEAF_PREFIX: u8 = 0x00;
+const NODE_PREFIX: u8 = 0x01;
+
+/// Hash a block hash into a Merkle leaf
+pub fn leaf_hash(block_hash: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([LEAF_PREFIX]);
+    hasher.update(block_hash.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Combine two child hashes into an interior node
+fn node_hash(left: &str, right: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left.as_bytes());
+    hasher.update(right.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// Root of the empty tree (SHA-256 of the empty string)
+fn empty_root() -> String {
+    format!("{:x}", Sha256::new().finalize())
+}
+
+/// Largest power of two strictly smaller than `n` (requires `n > 1`)
+fn split_point(n: u64) -> u64 {
+    let mut k = 1;
+    while k << 1 < n {
+        k <<= 1;
+    }
+    k
+}
+
+/// Root commitment of a ledger at a given size
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerRoot {
+    /// Number of blocks covered by the root
+    pub tree_size: u64,
+    /// Merkle root over all block hashes
+    pub root: String,
+    /// Hash of the last block covered by the root
+    pub last_block_hash: String,
+    /// Time the root was issued
+    pub timestamp: String,
+    /// Key ID of the signer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signer: Option<String>,
+    /// Hex-encoded Ed25519 signature over [`LedgerRoot::signing_payload`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<String>,
+}
+
+impl LedgerRoot {
+    /// Canonical byte string covered by the root signature
+    pub fn signing_payload(&self) -> String {
+        format!(
+            "{}|{}|{}|{}",
+            self.tree_size, self.root, self.last_block_hash, self.timestamp
+        )
+    }
+
+    /// Verify the root signature against a key registry
+    ///
+    /// The signer must be registered and allowed to sign the last block the
+    /// root covers, by the same rule applied to block signatures.
+    pub fn verify_signature(&self, registry: &KeyRegistry) -> bool {
+        let (key_id, signature) = match (&self.signer, &self.signature) {
+            (Some(key_id), Some(signature)) => (key_id, signature),
+            _ => return false,
+        };
+        let last_index = self.tree_size as i32 - 1;
+        registry.verify(
+            key_id,
+            last_index,
+            self.signing_payload().as_bytes(),
+            signature,
+        )
+    }
+}
+
+/// Compact proof that a block is included in a ledger root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InclusionProof {
+    /// Index of the proven block
+    pub block_index: i32,
+    /// Hash of the proven block
+    pub block_hash: String,
+    /// Tree size the proof was generated against
+    pub tree_size: u64,
+    /// Sibling hashes from the leaf up to the root
+    pub audit_path: Vec<String>,
+}
+
+impl InclusionProof {
+    /// Recompute the root implied by this proof
+    ///
+    /// Returns `None` if the audit path does not fit the claimed position and
+    /// tree size.
+    pub fn compute_root(&self) -> Option<String> {
+        if self.block_index < 0 || self.block_index as u64 >= self.tree_size {
+            return None;
+        }
+
+        let mut fn_ = self.block_index as u64;
+        let mut sn = self.tree_size - 1;
+        let mut running = leaf_hash(&self.block_hash);
+
+        for sibling in &self.audit_path {
+            if sn == 0 {
+                return None;
+            }
+            if fn_ & 1 == 1 || fn_ == sn {
+                running = node_hash(sibling, &running);
+                while fn_ & 1 == 0 && fn_ != 0 {
+                    fn_ >>= 1;
+                    sn >>= 1;
+                }
+            } else {
+                running = node_hash(&running, sibling);
+            }
+            fn_ >>= 1;
+            sn >>= 1;
+        }
+
+        if sn != 0 {
+            return None;
+        }
+        Some(running)
+    }
+
+    /// Verify the proof against a ledger root
+    ///
+    /// # Arguments
+    /// * `root` - Root commitment obtained from a trusted source
+    pub fn verify(&self, root: &LedgerRoot) -> bool {
+        self.tree_size == root.tree_size && self.compute_root().as_deref() == Some(&root.root)
+    }
+}
+
+/// Append-only Merkle accumulator over block hashes
+///
+/// `levels[h][i]` holds the root of the perfect subtree of `2^h` leaves that
+/// starts at leaf `i * 2^h`. Appending is amortized O(1), while roots and
+/// proofs for any prefix are assembled from these subtrees in O(log^2 n).
+#[derive(Debug, Clone, Default)]
+pub struct MerkleAccumulator {
+    levels: Vec<Vec<String>>,
+}
+
+impl MerkleAccumulator {
+    /// Create an empty accumulator
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build an accumulator from an ordered sequence of block hashes
+    pub fn from_block_hashes<'a>(hashes: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut acc = Self::new();
+        for hash in hashes {
+            acc.push(hash);
+        }
+        acc
+    }
+
+    /// Number of leaves in the accumulator
+    pub fn len(&self) -> u64 {
+        self.levels.first().map(|l| l.len() as u64).unwrap_or(0)
+    }
+
+    /// Leaf hash at `index`, as produced by [`leaf_hash`]
+    pub fn leaf(&self, index: u64) -> Option<&str> {
+        self.levels.first()?.get(index as usize).map(String::as_str)
+    }
+
+    /// Whether the accumulator has no leaves
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Append a block hash as the next leaf
+    pub fn push(&mut self, block_hash: &str) {
+        let mut node = leaf_hash(block_hash);
+        let mut height = 0;
+
+        loop {
+            if self.levels.len() == height {
+                self.levels.push(Vec::new());
+            }
+            let level = &mut self.levels[height];
+            level.push(node);
+            if level.len() % 2 == 1 {
+                break;
+            }
+            let right = &level[level.len() - 1];
+            let left = &level[level.len() - 2];
+            node = node_hash(left, right);
+            height += 1;
+        }
+    }
+
+    /// Merkle root over all leaves
+    pub fn root(&self) -> String {
+        self.root_at(self.len()).unwrap_or_else(empty_root)
+    }
+
+    /// Merkle root over the first `tree_size` leaves
+    pub fn root_at(&self, tree_size: u64) -> Option<String> {
+        if tree_size > self.len() {
+            return None;
+        }
+        if tree_size == 0 {
+            return Some(empty_root());
+        }
+        Some(self.subtree_hash(0, tree_size))
+    }
+
+    /// Audit path for leaf `leaf_index` in the tree of the first `tree_size` leaves
+    pub fn audit_path(&self, leaf_index: u64, tree_size: u64) -> Option<Vec<String>> {
+        if tree_size > self.len() || leaf_index >= tree_size {
+            return None;
+        }
+        let mut path = Vec::new();
+        self.collect_path(leaf_index, 0, tree_size, &mut path);
+        Some(path)
+    }
+
+    /// Hash of the RFC 6962 subtree covering leaves `[start, start + size)`
+    fn subtree_hash(&self, start: u64, size: u64) -> String {
+        if size.is_power_of_two() {
+            let height = size.trailing_zeros() as usize;
+            return self.levels[height][(start >> height) as usize].clone();
+        }
+        let k = split_point(size);
+        node_hash(
+            &self.subtree_hash(start, k),
+            &self.subtree_hash(start + k, size - k),
+        )
+    }
+
+    /// Collect the audit path for `leaf` within `[start, start + size)`, leaf first
+    fn collect_path(&self, leaf: u64, start: u64, size: u64, path: &mut Vec<String>) {
+        if size <= 1 {
+            return;
+        }
+        let k = split_point(size);
+        if leaf < k {
+            self.collect_path(leaf, start, k, path);
+            path.push(self.subtree_hash(start + k, size - k));
+        } else {
+            self.collect_path(leaf - k, start + k, size - k, path);
+            path.push(self.subtree_hash(start, k));
+        }
+    }
+}
+
+/// Right edge of an RFC 6962 tree: the perfect subtrees covering its leaves
+///
+/// Holds `O(log n)` hashes yet yields the same root as a
+/// [`MerkleAccumulator`] over the same leaves, and can be extended with
+/// further leaves. Used where keeping every leaf in memory is too costly,
+/// such as streaming verification of large ledgers.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompactRange {
+    size: u64,
+    /// `(height, hash)` of each perfect subtree, largest (leftmost) first
+    nodes: Vec<(u32, String)>,
+}
+
+impl CompactRange {
+    /// Create an empty range
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of leaves covered
+    pub fn len(&self) -> u64 {
+        self.size
+    }
+
+    /// Whether the range covers no leaves
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    /// Append a block hash as the next leaf
+    pub fn push(&mut self, block_hash: &str) {
+        let mut node = (0, leaf_hash(block_hash));
+        while let Some((height, _)) = self.nodes.last() {
+            if *height != node.0 {
+                break;
+            }
+            let (height, left) = self.nodes.pop().expect("checked above");
+            node = (height + 1, node_hash(&left, &node.1));
+        }
+        self.nodes.push(node);
+        self.size += 1;
+    }
+
+    /// Merkle root over the covered leaves
+    pub fn root(&self) -> String {
+        // The first subtree is the left child of the root, the rest form the
+        // right child, recursively
+        let mut nodes = self.nodes.iter().rev();
+        match nodes.next() {
+            None => empty_root(),
+            Some((_, last)) => nodes.fold(last.clone(), |right, (_, left)| node_hash(left, &right)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block_hashes(n: usize) -> Vec<String> {
+        (0..n).map(|i| format!("{:064x}", i + 1)).collect()
+    }
+
+    /// Reference RFC 6962 tree hash computed recursively from the leaves
+    fn reference_root(hashes: &[String]) -> String {
+        match hashes.len() {
+            0 => empty_root(),
+            1 => leaf_hash(&hashes[0]),
+            n => {
+                let k = split_point(n as u64) as usize;
+                node_hash(&reference_root(&hashes[..k]), &reference_root(&hashes[k..]))
+            }
+        }
+    }
+
+    #[test]
+    fn test_empty_accumulator() {
+        let acc = MerkleAccumulator::new();
+        assert!(acc.is_empty());
+        assert_eq!(acc.root(), empty_root());
+        assert!(acc.audit_path(0, 0).is_none());
+    }
+
+    #[test]
+    fn test_root_matches_reference() {
+        let hashes = block_hashes(33);
+        let mut acc = MerkleAccumulator::new();
+        for (i, hash) in hashes.iter().enumerate() {
+            acc.push(hash);
+            assert_eq!(acc.root(), reference_root(&hashes[..=i]));
+        }
+        // Historical roots stay available
+        assert_eq!(acc.root_at(5).unwrap(), reference_root(&hashes[..5]));
+    }
+
+    #[test]
+    fn test_compact_range_matches_accumulator() {
+        let hashes = block_hashes(40);
+        let mut range = CompactRange::new();
+        assert_eq!(range.root(), empty_root());
+        for (i, hash) in hashes.iter().enumerate() {
+            range.push(hash);
+            assert_eq!(range.len(), i as u64 + 1);
+            assert_eq!(range.root(), reference_root(&hashes[..=i]));
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proofs_verify_for_every_leaf() {
+        for n in 1..=17 {
+            let hashes = block_hashes(n);
+            let acc = MerkleAccumulator::from_block_hashes(hashes.iter().map(|s| s.as_str()));
+            let root = LedgerRoot {
+                tree_size: n as u64,
+                root: acc.root(),
+                last_block_hash: hashes[n - 1].clone(),
+                timestamp: String::new(),
+                signer: None,
+                signature: None,
+            };
+
+            for (i, hash) in hashes.iter().enumerate() {
+                let proof = InclusionProof {
+                    block_index: i as i32,
+                    block_hash: hash.clone(),
+                    tree_size: n as u64,
+                    audit_path: acc.audit_path(i as u64, n as u64).unwrap(),
+                };
+                assert!(proof.verify(&root), "leaf {} of {} failed", i, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_tampered_proof_rejected() {
+        let hashes = block_hashes(10);
+        let acc = MerkleAccumulator::from_block_hashes(hashes.iter().map(|s| s.as_str()));
+        let root = LedgerRoot {
+            tree_size: 10,
+            root: acc.root(),
+            last_block_hash: hashes[9].clone(),
+            timestamp: String::new(),
+            signer: None,
+            signature: None,
+        };
+        let proof = InclusionProof {
+            block_index: 3,
+            block_hash: hashes[3].clone(),
+            tree_size: 10,
+            audit_path: acc.audit_path(3, 10).unwrap(),
+        };
+        assert!(proof.verify(&root));
+
+        // Wrong block hash
+        let mut bad = proof.clone();
+        bad.block_hash = hashes[4].clone();
+        assert!(!bad.verify(&root));
+
+        // Proof replayed for a different position
+        let mut bad = proof.clone();
+        bad.block_index = 2;
+        assert!(!bad.verify(&root));
+
+        // Truncated path
+        let mut bad = proof.clone();
+        bad.audit_path.pop();
+        assert!(!bad.verify(&root));
+
+        // Out of range index
+        let mut bad = proof;
+        bad.block_index = 10;
+        assert!(bad.compute_root().is_none());
+    }
+
+    #[test]
+    fn test_root_signature_respects_key_range() {
+        use crate::signing::LedgerSigner;
+
+        let k1 = LedgerSigner::generate("k1");
+        let k2 = LedgerSigner::generate("k2");
+        let mut registry = KeyRegistry::default();
+        registry.register("k1", &k1.public_key_hex(), 0).unwrap();
+        registry.rotate("k2", &k2.public_key_hex(), 5).unwrap();
+
+        let sign = |signer: &LedgerSigner, tree_size: u64| {
+            let mut root = LedgerRoot {
+                tree_size,
+                root: String::new(),
+                last_block_hash: String::new(),
+                timestamp: String::new(),
+                signer: Some(signer.key_id().to_string()),
+                signature: None,
+            };
+            root.signature = Some(signer.sign(root.signing_payload().as_bytes()));
+            root
+        };
+
+        assert!(sign(&k1, 5).verify_signature(&registry));
+        assert!(sign(&k2, 8).verify_signature(&registry));
+        // Retired key past its range, new key before its range
+        assert!(!sign(&k1, 8).verify_signature(&registry));
+        assert!(!sign(&k2, 5).verify_signature(&registry));
+    }
+}
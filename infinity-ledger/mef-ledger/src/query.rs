@@ -0,0 +1,271 @@
+//! Secondary indexes and range queries over ledger blocks.
+//!
+//! The indexes are built in memory from the block summaries held in
+//! `ledger_index.json`, so answering a query never touches the segment files.
+//! Summaries carry the seed and fixpoint norm of each block for that purpose.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::mef_block::BlockSummary;
+
+/// Filter over ledger blocks; all set criteria must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LedgerQuery {
+    pub tic_id: Option<String>,
+    pub seed: Option<String>,
+    /// Inclusive lower timestamp bound (RFC 3339 or `YYYY-MM-DD`)
+    pub from: Option<String>,
+    /// Exclusive upper timestamp bound (RFC 3339 or `YYYY-MM-DD`)
+    pub to: Option<String>,
+    /// Inclusive lower bound on `CompactTic::fixpoint_norm`
+    pub min_fixpoint_norm: Option<f64>,
+    /// Inclusive upper bound on `CompactTic::fixpoint_norm`
+    pub max_fixpoint_norm: Option<f64>,
+    /// Maximum number of results
+    pub limit: Option<usize>,
+}
+
+/// Normalize a query timestamp to the ledger's timestamp format
+///
+/// Ledger timestamps share one fixed-width UTC format, so normalized bounds
+/// compare correctly as strings.
+pub fn normalize_timestamp(value: &str) -> Result<String> {
+    let time = match DateTime::parse_from_rfc3339(value) {
+        Ok(time) => time.with_timezone(&Utc),
+        Err(_) => NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .with_context(|| format!("Invalid timestamp: {}", value))?
+            .and_hms_opt(0, 0, 0)
+            .expect("midnight is a valid time")
+            .and_utc(),
+    };
+    Ok(time.format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string())
+}
+
+/// In-memory secondary indexes over block summaries
+#[derive(Debug, Clone, Default)]
+pub struct SecondaryIndex {
+    by_tic_id: HashMap<String, Vec<i32>>,
+    by_seed: HashMap<String, Vec<i32>>,
+    /// `(timestamp, index)` sorted by timestamp
+    by_timestamp: Vec<(String, i32)>,
+    /// `(fixpoint_norm, index)` sorted by norm
+    by_fixpoint_norm: Vec<(f64, i32)>,
+}
+
+impl SecondaryIndex {
+    /// Build the indexes from ordered block summaries
+    pub fn build(blocks: &[BlockSummary]) -> Self {
+        let mut index = Self::default();
+        for block in blocks {
+            index.insert(block);
+        }
+        index
+    }
+
+    /// Add a newly appended block
+    pub fn insert(&mut self, block: &BlockSummary) {
+        self.by_tic_id
+            .entry(block.tic_id.clone())
+            .or_default()
+            .push(block.index);
+        if let Some(seed) = &block.seed {
+            self.by_seed
+                .entry(seed.clone())
+                .or_default()
+                .push(block.index);
+        }
+
+        let pos = self
+            .by_timestamp
+            .partition_point(|(ts, _)| ts.as_str() <= block.timestamp.as_str());
+        self.by_timestamp
+            .insert(pos, (block.timestamp.clone(), block.index));
+
+        if let Some(norm) = block.fixpoint_norm {
+            let pos = self.by_fixpoint_norm.partition_point(|(n, _)| *n <= norm);
+            self.by_fixpoint_norm.insert(pos, (norm, block.index));
+        }
+    }
+
+    /// Indices of blocks committing the given TIC
+    pub fn by_tic_id(&self, tic_id: &str) -> &[i32] {
+        self.by_tic_id.get(tic_id).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Indices of blocks derived from the given seed
+    pub fn by_seed(&self, seed: &str) -> &[i32] {
+        self.by_seed.get(seed).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Sorted indices of blocks with `from <= timestamp < to`
+    ///
+    /// Bounds must already be normalized with [`normalize_timestamp`].
+    pub fn by_time_range(&self, from: Option<&str>, to: Option<&str>) -> Vec<i32> {
+        let start = from
+            .map(|f| self.by_timestamp.partition_point(|(ts, _)| ts.as_str() < f))
+            .unwrap_or(0);
+        let end = to
+            .map(|t| self.by_timestamp.partition_point(|(ts, _)| ts.as_str() < t))
+            .unwrap_or(self.by_timestamp.len());
+
+        let mut indices: Vec<i32> = self.by_timestamp[start..end.max(start)]
+            .iter()
+            .map(|(_, i)| *i)
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Sorted indices of blocks with `min <= fixpoint_norm <= max`
+    pub fn by_fixpoint_norm(&self, min: Option<f64>, max: Option<f64>) -> Vec<i32> {
+        let start = min
+            .map(|m| self.by_fixpoint_norm.partition_point(|(n, _)| *n < m))
+            .unwrap_or(0);
+        let end = max
+            .map(|m| self.by_fixpoint_norm.partition_point(|(n, _)| *n <= m))
+            .unwrap_or(self.by_fixpoint_norm.len());
+
+        let mut indices: Vec<i32> = self.by_fixpoint_norm[start..end.max(start)]
+            .iter()
+            .map(|(_, i)| *i)
+            .collect();
+        indices.sort_unstable();
+        indices
+    }
+
+    /// Evaluate a query, returning matching block indices in ascending order
+    pub fn query(&self, query: &LedgerQuery) -> Result<Vec<i32>> {
+        let mut candidates: Vec<Vec<i32>> = Vec::new();
+
+        if let Some(tic_id) = &query.tic_id {
+            candidates.push(self.by_tic_id(tic_id).to_vec());
+        }
+        if let Some(seed) = &query.seed {
+            candidates.push(self.by_seed(seed).to_vec());
+        }
+        if query.from.is_some() || query.to.is_some() {
+            let from = query.from.as_deref().map(normalize_timestamp).transpose()?;
+            let to = query.to.as_deref().map(normalize_timestamp).transpose()?;
+            candidates.push(self.by_time_range(from.as_deref(), to.as_deref()));
+        }
+        if query.min_fixpoint_norm.is_some() || query.max_fixpoint_norm.is_some() {
+            candidates
+                .push(self.by_fixpoint_norm(query.min_fixpoint_norm, query.max_fixpoint_norm));
+        }
+
+        let mut result = match candidates.pop() {
+            Some(first) => candidates
+                .iter()
+                .fold(first, |acc, other| intersect(&acc, other)),
+            None => self.by_time_range(None, None),
+        };
+        if let Some(limit) = query.limit {
+            result.truncate(limit);
+        }
+        Ok(result)
+    }
+}
+
+/// Intersection of two ascending index lists
+fn intersect(a: &[i32], b: &[i32]) -> Vec<i32> {
+    let (mut i, mut j) = (0, 0);
+    let mut out = Vec::new();
+    while i < a.len() && j < b.len() {
+        match a[i].cmp(&b[j]) {
+            std::cmp::Ordering::Less => i += 1,
+            std::cmp::Ordering::Greater => j += 1,
+            std::cmp::Ordering::Equal => {
+                out.push(a[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn summary(index: i32, tic_id: &str, seed: &str, timestamp: &str, norm: f64) -> BlockSummary {
+        BlockSummary {
+            index,
+            hash: format!("{:064x}", index),
+            tic_id: tic_id.to_string(),
+            timestamp: timestamp.to_string(),
+            file: String::new(),
+            segment: 0,
+            offset: 0,
+            seed: Some(seed.to_string()),
+            fixpoint_norm: Some(norm),
+            checkpoint: None,
+            pruned: false,
+        }
+    }
+
+    fn sample_index() -> SecondaryIndex {
+        SecondaryIndex::build(&[
+            summary(0, "tic-a", "S1", "2026-10-14T23:59:59.000000Z", 0.5),
+            summary(1, "tic-b", "S1", "2026-10-15T08:00:00.000000Z", 1.0),
+            summary(2, "tic-a", "S2", "2026-10-15T17:30:00.000000Z", 1.5),
+            summary(3, "tic-c", "S2", "2026-10-16T00:00:00.000000Z", 2.0),
+        ])
+    }
+
+    #[test]
+    fn test_normalize_timestamp() {
+        assert_eq!(
+            normalize_timestamp("2026-10-15").unwrap(),
+            "2026-10-15T00:00:00.000000Z"
+        );
+        assert_eq!(
+            normalize_timestamp("2026-10-15T12:00:00+02:00").unwrap(),
+            "2026-10-15T10:00:00.000000Z"
+        );
+        assert!(normalize_timestamp("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_single_criteria() {
+        let index = sample_index();
+        assert_eq!(index.by_tic_id("tic-a"), &[0, 2]);
+        assert_eq!(index.by_seed("S2"), &[2, 3]);
+        assert!(index.by_tic_id("missing").is_empty());
+
+        let day = LedgerQuery {
+            from: Some("2026-10-15".to_string()),
+            to: Some("2026-10-16".to_string()),
+            ..LedgerQuery::default()
+        };
+        assert_eq!(index.query(&day).unwrap(), vec![1, 2]);
+
+        let norms = LedgerQuery {
+            min_fixpoint_norm: Some(1.0),
+            max_fixpoint_norm: Some(1.5),
+            ..LedgerQuery::default()
+        };
+        assert_eq!(index.query(&norms).unwrap(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_combined_query() {
+        let index = sample_index();
+        let query = LedgerQuery {
+            tic_id: Some("tic-a".to_string()),
+            from: Some("2026-10-15".to_string()),
+            min_fixpoint_norm: Some(1.2),
+            ..LedgerQuery::default()
+        };
+        assert_eq!(index.query(&query).unwrap(), vec![2]);
+
+        let all = LedgerQuery {
+            limit: Some(3),
+            ..LedgerQuery::default()
+        };
+        assert_eq!(index.query(&all).unwrap(), vec![0, 1, 2]);
+    }
+}
@@ -0,0 +1,747 @@
+//! Append-only segment storage for ledger blocks.
+//!
+//! Blocks are stored as length-prefixed, checksummed records inside
+//! fixed-size segment files:
+//!
+//! ```text
+//! segment header: b"MEFSEG" | version: u16 LE
+//! record:         len: u32 LE | crc32(payload): u32 LE | payload (compact JSON block)
+//! ```
+//!
+//! Every append is fsynced before it is acknowledged. A crash can therefore
+//! only leave a torn record at the tail of the newest segment, which is
+//! truncated away when the store is reopened. The dropped bytes are kept in a
+//! `.tail` file next to the segment and reported as a [`TruncatedTail`].
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::mef_block::MefBlock;
+
+/// Magic bytes at the start of every segment file
+const SEGMENT_MAGIC: &[u8; 6] = b"MEFSEG";
+
+/// Current segment format version
+const SEGMENT_VERSION: u16 = 1;
+
+/// Size of the segment header in bytes
+const HEADER_LEN: u64 = 8;
+
+/// Size of a record header (length + checksum) in bytes
+const RECORD_HEADER_LEN: u64 = 8;
+
+/// Default maximum segment size before rotation (64 MiB)
+pub const DEFAULT_MAX_SEGMENT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest accepted record payload (64 MiB)
+///
+/// Record lengths are read from disk, so a corrupt header must not be able
+/// to request an arbitrarily large allocation.
+pub const MAX_RECORD_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Position of a block record inside the segment files
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordLocation {
+    pub segment: u32,
+    pub offset: u64,
+}
+
+/// Invalid tail of the newest segment that was cut off on open
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TruncatedTail {
+    pub segment: u32,
+    /// Offset of the first dropped byte
+    pub offset: u64,
+    /// Number of bytes dropped
+    pub bytes: u64,
+    /// Why the tail record could not be read
+    pub reason: String,
+    /// File holding the dropped bytes
+    pub saved_to: PathBuf,
+}
+
+/// Append-only store of block records split across segment files
+pub struct SegmentStore {
+    dir: PathBuf,
+    max_segment_bytes: u64,
+    active_segment: u32,
+    active_len: u64,
+    truncated_tail: Option<TruncatedTail>,
+}
+
+impl SegmentStore {
+    /// Open (or create) a segment store and recover a torn tail
+    ///
+    /// # Arguments
+    /// * `dir` - Directory holding the segment files
+    /// * `max_segment_bytes` - Size threshold for rotating to a new segment
+    pub fn open(dir: impl AsRef<Path>, max_segment_bytes: u64) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        std::fs::create_dir_all(&dir).context("Failed to create segment directory")?;
+
+        let segments = Self::list_segments(&dir)?;
+        let mut store = Self {
+            dir,
+            max_segment_bytes,
+            active_segment: 0,
+            active_len: 0,
+            truncated_tail: None,
+        };
+
+        match segments.last() {
+            Some(&last) => {
+                store.active_segment = last;
+                store.active_len = store.recover_tail(last)?;
+            }
+            None => {
+                store.create_segment(0)?;
+            }
+        }
+
+        Ok(store)
+    }
+
+    /// Directory holding the segment files
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Tail that was cut off when the store was opened, if any
+    pub fn truncated_tail(&self) -> Option<&TruncatedTail> {
+        self.truncated_tail.as_ref()
+    }
+
+    /// File name of a segment
+    pub fn segment_file_name(segment: u32) -> String {
+        format!("segment_{:06}.mseg", segment)
+    }
+
+    fn segment_path(&self, segment: u32) -> PathBuf {
+        self.dir.join(Self::segment_file_name(segment))
+    }
+
+    /// List segment numbers present in a directory, in ascending order
+    pub fn list_segments(dir: &Path) -> Result<Vec<u32>> {
+        let mut segments = Vec::new();
+        if !dir.exists() {
+            return Ok(segments);
+        }
+        for entry in std::fs::read_dir(dir).context("Failed to read segment directory")? {
+            let name = entry?.file_name();
+            let name = name.to_string_lossy();
+            if let Some(num) = name
+                .strip_prefix("segment_")
+                .and_then(|rest| rest.strip_suffix(".mseg"))
+                .and_then(|num| num.parse::<u32>().ok())
+            {
+                segments.push(num);
+            }
+        }
+        segments.sort_unstable();
+        Ok(segments)
+    }
+
+    /// Create an empty segment file with a header and make it durable
+    fn create_segment(&mut self, segment: u32) -> Result<()> {
+        let path = self.segment_path(segment);
+        let mut file = OpenOptions::new()
+            .create_new(true)
+            .write(true)
+            .open(&path)
+            .with_context(|| format!("Failed to create segment {:?}", path))?;
+        file.write_all(SEGMENT_MAGIC)?;
+        file.write_all(&SEGMENT_VERSION.to_le_bytes())?;
+        file.sync_all().context("Failed to sync segment header")?;
+        sync_dir(&self.dir)?;
+
+        self.active_segment = segment;
+        self.active_len = HEADER_LEN;
+        Ok(())
+    }
+
+    /// Scan the newest segment and truncate any incomplete trailing record
+    ///
+    /// The dropped bytes are saved next to the segment and reported on
+    /// stderr and through [`SegmentStore::truncated_tail`]. Returns the
+    /// length of the valid prefix.
+    fn recover_tail(&mut self, segment: u32) -> Result<u64> {
+        let path = self.segment_path(segment);
+        let file_len = std::fs::metadata(&path)?.len();
+
+        if file_len < HEADER_LEN {
+            // Crash while writing the header: start the segment over
+            let mut file = OpenOptions::new().write(true).open(&path)?;
+            file.set_len(0)?;
+            file.write_all(SEGMENT_MAGIC)?;
+            file.write_all(&SEGMENT_VERSION.to_le_bytes())?;
+            file.sync_all()?;
+            return Ok(HEADER_LEN);
+        }
+
+        let mut valid_len = HEADER_LEN;
+        let mut reason = None;
+        let mut reader = SegmentReader::open(&path)?;
+        loop {
+            match reader.next_record() {
+                Ok(Some((_, _))) => valid_len = reader.position(),
+                Ok(None) => break,
+                Err(e) => {
+                    // Only the last record may be torn; a bad record followed by
+                    // more data is corruption and must not be silently dropped.
+                    if Self::record_end(&path, valid_len)? < file_len {
+                        return Err(e.context(format!(
+                            "Corrupt record in {} at offset {}",
+                            Self::segment_file_name(segment),
+                            valid_len
+                        )));
+                    }
+                    reason = Some(format!("{:#}", e));
+                    break;
+                }
+            }
+        }
+
+        if valid_len < file_len {
+            let saved_to = self.save_tail(segment, valid_len)?;
+            let file = OpenOptions::new().write(true).open(&path)?;
+            file.set_len(valid_len)
+                .context("Failed to truncate torn segment tail")?;
+            file.sync_all()?;
+
+            let tail = TruncatedTail {
+                segment,
+                offset: valid_len,
+                bytes: file_len - valid_len,
+                reason: reason.unwrap_or_else(|| "Truncated record".to_string()),
+                saved_to,
+            };
+            eprintln!(
+                "Warning: dropped {} bytes at offset {} of {} ({}); saved to {:?}",
+                tail.bytes,
+                tail.offset,
+                Self::segment_file_name(segment),
+                tail.reason,
+                tail.saved_to
+            );
+            self.truncated_tail = Some(tail);
+        }
+
+        Ok(valid_len)
+    }
+
+    /// Copy the bytes of a segment from `offset` on into a `.tail` file
+    fn save_tail(&self, segment: u32, offset: u64) -> Result<PathBuf> {
+        let path = self.segment_path(segment);
+        let saved_to = self.dir.join(format!(
+            "{}.{}.tail",
+            Self::segment_file_name(segment),
+            offset
+        ));
+        let mut file = File::open(&path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut tail =
+            File::create(&saved_to).with_context(|| format!("Failed to create {:?}", saved_to))?;
+        std::io::copy(&mut file, &mut tail).context("Failed to save segment tail")?;
+        tail.sync_all()?;
+        Ok(saved_to)
+    }
+
+    /// End offset declared by the record header at `offset` (or `u64::MAX` if
+    /// the header itself is incomplete)
+    fn record_end(path: &Path, offset: u64) -> Result<u64> {
+        let mut file = File::open(path)?;
+        file.seek(SeekFrom::Start(offset))?;
+        let mut len = [0u8; 4];
+        match file.read_exact(&mut len) {
+            Ok(()) => Ok(offset + RECORD_HEADER_LEN + u32::from_le_bytes(len) as u64),
+            Err(_) => Ok(u64::MAX),
+        }
+    }
+
+    /// Append a block as a new record and fsync it
+    ///
+    /// # Arguments
+    /// * `block` - Block to persist
+    pub fn append(&mut self, block: &MefBlock) -> Result<RecordLocation> {
+        let record = encode_record(block)?;
+        let record_len = record.len() as u64;
+
+        if self.active_len > HEADER_LEN && self.active_len + record_len > self.max_segment_bytes {
+            self.create_segment(self.active_segment + 1)?;
+        }
+
+        let path = self.segment_path(self.active_segment);
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("Failed to open segment {:?}", path))?;
+
+        file.write_all(&record)
+            .context("Failed to write segment record")?;
+        file.sync_data().context("Failed to sync segment")?;
+
+        let location = RecordLocation {
+            segment: self.active_segment,
+            offset: self.active_len,
+        };
+        self.active_len += record_len;
+        Ok(location)
+    }
+
+    /// Replace the records of an existing segment
+    ///
+    /// The new segment is written to a temporary file, fsynced and renamed
+    /// over the old one, so readers see either the old or the new contents.
+    /// Returns the new record locations in order.
+    ///
+    /// # Arguments
+    /// * `segment` - Segment number to rewrite
+    /// * `blocks` - Replacement records
+    pub fn rewrite_segment(
+        &mut self,
+        segment: u32,
+        blocks: &[MefBlock],
+    ) -> Result<Vec<RecordLocation>> {
+        let path = self.segment_path(segment);
+        if !path.exists() {
+            anyhow::bail!("Segment {} does not exist", segment);
+        }
+
+        let tmp_path = path.with_extension("mseg.tmp");
+        let mut contents = Vec::new();
+        contents.extend_from_slice(SEGMENT_MAGIC);
+        contents.extend_from_slice(&SEGMENT_VERSION.to_le_bytes());
+
+        let mut locations = Vec::with_capacity(blocks.len());
+        for block in blocks {
+            locations.push(RecordLocation {
+                segment,
+                offset: contents.len() as u64,
+            });
+            contents.extend_from_slice(&encode_record(block)?);
+        }
+
+        let mut file =
+            File::create(&tmp_path).with_context(|| format!("Failed to create {:?}", tmp_path))?;
+        file.write_all(&contents)
+            .context("Failed to write rewritten segment")?;
+        file.sync_all()
+            .context("Failed to sync rewritten segment")?;
+        std::fs::rename(&tmp_path, &path).context("Failed to replace segment")?;
+        sync_dir(&self.dir)?;
+
+        if segment == self.active_segment {
+            self.active_len = contents.len() as u64;
+        }
+        Ok(locations)
+    }
+
+    /// Read the block stored at a location
+    pub fn read(&self, location: RecordLocation) -> Result<MefBlock> {
+        let path = self.segment_path(location.segment);
+        let mut reader = SegmentReader::open(&path)?;
+        reader.seek(location.offset)?;
+        match reader.next_record()? {
+            Some((_, block)) => Ok(block),
+            None => anyhow::bail!(
+                "No record at segment {} offset {}",
+                location.segment,
+                location.offset
+            ),
+        }
+    }
+
+    /// Re-read the active segment position after another process appended
+    ///
+    /// Must only be called while holding the ledger write lock.
+    pub fn refresh(&mut self) -> Result<()> {
+        if let Some(&last) = Self::list_segments(&self.dir)?.last() {
+            self.active_segment = last;
+            self.active_len = std::fs::metadata(self.segment_path(last))?.len();
+        }
+        Ok(())
+    }
+
+    /// Reopen the store, truncating a torn tail left by a crashed writer
+    ///
+    /// Must only be called while holding the ledger write lock.
+    pub fn recover(&mut self) -> Result<()> {
+        *self = Self::open(self.dir.clone(), self.max_segment_bytes)?;
+        Ok(())
+    }
+
+    /// Open a segment file for reading
+    ///
+    /// An open handle keeps seeing the segment contents it was opened on,
+    /// even if the segment is later rewritten by pruning.
+    pub fn open_segment_file(&self, segment: u32) -> Result<File> {
+        let path = self.segment_path(segment);
+        File::open(&path).with_context(|| format!("Failed to open segment {:?}", path))
+    }
+
+    /// Read the block record at `offset` from an open segment file
+    pub fn read_from(file: &mut File, offset: u64) -> Result<MefBlock> {
+        let mut reader = SegmentReader::from_reader(file)?;
+        reader.seek(offset)?;
+        match reader.next_record()? {
+            Some((_, block)) => Ok(block),
+            None => anyhow::bail!("No record at offset {}", offset),
+        }
+    }
+
+    /// Whether the store holds no records
+    pub fn is_empty(&self) -> bool {
+        self.active_segment == 0 && self.active_len == HEADER_LEN
+    }
+
+    /// Whether the record at `location` is the last one in the store
+    pub fn is_tail(&self, location: RecordLocation) -> Result<bool> {
+        if location.segment != self.active_segment {
+            return Ok(false);
+        }
+        let end = Self::record_end(&self.segment_path(location.segment), location.offset)?;
+        Ok(end == self.active_len)
+    }
+
+    /// Read every record in segment order
+    pub fn scan(&self) -> Result<Vec<(RecordLocation, MefBlock)>> {
+        let mut records = Vec::new();
+        for segment in Self::list_segments(&self.dir)? {
+            let mut reader = SegmentReader::open(&self.segment_path(segment))?;
+            while let Some((offset, block)) = reader.next_record().with_context(|| {
+                format!("Corrupt record in {}", Self::segment_file_name(segment))
+            })? {
+                records.push((RecordLocation { segment, offset }, block));
+            }
+        }
+        Ok(records)
+    }
+
+    /// Reader for many records in location order
+    pub fn cursor(&self) -> SegmentCursor<'_> {
+        SegmentCursor {
+            store: self,
+            readers: HashMap::new(),
+        }
+    }
+
+    /// Total size of all segment files in bytes
+    pub fn total_size(&self) -> Result<u64> {
+        let mut total = 0;
+        for segment in Self::list_segments(&self.dir)? {
+            total += std::fs::metadata(self.segment_path(segment))?.len();
+        }
+        Ok(total)
+    }
+}
+
+/// Reads records by location, keeping one open reader per segment
+///
+/// Consecutive records of a segment are read without reopening the file or
+/// discarding the read buffer, which makes full scans in index order cheap.
+pub struct SegmentCursor<'a> {
+    store: &'a SegmentStore,
+    readers: HashMap<u32, SegmentReader>,
+}
+
+impl SegmentCursor<'_> {
+    /// Read the block stored at a location
+    ///
+    /// Returns `Ok(None)` if the segment file or the record does not exist
+    /// and an error for unreadable or corrupt records.
+    pub fn read(&mut self, location: RecordLocation) -> Result<Option<MefBlock>> {
+        let reader = match self.readers.entry(location.segment) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => {
+                let path = self.store.segment_path(location.segment);
+                if !path.exists() {
+                    return Ok(None);
+                }
+                entry.insert(SegmentReader::open(&path)?)
+            }
+        };
+
+        if reader.position() != location.offset {
+            reader.seek(location.offset)?;
+        }
+        match reader.next_record() {
+            Ok(record) => Ok(record.map(|(_, block)| block)),
+            Err(e) => {
+                // The reader position is unknown after a failed read
+                self.readers.remove(&location.segment);
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Encode a block as a length-prefixed, checksummed record
+fn encode_record(block: &MefBlock) -> Result<Vec<u8>> {
+    let payload = serde_json::to_vec(block).context("Failed to serialize block")?;
+    if payload.len() as u64 > MAX_RECORD_BYTES {
+        anyhow::bail!(
+            "Block {} is {} bytes, above the record limit of {} bytes",
+            block.index,
+            payload.len(),
+            MAX_RECORD_BYTES
+        );
+    }
+    let mut record = Vec::with_capacity(RECORD_HEADER_LEN as usize + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    record.extend_from_slice(&payload);
+    Ok(record)
+}
+
+/// Sequential reader over the records of one segment file
+struct SegmentReader<R = File> {
+    reader: BufReader<R>,
+    position: u64,
+}
+
+impl SegmentReader {
+    fn open(path: &Path) -> Result<Self> {
+        let file =
+            File::open(path).with_context(|| format!("Failed to open segment {:?}", path))?;
+        Self::from_reader(file).with_context(|| format!("Invalid segment {:?}", path))
+    }
+}
+
+impl<R: Read + Seek> SegmentReader<R> {
+    fn from_reader(mut inner: R) -> Result<Self> {
+        inner.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(inner);
+
+        let mut header = [0u8; HEADER_LEN as usize];
+        reader
+            .read_exact(&mut header)
+            .context("Failed to read segment header")?;
+        if &header[..6] != SEGMENT_MAGIC {
+            anyhow::bail!("Invalid segment magic");
+        }
+        let version = u16::from_le_bytes([header[6], header[7]]);
+        if version != SEGMENT_VERSION {
+            anyhow::bail!("Unsupported segment version {}", version);
+        }
+
+        Ok(Self {
+            reader,
+            position: HEADER_LEN,
+        })
+    }
+
+    fn position(&self) -> u64 {
+        self.position
+    }
+
+    fn seek(&mut self, offset: u64) -> Result<()> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.position = offset;
+        Ok(())
+    }
+
+    /// Read the next record, returning its offset and decoded block
+    ///
+    /// Returns `Ok(None)` at a clean end of file and an error for torn or
+    /// corrupt records.
+    fn next_record(&mut self) -> Result<Option<(u64, MefBlock)>> {
+        let offset = self.position;
+
+        let mut header = [0u8; RECORD_HEADER_LEN as usize];
+        match self.reader.read_exact(&mut header[..1]) {
+            Ok(()) => {}
+            Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+            Err(e) => return Err(e.into()),
+        }
+        self.reader
+            .read_exact(&mut header[1..])
+            .context("Truncated record header")?;
+
+        let len = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let checksum = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+        if len > MAX_RECORD_BYTES {
+            anyhow::bail!(
+                "Record at offset {} declares {} bytes, above the limit of {} bytes",
+                offset,
+                len,
+                MAX_RECORD_BYTES
+            );
+        }
+
+        // Grow the buffer with the bytes actually present instead of trusting
+        // the declared length
+        let mut payload = Vec::new();
+        (&mut self.reader)
+            .take(len)
+            .read_to_end(&mut payload)
+            .context("Failed to read record payload")?;
+        if (payload.len() as u64) < len {
+            anyhow::bail!("Truncated record payload at offset {}", offset);
+        }
+        if crc32fast::hash(&payload) != checksum {
+            anyhow::bail!("Checksum mismatch for record at offset {}", offset);
+        }
+
+        let block: MefBlock =
+            serde_json::from_slice(&payload).context("Failed to parse block record")?;
+        self.position = offset + RECORD_HEADER_LEN + len;
+        Ok(Some((offset, block)))
+    }
+}
+
+/// Fsync a directory so newly created entries survive a crash
+pub(crate) fn sync_dir(dir: &Path) -> Result<()> {
+    #[cfg(unix)]
+    {
+        File::open(dir)
+            .and_then(|d| d.sync_all())
+            .with_context(|| format!("Failed to sync directory {:?}", dir))?;
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = dir;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mef_block::CompactTic;
+
+    fn sample_block(index: i32) -> MefBlock {
+        MefBlock {
+            index,
+            previous_hash: "0".repeat(64),
+            timestamp: "2025-01-01T00:00:00.000000Z".to_string(),
+            tic_id: format!("tic-{}", index),
+            snapshot_hash: "abc".to_string(),
+            data: CompactTic {
+                tic_id: format!("tic-{}", index),
+                seed: "SEED".to_string(),
+                fixpoint_norm: 1.0,
+                invariants: serde_json::json!({}),
+                sigma_bar: serde_json::json!({}),
+                window: vec![],
+            },
+            proof: serde_json::Value::Null,
+            hash: format!("{:064x}", index),
+            signer: None,
+            signature: None,
+            checkpoint: None,
+            pruned: false,
+            hash_scheme: None,
+        }
+    }
+
+    #[test]
+    fn test_append_and_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SegmentStore::open(dir.path(), DEFAULT_MAX_SEGMENT_BYTES).unwrap();
+
+        let locations: Vec<_> = (0..3)
+            .map(|i| store.append(&sample_block(i)).unwrap())
+            .collect();
+
+        for (i, loc) in locations.iter().enumerate() {
+            assert_eq!(store.read(*loc).unwrap().index, i as i32);
+        }
+        assert_eq!(store.scan().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_segment_rotation() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SegmentStore::open(dir.path(), 600).unwrap();
+
+        for i in 0..10 {
+            store.append(&sample_block(i)).unwrap();
+        }
+
+        let segments = SegmentStore::list_segments(dir.path()).unwrap();
+        assert!(segments.len() > 1);
+
+        let scanned = store.scan().unwrap();
+        let indices: Vec<_> = scanned.iter().map(|(_, b)| b.index).collect();
+        assert_eq!(indices, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_torn_tail_is_truncated() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = SegmentStore::open(dir.path(), DEFAULT_MAX_SEGMENT_BYTES).unwrap();
+            store.append(&sample_block(0)).unwrap();
+            store.append(&sample_block(1)).unwrap();
+        }
+
+        // Simulate a crash halfway through writing a third record
+        let path = dir.path().join(SegmentStore::segment_file_name(0));
+        let good_len = std::fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&200u32.to_le_bytes()).unwrap();
+        file.write_all(&[0u8; 4]).unwrap();
+        file.write_all(b"{\"index\":").unwrap();
+        drop(file);
+
+        let mut store = SegmentStore::open(dir.path(), DEFAULT_MAX_SEGMENT_BYTES).unwrap();
+        assert_eq!(std::fs::metadata(&path).unwrap().len(), good_len);
+        assert_eq!(store.scan().unwrap().len(), 2);
+
+        // The dropped bytes are reported and kept
+        let tail = store.truncated_tail().unwrap().clone();
+        assert_eq!(tail.offset, good_len);
+        assert_eq!(tail.bytes, 17);
+        assert_eq!(std::fs::read(&tail.saved_to).unwrap().len(), 17);
+
+        // Appends continue cleanly after recovery
+        let loc = store.append(&sample_block(2)).unwrap();
+        assert_eq!(loc.offset, good_len);
+        assert_eq!(store.scan().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_corrupt_record_detected() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = SegmentStore::open(dir.path(), DEFAULT_MAX_SEGMENT_BYTES).unwrap();
+        let loc = store.append(&sample_block(0)).unwrap();
+
+        // Flip a payload byte
+        let path = dir.path().join(SegmentStore::segment_file_name(0));
+        let mut bytes = std::fs::read(&path).unwrap();
+        let pos = (loc.offset + RECORD_HEADER_LEN + 2) as usize;
+        bytes[pos] ^= 0xff;
+        std::fs::write(&path, bytes).unwrap();
+
+        assert!(store.read(loc).is_err());
+    }
+
+    #[test]
+    fn test_oversized_record_length_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        {
+            let mut store = SegmentStore::open(dir.path(), DEFAULT_MAX_SEGMENT_BYTES).unwrap();
+            store.append(&sample_block(0)).unwrap();
+        }
+
+        // A corrupt header declaring ~4 GiB must not be allocated up front
+        let path = dir.path().join(SegmentStore::segment_file_name(0));
+        let good_len = std::fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+        file.write_all(&u32::MAX.to_le_bytes()).unwrap();
+        file.write_all(&[0u8; 4]).unwrap();
+        file.write_all(b"{}").unwrap();
+        drop(file);
+
+        let store = SegmentStore::open(dir.path(), DEFAULT_MAX_SEGMENT_BYTES).unwrap();
+        assert_eq!(store.scan().unwrap().len(), 1);
+        let tail = store.truncated_tail().unwrap();
+        assert_eq!(tail.offset, good_len);
+        assert!(tail.reason.contains("above the limit"), "{}", tail.reason);
+    }
+}
@@ -0,0 +1,422 @@
+//! Ed25519 block signing and signer key registry.
+//!
+//! Each block records the key ID of the signer that produced it, and the
+//! signature covers the block hash. The registry, persisted in
+//! `key_registry.json` and mirrored in `LedgerMetadata`, maps key IDs to
+//! public keys together with the block range each key is allowed to sign, so
+//! rotation and revocation are checked per block.
+//!
+//! The registry stored next to the ledger is only as trustworthy as the
+//! directory it lives in; auditors should pin a registry obtained out of band
+//! and verify against it.
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+
+/// Lifecycle state of a signer key
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyStatus {
+    /// Key currently used for new blocks
+    Active,
+    /// Key replaced by a rotation; its earlier signatures stay valid
+    Retired,
+    /// Key compromised; signatures from `revoked_from_index` on are rejected
+    Revoked,
+}
+
+/// Registered signer public key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignerKey {
+    pub key_id: String,
+    /// Hex-encoded Ed25519 public key
+    pub public_key: String,
+    pub status: KeyStatus,
+    /// First block index the key may sign
+    pub valid_from_index: i32,
+    /// Last block index the key may sign (set on rotation)
+    pub valid_until_index: Option<i32>,
+    /// First block index whose signature by this key is rejected
+    pub revoked_from_index: Option<i32>,
+    pub added: String,
+}
+
+impl SignerKey {
+    /// Whether this key may sign the block at `index`
+    pub fn covers(&self, index: i32) -> bool {
+        if index < self.valid_from_index {
+            return false;
+        }
+        if let Some(until) = self.valid_until_index {
+            if index > until {
+                return false;
+            }
+        }
+        if let Some(revoked) = self.revoked_from_index {
+            if index >= revoked {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Registry of signer keys supporting rotation and revocation
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRegistry {
+    pub keys: Vec<SignerKey>,
+}
+
+impl KeyRegistry {
+    /// Look up a key by ID
+    pub fn get(&self, key_id: &str) -> Option<&SignerKey> {
+        self.keys.iter().find(|k| k.key_id == key_id)
+    }
+
+    /// Currently active key, if any
+    pub fn active(&self) -> Option<&SignerKey> {
+        self.keys.iter().find(|k| k.status == KeyStatus::Active)
+    }
+
+    /// First block index that must carry a signature
+    pub fn signed_from_index(&self) -> Option<i32> {
+        self.keys.iter().map(|k| k.valid_from_index).min()
+    }
+
+    /// Register the first signer key of a ledger
+    ///
+    /// # Arguments
+    /// * `key_id` - Signer key ID
+    /// * `public_key` - Hex-encoded Ed25519 public key
+    /// * `from_index` - First block index the key signs
+    pub fn register(&mut self, key_id: &str, public_key: &str, from_index: i32) -> Result<()> {
+        if self.get(key_id).is_some() {
+            anyhow::bail!("Key {} already registered", key_id);
+        }
+        if self.active().is_some() {
+            anyhow::bail!("Registry already has an active key; rotate instead");
+        }
+        parse_public_key(public_key)?;
+
+        self.keys.push(SignerKey {
+            key_id: key_id.to_string(),
+            public_key: public_key.to_string(),
+            status: KeyStatus::Active,
+            valid_from_index: from_index,
+            valid_until_index: None,
+            revoked_from_index: None,
+            added: now(),
+        });
+        Ok(())
+    }
+
+    /// Replace the active key, retiring it at `from_index - 1`
+    ///
+    /// # Arguments
+    /// * `key_id` - New signer key ID
+    /// * `public_key` - Hex-encoded Ed25519 public key
+    /// * `from_index` - First block index signed by the new key
+    pub fn rotate(&mut self, key_id: &str, public_key: &str, from_index: i32) -> Result<()> {
+        if self.get(key_id).is_some() {
+            anyhow::bail!("Key {} already registered", key_id);
+        }
+        parse_public_key(public_key)?;
+
+        if let Some(current) = self.keys.iter_mut().find(|k| k.status == KeyStatus::Active) {
+            current.status = KeyStatus::Retired;
+            current.valid_until_index = Some(from_index - 1);
+        }
+
+        self.keys.push(SignerKey {
+            key_id: key_id.to_string(),
+            public_key: public_key.to_string(),
+            status: KeyStatus::Active,
+            valid_from_index: from_index,
+            valid_until_index: None,
+            revoked_from_index: None,
+            added: now(),
+        });
+        Ok(())
+    }
+
+    /// Revoke a key so its signatures from `from_index` on are rejected
+    ///
+    /// Revoking an already revoked key can only move the revocation earlier;
+    /// blocks rejected once are never accepted again.
+    ///
+    /// # Arguments
+    /// * `key_id` - Key to revoke
+    /// * `from_index` - First affected block index (`None` rejects every block
+    ///   the key signed)
+    pub fn revoke(&mut self, key_id: &str, from_index: Option<i32>) -> Result<()> {
+        let key = self
+            .keys
+            .iter_mut()
+            .find(|k| k.key_id == key_id)
+            .ok_or_else(|| anyhow::anyhow!("Unknown key {}", key_id))?;
+
+        let from_index = from_index.unwrap_or(key.valid_from_index);
+        key.status = KeyStatus::Revoked;
+        key.revoked_from_index = Some(match key.revoked_from_index {
+            Some(existing) => existing.min(from_index),
+            None => from_index,
+        });
+        Ok(())
+    }
+
+    /// Check that `other` only extends this registry
+    ///
+    /// Every known key must keep its public key, status and signing range or
+    /// narrow them: a revoked key stays revoked, a retired key does not
+    /// become active again and no range grows. Keys new to this registry may
+    /// only sign blocks after `head_index`, the last block held locally.
+    ///
+    /// # Arguments
+    /// * `other` - Registry received from another replica
+    /// * `head_index` - Index of the last local block (`-1` if empty)
+    pub fn check_successor(&self, other: &KeyRegistry, head_index: i32) -> Result<()> {
+        for key in &self.keys {
+            let Some(theirs) = other.get(&key.key_id) else {
+                anyhow::bail!("Key {} is missing from the other registry", key.key_id);
+            };
+            if theirs.public_key != key.public_key {
+                anyhow::bail!("Key {} has a different public key", key.key_id);
+            }
+            let status_ok = match key.status {
+                KeyStatus::Active => true,
+                KeyStatus::Retired => theirs.status != KeyStatus::Active,
+                KeyStatus::Revoked => theirs.status == KeyStatus::Revoked,
+            };
+            if !status_ok {
+                anyhow::bail!(
+                    "Key {} would change from {:?} to {:?}",
+                    key.key_id,
+                    key.status,
+                    theirs.status
+                );
+            }
+            let narrows = |ours: Option<i32>, theirs: Option<i32>| match (ours, theirs) {
+                (Some(ours), Some(theirs)) => theirs <= ours,
+                (Some(_), None) => false,
+                (None, _) => true,
+            };
+            if theirs.valid_from_index < key.valid_from_index
+                || !narrows(key.valid_until_index, theirs.valid_until_index)
+                || !narrows(key.revoked_from_index, theirs.revoked_from_index)
+            {
+                anyhow::bail!("Key {} would cover more blocks than before", key.key_id);
+            }
+        }
+
+        for key in &other.keys {
+            if self.get(&key.key_id).is_none() && key.valid_from_index <= head_index {
+                anyhow::bail!(
+                    "New key {} would cover existing block {}",
+                    key.key_id,
+                    key.valid_from_index
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Verify a signature over a message made by `key_id` for block `index`
+    pub fn verify(&self, key_id: &str, index: i32, message: &[u8], signature: &str) -> bool {
+        match self.get(key_id) {
+            Some(key) if key.covers(index) => {
+                verify_signature(&key.public_key, message, signature).unwrap_or(false)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Ed25519 signing identity used to sign new blocks
+#[derive(Clone)]
+pub struct LedgerSigner {
+    key_id: String,
+    signing_key: SigningKey,
+}
+
+impl std::fmt::Debug for LedgerSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LedgerSigner")
+            .field("key_id", &self.key_id)
+            .field("public_key", &self.public_key_hex())
+            .finish()
+    }
+}
+
+impl LedgerSigner {
+    /// Generate a fresh random signing key
+    pub fn generate(key_id: impl Into<String>) -> Self {
+        Self {
+            key_id: key_id.into(),
+            signing_key: SigningKey::generate(&mut rand::rngs::OsRng),
+        }
+    }
+
+    /// Load a signing key from its hex-encoded 32-byte secret
+    pub fn from_secret_hex(key_id: impl Into<String>, secret: &str) -> Result<Self> {
+        let bytes = hex::decode(secret.trim()).context("Signer secret is not valid hex")?;
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| anyhow::anyhow!("Signer secret must be 32 bytes"))?;
+        Ok(Self {
+            key_id: key_id.into(),
+            signing_key: SigningKey::from_bytes(&bytes),
+        })
+    }
+
+    /// Load the signer from `MEF_LEDGER_SIGNER_KEY_ID` and `MEF_LEDGER_SIGNER_SECRET`
+    pub fn from_env() -> Result<Option<Self>> {
+        match (
+            std::env::var("MEF_LEDGER_SIGNER_KEY_ID"),
+            std::env::var("MEF_LEDGER_SIGNER_SECRET"),
+        ) {
+            (Ok(key_id), Ok(secret)) => Ok(Some(Self::from_secret_hex(key_id, &secret)?)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Key ID recorded in signed blocks
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Hex-encoded public key for the registry
+    pub fn public_key_hex(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().to_bytes())
+    }
+
+    /// Hex-encoded secret key
+    pub fn secret_hex(&self) -> String {
+        hex::encode(self.signing_key.to_bytes())
+    }
+
+    /// Sign a message, returning the hex-encoded signature
+    pub fn sign(&self, message: &[u8]) -> String {
+        hex::encode(self.signing_key.sign(message).to_bytes())
+    }
+}
+
+/// Verify a hex-encoded Ed25519 signature against a hex-encoded public key
+pub fn verify_signature(public_key: &str, message: &[u8], signature: &str) -> Result<bool> {
+    let key = parse_public_key(public_key)?;
+    let sig_bytes = hex::decode(signature).context("Signature is not valid hex")?;
+    let sig_bytes: [u8; 64] = sig_bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Signature must be 64 bytes"))?;
+    let signature = Signature::from_bytes(&sig_bytes);
+    Ok(key.verify(message, &signature).is_ok())
+}
+
+fn parse_public_key(public_key: &str) -> Result<VerifyingKey> {
+    let bytes = hex::decode(public_key).context("Public key is not valid hex")?;
+    let bytes: [u8; 32] = bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Public key must be 32 bytes"))?;
+    VerifyingKey::from_bytes(&bytes).context("Invalid Ed25519 public key")
+}
+
+fn now() -> String {
+    Utc::now().format("%Y-%m-%dT%H:%M:%S%.6fZ").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_and_verify() {
+        let signer = LedgerSigner::generate("k1");
+        let signature = signer.sign(b"block-hash");
+        assert!(verify_signature(&signer.public_key_hex(), b"block-hash", &signature).unwrap());
+        assert!(!verify_signature(&signer.public_key_hex(), b"other", &signature).unwrap());
+    }
+
+    #[test]
+    fn test_secret_roundtrip() {
+        let signer = LedgerSigner::generate("k1");
+        let restored = LedgerSigner::from_secret_hex("k1", &signer.secret_hex()).unwrap();
+        assert_eq!(signer.public_key_hex(), restored.public_key_hex());
+        assert!(LedgerSigner::from_secret_hex("k1", "abcd").is_err());
+    }
+
+    #[test]
+    fn test_rotation_ranges() {
+        let k1 = LedgerSigner::generate("k1");
+        let k2 = LedgerSigner::generate("k2");
+        let mut registry = KeyRegistry::default();
+        registry.register("k1", &k1.public_key_hex(), 0).unwrap();
+        registry.rotate("k2", &k2.public_key_hex(), 5).unwrap();
+
+        assert_eq!(registry.active().unwrap().key_id, "k2");
+        assert_eq!(registry.get("k1").unwrap().status, KeyStatus::Retired);
+
+        let sig1 = k1.sign(b"m");
+        assert!(registry.verify("k1", 4, b"m", &sig1));
+        assert!(!registry.verify("k1", 5, b"m", &sig1));
+
+        let sig2 = k2.sign(b"m");
+        assert!(registry.verify("k2", 5, b"m", &sig2));
+        assert!(!registry.verify("k2", 4, b"m", &sig2));
+        assert!(!registry.verify("unknown", 5, b"m", &sig2));
+    }
+
+    #[test]
+    fn test_revocation() {
+        let k1 = LedgerSigner::generate("k1");
+        let mut registry = KeyRegistry::default();
+        registry.register("k1", &k1.public_key_hex(), 0).unwrap();
+        registry.revoke("k1", Some(3)).unwrap();
+
+        let sig = k1.sign(b"m");
+        assert!(registry.verify("k1", 2, b"m", &sig));
+        assert!(!registry.verify("k1", 3, b"m", &sig));
+
+        // Revoking again later does not re-validate blocks 3 and 4
+        registry.revoke("k1", Some(5)).unwrap();
+        assert_eq!(registry.get("k1").unwrap().revoked_from_index, Some(3));
+        assert!(!registry.verify("k1", 4, b"m", &sig));
+
+        registry.revoke("k1", None).unwrap();
+        assert!(!registry.verify("k1", 0, b"m", &sig));
+    }
+
+    #[test]
+    fn test_successor_may_not_weaken_keys() {
+        let k1 = LedgerSigner::generate("k1");
+        let k2 = LedgerSigner::generate("k2");
+        let mut registry = KeyRegistry::default();
+        registry.register("k1", &k1.public_key_hex(), 0).unwrap();
+        registry.rotate("k2", &k2.public_key_hex(), 5).unwrap();
+        registry.revoke("k1", Some(3)).unwrap();
+
+        // Rotating and revoking further is accepted
+        let mut next = registry.clone();
+        let k3 = LedgerSigner::generate("k3");
+        next.rotate("k3", &k3.public_key_hex(), 9).unwrap();
+        next.revoke("k1", Some(1)).unwrap();
+        assert!(registry.check_successor(&next, 8).is_ok());
+
+        // Un-revoking a key is refused
+        let mut unrevoked = registry.clone();
+        unrevoked.keys[0].status = KeyStatus::Retired;
+        unrevoked.keys[0].revoked_from_index = None;
+        assert!(registry.check_successor(&unrevoked, 8).is_err());
+
+        // Widening a range is refused
+        let mut widened = registry.clone();
+        widened.keys[0].valid_until_index = Some(7);
+        assert!(registry.check_successor(&widened, 8).is_err());
+        let mut widened = registry.clone();
+        widened.keys[1].valid_from_index = 2;
+        assert!(registry.check_successor(&widened, 8).is_err());
+
+        // A new key may not cover blocks that already exist
+        assert!(registry.check_successor(&next, 9).is_err());
+    }
+}
@@ -0,0 +1,390 @@
+//! Fork detection and reconciliation between ledger replicas.
+//!
+//! Two replicas are compared through their block summaries. Because every
+//! block commits to its predecessor via `previous_hash`, matching hashes at
+//! index `i` imply matching prefixes up to `i`, so the common prefix is found
+//! with a binary search. When one replica strictly extends the other, the
+//! missing blocks can be fast-forwarded after checking their linkage.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::lock::LedgerSnapshot;
+use crate::mef_block::{BlockSummary, MEFLedger, MefBlock};
+use crate::signing::KeyRegistry;
+
+/// Read access to a ledger replica
+pub trait BlockSource {
+    /// Ordered summaries of every block in the replica
+    fn summaries(&self) -> Result<Vec<BlockSummary>>;
+
+    /// Full block at `index`
+    fn block(&self, index: i32) -> Result<Option<MefBlock>>;
+
+    /// Signer key registry of the replica
+    fn key_registry(&self) -> Result<KeyRegistry>;
+}
+
+impl BlockSource for MEFLedger {
+    fn summaries(&self) -> Result<Vec<BlockSummary>> {
+        Ok(self.index().blocks.clone())
+    }
+
+    fn block(&self, index: i32) -> Result<Option<MefBlock>> {
+        self.get_block(index)
+    }
+
+    fn key_registry(&self) -> Result<KeyRegistry> {
+        Ok(MEFLedger::key_registry(self).clone())
+    }
+}
+
+impl BlockSource for LedgerSnapshot {
+    fn summaries(&self) -> Result<Vec<BlockSummary>> {
+        Ok(self.index().blocks.clone())
+    }
+
+    fn block(&self, index: i32) -> Result<Option<MefBlock>> {
+        self.get_block(index)
+    }
+
+    fn key_registry(&self) -> Result<KeyRegistry> {
+        Ok(self.index().metadata.key_registry.clone())
+    }
+}
+
+/// How two replicas relate to each other
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LedgerRelation {
+    /// Both replicas hold the same blocks
+    Identical,
+    /// The remote replica strictly extends the local one
+    LocalBehind,
+    /// The local replica strictly extends the remote one
+    LocalAhead,
+    /// The replicas share a prefix and then disagree
+    Diverged,
+}
+
+/// Result of comparing two replicas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkReport {
+    pub relation: LedgerRelation,
+    /// Number of leading blocks both replicas agree on
+    pub common_prefix_len: usize,
+    /// Hash of the last common block (`None` if nothing is shared)
+    pub common_hash: Option<String>,
+    /// First index at which the replicas disagree, if they diverged
+    pub fork_index: Option<i32>,
+    pub local_height: usize,
+    pub remote_height: usize,
+    /// Blocks only present locally after the common prefix
+    pub local_only: Vec<BlockSummary>,
+    /// Blocks only present remotely after the common prefix
+    pub remote_only: Vec<BlockSummary>,
+}
+
+/// Step of a reconciliation plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum SyncAction {
+    /// Nothing to do
+    None,
+    /// Copy remote blocks `from_index..=to_index` onto the local ledger
+    FastForward { from_index: i32, to_index: i32 },
+    /// The remote side is behind and needs the local blocks
+    PushToRemote { from_index: i32, to_index: i32 },
+    /// The replicas forked; an operator has to pick a branch
+    ManualResolution { fork_index: i32 },
+}
+
+/// Plan describing how to reconcile two replicas
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationPlan {
+    pub report: ForkReport,
+    pub action: SyncAction,
+}
+
+impl ReconciliationPlan {
+    /// Whether the plan can be applied to the local ledger automatically
+    pub fn is_fast_forward(&self) -> bool {
+        matches!(self.action, SyncAction::FastForward { .. })
+    }
+}
+
+/// Length of the common prefix of two summary lists
+///
+/// Hash equality at index `i` implies equality of the whole prefix, so the
+/// boundary is located by binary search.
+fn common_prefix_len(local: &[BlockSummary], remote: &[BlockSummary]) -> usize {
+    let (mut lo, mut hi) = (0, local.len().min(remote.len()));
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if local[mid].hash == remote[mid].hash {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo
+}
+
+/// Compare two replicas and report where they diverge
+///
+/// # Arguments
+/// * `local` - Replica treated as local
+/// * `remote` - Replica to compare against
+pub fn compare_ledgers(local: &dyn BlockSource, remote: &dyn BlockSource) -> Result<ForkReport> {
+    let local_blocks = local.summaries()?;
+    let remote_blocks = remote.summaries()?;
+    let common = common_prefix_len(&local_blocks, &remote_blocks);
+
+    let relation = if common == local_blocks.len() && common == remote_blocks.len() {
+        LedgerRelation::Identical
+    } else if common == local_blocks.len() {
+        LedgerRelation::LocalBehind
+    } else if common == remote_blocks.len() {
+        LedgerRelation::LocalAhead
+    } else {
+        LedgerRelation::Diverged
+    };
+
+    Ok(ForkReport {
+        relation,
+        common_prefix_len: common,
+        common_hash: common.checked_sub(1).map(|i| local_blocks[i].hash.clone()),
+        fork_index: (relation == LedgerRelation::Diverged).then_some(common as i32),
+        local_height: local_blocks.len(),
+        remote_height: remote_blocks.len(),
+        local_only: local_blocks[common..].to_vec(),
+        remote_only: remote_blocks[common..].to_vec(),
+    })
+}
+
+/// Build a reconciliation plan for bringing `local` in line with `remote`
+pub fn plan_reconciliation(
+    local: &dyn BlockSource,
+    remote: &dyn BlockSource,
+) -> Result<ReconciliationPlan> {
+    let report = compare_ledgers(local, remote)?;
+    let common = report.common_prefix_len as i32;
+
+    let action = match report.relation {
+        LedgerRelation::Identical => SyncAction::None,
+        LedgerRelation::LocalBehind => SyncAction::FastForward {
+            from_index: common,
+            to_index: report.remote_height as i32 - 1,
+        },
+        LedgerRelation::LocalAhead => SyncAction::PushToRemote {
+            from_index: common,
+            to_index: report.local_height as i32 - 1,
+        },
+        LedgerRelation::Diverged => SyncAction::ManualResolution { fork_index: common },
+    };
+
+    Ok(ReconciliationPlan { report, action })
+}
+
+/// Apply a fast-forward plan, importing the missing remote blocks
+///
+/// The remote key registry is adopted first (it may only extend the local
+/// one), then every imported block is checked for hash, signature and
+/// `previous_hash` linkage before it is written. Returns the number of
+/// imported blocks.
+pub fn apply_fast_forward(
+    local: &mut MEFLedger,
+    remote: &dyn BlockSource,
+    plan: &ReconciliationPlan,
+) -> Result<usize> {
+    let (from_index, to_index) = match plan.action {
+        SyncAction::FastForward {
+            from_index,
+            to_index,
+        } => (from_index, to_index),
+        SyncAction::None => return Ok(0),
+        _ => anyhow::bail!("Reconciliation plan is not a fast-forward"),
+    };
+
+    if local.index().current_index + 1 != from_index {
+        anyhow::bail!("Local ledger changed since the plan was made");
+    }
+
+    let registry = remote.key_registry()?;
+    if &registry != local.key_registry() {
+        local.adopt_key_registry(registry)?;
+    }
+
+    let mut imported = 0;
+    for i in from_index..=to_index {
+        let block = remote
+            .block(i)?
+            .ok_or_else(|| anyhow::anyhow!("Remote block {} is missing", i))?;
+        let expected = &plan.report.remote_only[(i - from_index) as usize];
+        if block.hash != expected.hash {
+            anyhow::bail!("Remote block {} changed since the plan was made", i);
+        }
+        local.import_block(block)?;
+        imported += 1;
+    }
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn tic(i: usize, branch: &str) -> serde_json::Value {
+        json!({
+            "tic_id": format!("{}-tic-{}", branch, i),
+            "seed": "SEED",
+            "fixpoint": [0.1, 0.2, 0.3],
+            "invariants": {},
+            "sigma_bar": {},
+            "window": [],
+            "proof": null
+        })
+    }
+
+    fn append(ledger: &mut MEFLedger, range: std::ops::Range<usize>, branch: &str) {
+        for i in range {
+            ledger
+                .append_block(&tic(i, branch), &json!({"id": i}))
+                .unwrap();
+        }
+    }
+
+    /// Copy the first `n` blocks of `source` into `target`
+    fn replicate(source: &MEFLedger, target: &mut MEFLedger, n: i32) {
+        for i in 0..n {
+            target
+                .import_block(source.get_block(i).unwrap().unwrap())
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_identical_ledgers() {
+        let a_dir = tempfile::tempdir().unwrap();
+        let b_dir = tempfile::tempdir().unwrap();
+        let mut a = MEFLedger::new(a_dir.path()).unwrap();
+        let mut b = MEFLedger::new(b_dir.path()).unwrap();
+        append(&mut a, 0..3, "main");
+        replicate(&a, &mut b, 3);
+
+        let plan = plan_reconciliation(&a, &b).unwrap();
+        assert_eq!(plan.report.relation, LedgerRelation::Identical);
+        assert_eq!(plan.report.common_prefix_len, 3);
+        assert!(matches!(plan.action, SyncAction::None));
+    }
+
+    #[test]
+    fn test_fast_forward_between_directories() {
+        let a_dir = tempfile::tempdir().unwrap();
+        let b_dir = tempfile::tempdir().unwrap();
+        let mut remote = MEFLedger::new(a_dir.path()).unwrap();
+        let mut local = MEFLedger::new(b_dir.path()).unwrap();
+        append(&mut remote, 0..5, "main");
+        replicate(&remote, &mut local, 2);
+
+        let plan = plan_reconciliation(&local, &remote).unwrap();
+        assert_eq!(plan.report.relation, LedgerRelation::LocalBehind);
+        assert!(plan.is_fast_forward());
+
+        let imported = apply_fast_forward(&mut local, &remote, &plan).unwrap();
+        assert_eq!(imported, 3);
+        assert!(local.verify_chain_integrity(0).unwrap());
+        assert_eq!(
+            local.get_last_hash().unwrap(),
+            remote.get_last_hash().unwrap()
+        );
+
+        // Reopened from disk the replicas are identical
+        let local = MEFLedger::new(b_dir.path()).unwrap();
+        let report = compare_ledgers(&local, &remote).unwrap();
+        assert_eq!(report.relation, LedgerRelation::Identical);
+    }
+
+    #[test]
+    fn test_fast_forward_adopts_signer_keys() {
+        let a_dir = tempfile::tempdir().unwrap();
+        let b_dir = tempfile::tempdir().unwrap();
+        let config = crate::LedgerConfig {
+            signer: Some(crate::LedgerSigner::generate("k1")),
+            ..crate::LedgerConfig::default()
+        };
+        let mut remote = MEFLedger::with_config(a_dir.path(), config).unwrap();
+        let mut local = MEFLedger::new(b_dir.path()).unwrap();
+        append(&mut remote, 0..3, "main");
+
+        // Signed blocks are rejected without the signer key
+        let block = remote.get_block(0).unwrap().unwrap();
+        assert!(local.import_block(block).is_err());
+
+        let plan = plan_reconciliation(&local, &remote).unwrap();
+        assert_eq!(apply_fast_forward(&mut local, &remote, &plan).unwrap(), 3);
+        assert!(local.key_registry().get("k1").is_some());
+        assert!(local.verify_chain_integrity(0).unwrap());
+
+        // A registry that swaps a known key is refused
+        let mut forged = local.key_registry().clone();
+        forged.keys[0].public_key = crate::LedgerSigner::generate("k1").public_key_hex();
+        assert!(local.adopt_key_registry(forged).is_err());
+
+        // So is one that revives a revoked key
+        let mut revived = local.key_registry().clone();
+        local.revoke_signer_key("k1", Some(2)).unwrap();
+        revived.keys[0].status = crate::KeyStatus::Active;
+        assert!(local.adopt_key_registry(revived).is_err());
+        assert!(!local.verify_chain_integrity(0).unwrap());
+    }
+
+    #[test]
+    fn test_diverged_ledgers() {
+        let a_dir = tempfile::tempdir().unwrap();
+        let b_dir = tempfile::tempdir().unwrap();
+        let mut a = MEFLedger::new(a_dir.path()).unwrap();
+        let mut b = MEFLedger::new(b_dir.path()).unwrap();
+        append(&mut a, 0..3, "main");
+        replicate(&a, &mut b, 3);
+        append(&mut a, 3..5, "left");
+        append(&mut b, 3..4, "right");
+
+        let plan = plan_reconciliation(&a, &b).unwrap();
+        assert_eq!(plan.report.relation, LedgerRelation::Diverged);
+        assert_eq!(plan.report.fork_index, Some(3));
+        assert_eq!(
+            plan.report.common_hash,
+            Some(a.get_block(2).unwrap().unwrap().hash)
+        );
+        assert_eq!(plan.report.local_only.len(), 2);
+        assert_eq!(plan.report.remote_only.len(), 1);
+        assert!(matches!(
+            plan.action,
+            SyncAction::ManualResolution { fork_index: 3 }
+        ));
+        assert!(apply_fast_forward(&mut a, &b, &plan).is_err());
+    }
+
+    #[test]
+    fn test_local_ahead() {
+        let a_dir = tempfile::tempdir().unwrap();
+        let b_dir = tempfile::tempdir().unwrap();
+        let mut a = MEFLedger::new(a_dir.path()).unwrap();
+        let mut b = MEFLedger::new(b_dir.path()).unwrap();
+        append(&mut a, 0..4, "main");
+        replicate(&a, &mut b, 1);
+
+        let plan = plan_reconciliation(&a, &b).unwrap();
+        assert_eq!(plan.report.relation, LedgerRelation::LocalAhead);
+        assert!(matches!(
+            plan.action,
+            SyncAction::PushToRemote {
+                from_index: 1,
+                to_index: 3
+            }
+        ));
+    }
+}
@@ -0,0 +1,707 @@
+//! Parallel, streaming chain verification.
+//!
+//! [`MEFLedger::verify_chain_parallel`] splits the index into batches that
+//! worker threads read and re-hash independently; a final pass then walks the
+//! per-block results in order to check the `previous_hash` links, checkpoint
+//! commitments and pruned-block coverage. Checkpoint roots are tracked with
+//! [`CompactRange`]s, so memory use is bounded by the batch size rather than
+//! the ledger length. Unlike [`MEFLedger::verify_chain_integrity`] the engine
+//! does not stop at the first problem but reports every failing block.
+//! [`LedgerSnapshot::verify_chain_parallel`] runs the same engine over the
+//! segment files pinned by a snapshot, without holding any ledger lock.
+//!
+//! Each verified checkpoint yields a [`ResumePoint`], and a later run started
+//! from it only reads the blocks after the checkpoint. A resume point is
+//! checked against the roots committed in its checkpoint block, so it may be
+//! stored anywhere, but the blocks it covers are trusted rather than re-read:
+//! it should come from an earlier run that verified them.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+use crate::checkpoint::{self, Checkpoint};
+use crate::hashing::HashScheme;
+use crate::lock::LedgerSnapshot;
+use crate::mef_block::{BlockSummary, LedgerIndex, MEFLedger, MefBlock};
+use crate::merkle::CompactRange;
+use crate::segment::SegmentCursor;
+use crate::signing::KeyRegistry;
+
+/// Default number of blocks handed to a worker at a time
+pub const DEFAULT_BATCH_SIZE: usize = 1024;
+
+/// Why a block failed verification
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureKind {
+    /// The segment file or record listed in the index does not exist
+    MissingBlock,
+    /// The block record is corrupt or cannot be decoded
+    UnreadableBlock,
+    /// The record does not hold the block its index entry describes
+    IndexMismatch,
+    /// The block records a hash scheme version this build does not know
+    UnsupportedHashScheme,
+    /// The stored hash differs from the recomputed one
+    HashMismatch,
+    /// The signature does not verify under the key registry
+    InvalidSignature,
+    /// `previous_hash` differs from the hash of the preceding block
+    BrokenLink,
+    /// The checkpoint roots do not match the blocks they cover
+    InvalidCheckpoint,
+    /// A pruned block is not covered by a later valid checkpoint
+    UncoveredPrunedBlock,
+}
+
+/// One problem found with one block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockFailure {
+    pub index: i32,
+    pub kind: FailureKind,
+    pub detail: String,
+}
+
+/// Progress reported after every batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationProgress {
+    /// Blocks verified so far
+    pub checked: u64,
+    /// Blocks to verify in this run
+    pub total: u64,
+    /// Failures found so far
+    pub failures: usize,
+}
+
+/// State needed to continue verification after a verified checkpoint
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumePoint {
+    /// Index of the checkpoint block
+    pub checkpoint_index: i32,
+    /// Compact range over the block hashes the checkpoint covers
+    pub hashes: CompactRange,
+    /// Compact range over the header digests the checkpoint covers
+    pub headers: CompactRange,
+}
+
+/// Outcome of a verification run
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// First block verified
+    pub start_index: i32,
+    /// Last block verified
+    pub end_index: i32,
+    pub blocks_checked: u64,
+    /// Failures ordered by block index
+    pub failures: Vec<BlockFailure>,
+    /// Latest checkpoint verified with no failure before it
+    pub resume_point: Option<ResumePoint>,
+}
+
+impl VerificationReport {
+    /// Whether every block passed
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Options for [`MEFLedger::verify_chain_parallel`]
+#[derive(Debug, Clone)]
+pub struct VerifyOptions {
+    /// Worker threads; `0` uses the available parallelism
+    pub threads: usize,
+    /// Blocks handed to a worker at a time
+    pub batch_size: usize,
+    /// Trusted signer keys; defaults to the ledger's own registry
+    pub registry: Option<KeyRegistry>,
+    /// Continue after a checkpoint verified by an earlier run
+    pub resume_from: Option<ResumePoint>,
+}
+
+impl Default for VerifyOptions {
+    fn default() -> Self {
+        Self {
+            threads: 0,
+            batch_size: DEFAULT_BATCH_SIZE,
+            registry: None,
+            resume_from: None,
+        }
+    }
+}
+
+/// Fields of a readable block needed by the final pass
+struct CheckedBlock {
+    hash: String,
+    previous_hash: String,
+    header_digest: String,
+    checkpoint: Option<Checkpoint>,
+    pruned: bool,
+}
+
+/// Result of the per-block checks done by a worker
+struct BlockCheck {
+    block: Option<CheckedBlock>,
+    failures: Vec<(FailureKind, String)>,
+}
+
+impl BlockCheck {
+    fn unreadable(kind: FailureKind, detail: String) -> Self {
+        Self {
+            block: None,
+            failures: vec![(kind, detail)],
+        }
+    }
+}
+
+/// Reads the blocks listed in an index, one reader per worker
+trait BlockReader {
+    /// Read the block a summary points to; `Ok(None)` if it does not exist
+    fn read_block(&mut self, summary: &BlockSummary) -> Result<Option<MefBlock>>;
+}
+
+impl BlockReader for SegmentCursor<'_> {
+    fn read_block(&mut self, summary: &BlockSummary) -> Result<Option<MefBlock>> {
+        self.read(summary.location())
+    }
+}
+
+/// Reader over the segment files pinned by a snapshot
+struct SnapshotReader<'a>(&'a LedgerSnapshot);
+
+impl BlockReader for SnapshotReader<'_> {
+    fn read_block(&mut self, summary: &BlockSummary) -> Result<Option<MefBlock>> {
+        self.0.read(summary).map(Some)
+    }
+}
+
+/// Read one block and check everything that does not depend on other blocks
+fn check_block(
+    reader: &mut impl BlockReader,
+    summary: &BlockSummary,
+    registry: &KeyRegistry,
+) -> BlockCheck {
+    let block = match reader.read_block(summary) {
+        Ok(Some(block)) => block,
+        Ok(None) => {
+            return BlockCheck::unreadable(
+                FailureKind::MissingBlock,
+                format!("No record at offset {} of {}", summary.offset, summary.file),
+            )
+        }
+        Err(e) => return BlockCheck::unreadable(FailureKind::UnreadableBlock, format!("{:#}", e)),
+    };
+
+    let mut failures = Vec::new();
+    if block.index != summary.index || block.hash != summary.hash {
+        failures.push((
+            FailureKind::IndexMismatch,
+            format!(
+                "Record holds block {} with hash {}",
+                block.index, block.hash
+            ),
+        ));
+    }
+    match block.hash_scheme.map(HashScheme::from_version).transpose() {
+        Err(e) => failures.push((FailureKind::UnsupportedHashScheme, e.to_string())),
+        // Pruned blocks are vouched for by a checkpoint, which does not
+        // cover a payload
+        Ok(_) if block.pruned => {
+            if !checkpoint::is_pruned_form(&block) {
+                failures.push((
+                    FailureKind::HashMismatch,
+                    "Pruned block still carries payload data".to_string(),
+                ));
+            }
+        }
+        Ok(_) => {
+            let computed = serde_json::to_value(&block)
+                .map(|json| MEFLedger::compute_block_hash(&json))
+                .unwrap_or_default();
+            if computed != block.hash {
+                failures.push((
+                    FailureKind::HashMismatch,
+                    format!("Stored {} but computed {}", block.hash, computed),
+                ));
+            }
+        }
+    }
+    if !MEFLedger::check_block_signature(registry, &block) {
+        failures.push((
+            FailureKind::InvalidSignature,
+            format!("Signature by {:?} does not verify", block.signer),
+        ));
+    }
+
+    BlockCheck {
+        block: Some(CheckedBlock {
+            header_digest: checkpoint::header_digest(&block),
+            hash: block.hash,
+            previous_hash: block.previous_hash,
+            checkpoint: block.checkpoint,
+            pruned: block.pruned,
+        }),
+        failures,
+    }
+}
+
+/// Ranges and last block hash at a size some checkpoint commits to
+struct Commitment {
+    hashes: CompactRange,
+    headers: CompactRange,
+    last_block_hash: String,
+}
+
+/// Sequential pass over the per-block results
+struct ChainWalk {
+    previous_hash: String,
+    hashes: CompactRange,
+    headers: CompactRange,
+    /// Tree sizes covered by checkpoints in the index
+    needed: HashSet<u64>,
+    commitments: HashMap<u64, Commitment>,
+    uncovered_pruned: Vec<i32>,
+    failures: Vec<BlockFailure>,
+    resume_point: Option<ResumePoint>,
+}
+
+impl ChainWalk {
+    fn fail(&mut self, index: i32, kind: FailureKind, detail: String) {
+        self.failures.push(BlockFailure {
+            index,
+            kind,
+            detail,
+        });
+    }
+
+    fn record_commitment(&mut self) {
+        let size = self.hashes.len();
+        if self.needed.contains(&size) {
+            self.commitments.insert(
+                size,
+                Commitment {
+                    hashes: self.hashes.clone(),
+                    headers: self.headers.clone(),
+                    last_block_hash: self.previous_hash.clone(),
+                },
+            );
+        }
+    }
+
+    fn visit(&mut self, summary: &BlockSummary, check: BlockCheck) {
+        let index = summary.index;
+        for (kind, detail) in check.failures {
+            self.fail(index, kind, detail);
+        }
+
+        let (hash, header_digest) = match check.block {
+            Some(block) => {
+                if block.previous_hash != self.previous_hash {
+                    self.fail(
+                        index,
+                        FailureKind::BrokenLink,
+                        format!(
+                            "previous_hash {} does not match preceding hash {}",
+                            block.previous_hash, self.previous_hash
+                        ),
+                    );
+                }
+                if block.pruned {
+                    self.uncovered_pruned.push(index);
+                }
+                if let Some(checkpoint) = &block.checkpoint {
+                    self.check_checkpoint(index, checkpoint);
+                }
+                (block.hash, block.header_digest)
+            }
+            // Keep linking against the indexed hash; no later checkpoint can
+            // match without the header of the missing block
+            None => (summary.hash.clone(), String::new()),
+        };
+
+        self.hashes.push(&hash);
+        self.headers.push(&header_digest);
+        self.previous_hash = hash;
+        self.record_commitment();
+    }
+
+    fn check_checkpoint(&mut self, index: i32, checkpoint: &Checkpoint) {
+        let up_to = checkpoint.up_to_index;
+        let commitment = if up_to < 0 || up_to >= index {
+            None
+        } else {
+            self.commitments.get(&checkpoint.tree_size())
+        };
+        let Some(commitment) = commitment else {
+            self.fail(
+                index,
+                FailureKind::InvalidCheckpoint,
+                format!("Checkpoint covering block {} cannot be checked", up_to),
+            );
+            return;
+        };
+
+        if commitment.hashes.root() != checkpoint.merkle_root
+            || commitment.headers.root() != checkpoint.header_root
+            || commitment.last_block_hash != checkpoint.last_block_hash
+        {
+            self.fail(
+                index,
+                FailureKind::InvalidCheckpoint,
+                format!("Roots do not match blocks 0..={}", up_to),
+            );
+            return;
+        }
+
+        self.uncovered_pruned.retain(|&p| p > up_to);
+        if self.failures.is_empty() {
+            self.resume_point = Some(ResumePoint {
+                checkpoint_index: index,
+                hashes: commitment.hashes.clone(),
+                headers: commitment.headers.clone(),
+            });
+        }
+    }
+}
+
+impl MEFLedger {
+    /// Verify the chain with parallel re-hashing and a structured report
+    ///
+    /// # Arguments
+    /// * `options` - Parallelism, trusted keys and resume point
+    /// * `on_progress` - Called after every batch of blocks
+    pub fn verify_chain_parallel(
+        &self,
+        options: &VerifyOptions,
+        on_progress: impl FnMut(&VerificationProgress),
+    ) -> Result<VerificationReport> {
+        let segments = self.segments();
+        verify_chain(
+            self.index(),
+            self.genesis_hash(),
+            options,
+            || segments.cursor(),
+            on_progress,
+        )
+    }
+}
+
+impl LedgerSnapshot {
+    /// Verify the chain exactly as captured by the snapshot
+    ///
+    /// Works like [`MEFLedger::verify_chain_parallel`] but reads the segment
+    /// files pinned when the snapshot was taken, so it needs no ledger lock
+    /// and writers may keep appending while it runs.
+    ///
+    /// # Arguments
+    /// * `options` - Parallelism, trusted keys and resume point
+    /// * `on_progress` - Called after every batch of blocks
+    pub fn verify_chain_parallel(
+        &self,
+        options: &VerifyOptions,
+        on_progress: impl FnMut(&VerificationProgress),
+    ) -> Result<VerificationReport> {
+        verify_chain(
+            self.index(),
+            &"0".repeat(64),
+            options,
+            || SnapshotReader(self),
+            on_progress,
+        )
+    }
+}
+
+/// Verification engine shared by ledgers and snapshots
+///
+/// # Arguments
+/// * `index` - Index listing the blocks to verify
+/// * `genesis_hash` - Previous hash of the first block
+/// * `options` - Parallelism, trusted keys and resume point
+/// * `new_reader` - Opens a block reader for one worker
+/// * `on_progress` - Called after every batch of blocks
+fn verify_chain<R: BlockReader>(
+    index: &LedgerIndex,
+    genesis_hash: &str,
+    options: &VerifyOptions,
+    new_reader: impl Fn() -> R + Sync,
+    mut on_progress: impl FnMut(&VerificationProgress),
+) -> Result<VerificationReport> {
+    let registry = options
+        .registry
+        .as_ref()
+        .unwrap_or(&index.metadata.key_registry);
+    let blocks = &index.blocks;
+
+    let mut walk = ChainWalk {
+        previous_hash: genesis_hash.to_string(),
+        hashes: CompactRange::new(),
+        headers: CompactRange::new(),
+        needed: blocks
+            .iter()
+            .filter_map(|b| b.checkpoint)
+            .map(|up_to| up_to as u64 + 1)
+            .collect(),
+        commitments: HashMap::new(),
+        uncovered_pruned: Vec::new(),
+        failures: Vec::new(),
+        resume_point: None,
+    };
+
+    let mut start_index = 0;
+    if let Some(point) = &options.resume_from {
+        let missing = || anyhow::anyhow!("Missing block at index {}", point.checkpoint_index);
+        let summary = usize::try_from(point.checkpoint_index)
+            .ok()
+            .and_then(|i| blocks.get(i))
+            .ok_or_else(missing)?;
+        let block = new_reader().read_block(summary)?.ok_or_else(missing)?;
+        if block.index != summary.index || block.hash != summary.hash {
+            anyhow::bail!(
+                "Checkpoint block {} does not match the index",
+                summary.index
+            );
+        }
+        let checkpoint = block.checkpoint.as_ref().ok_or_else(|| {
+            anyhow::anyhow!("Block {} is not a checkpoint", point.checkpoint_index)
+        })?;
+        let computed = MEFLedger::compute_block_hash(&serde_json::to_value(&block)?);
+        if computed != block.hash || !MEFLedger::check_block_signature(registry, &block) {
+            anyhow::bail!("Checkpoint block {} failed verification", block.index);
+        }
+        if point.hashes.len() != checkpoint.tree_size()
+            || point.headers.len() != checkpoint.tree_size()
+            || point.hashes.root() != checkpoint.merkle_root
+            || point.headers.root() != checkpoint.header_root
+        {
+            anyhow::bail!("Resume point does not match checkpoint {}", block.index);
+        }
+
+        start_index = checkpoint.up_to_index + 1;
+        walk.previous_hash = checkpoint.last_block_hash.clone();
+        walk.hashes = point.hashes.clone();
+        walk.headers = point.headers.clone();
+        walk.record_commitment();
+    }
+
+    let pending = &blocks[(start_index as usize).min(blocks.len())..];
+    let threads = match options.threads {
+        0 => std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1),
+        n => n,
+    };
+    let batch_size = options.batch_size.max(1);
+    let new_reader = &new_reader;
+
+    let mut progress = VerificationProgress {
+        checked: 0,
+        total: pending.len() as u64,
+        failures: 0,
+    };
+    for wave in pending.chunks(batch_size * threads) {
+        let checks: Vec<Vec<BlockCheck>> = std::thread::scope(|scope| {
+            let workers: Vec<_> = wave
+                .chunks(batch_size)
+                .map(|batch| {
+                    scope.spawn(move || {
+                        let mut reader = new_reader();
+                        batch
+                            .iter()
+                            .map(|summary| check_block(&mut reader, summary, registry))
+                            .collect()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("verification worker panicked"))
+                .collect()
+        });
+
+        for (summary, check) in wave.iter().zip(checks.into_iter().flatten()) {
+            walk.visit(summary, check);
+        }
+        progress.checked += wave.len() as u64;
+        progress.failures = walk.failures.len();
+        on_progress(&progress);
+    }
+
+    for index in std::mem::take(&mut walk.uncovered_pruned) {
+        walk.fail(
+            index,
+            FailureKind::UncoveredPrunedBlock,
+            "Pruned block is not covered by a later checkpoint".to_string(),
+        );
+    }
+    walk.failures.sort_by_key(|f| f.index);
+
+    Ok(VerificationReport {
+        start_index,
+        end_index: index.current_index,
+        blocks_checked: progress.checked,
+        failures: walk.failures,
+        resume_point: walk.resume_point,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::segment::SegmentStore;
+    use crate::LedgerConfig;
+    use serde_json::json;
+
+    fn tic(i: usize) -> serde_json::Value {
+        json!({
+            "tic_id": format!("tic-{}", i),
+            "seed": "SEED",
+            "fixpoint": [0.1, 0.2, 0.3],
+            "invariants": {"variance": 0.1},
+            "sigma_bar": {"psi": 0.5},
+            "window": ["w"],
+            "proof": null
+        })
+    }
+
+    fn small_batches() -> VerifyOptions {
+        VerifyOptions {
+            threads: 3,
+            batch_size: 2,
+            ..VerifyOptions::default()
+        }
+    }
+
+    #[test]
+    fn test_clean_ledger_with_pruning() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        for i in 0..9 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+        ledger.create_checkpoint().unwrap();
+        for i in 9..12 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+        ledger.prune_payloads(5, false).unwrap();
+
+        let mut updates = Vec::new();
+        let report = ledger
+            .verify_chain_parallel(&small_batches(), |p| updates.push(*p))
+            .unwrap();
+        assert!(report.is_ok(), "{:?}", report.failures);
+        assert_eq!(report.blocks_checked, 13);
+        assert_eq!(report.resume_point.unwrap().checkpoint_index, 9);
+        assert_eq!(updates.len(), 3);
+        assert_eq!(updates.last().unwrap().checked, 13);
+    }
+
+    #[test]
+    fn test_reports_every_failure() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        // One block per segment, so blocks can be rewritten independently
+        let config = LedgerConfig {
+            max_segment_bytes: 1,
+            ..LedgerConfig::default()
+        };
+        let mut ledger = MEFLedger::with_config(temp_dir.path(), config).unwrap();
+        for i in 0..6 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+
+        let mut store = SegmentStore::open(temp_dir.path().join("segments"), 1).unwrap();
+        let segment = |i: usize| ledger.index().blocks[i].segment;
+        let mut tamper = |i: usize, edit: &dyn Fn(&mut crate::MefBlock)| {
+            let mut block = ledger.get_block(i as i32).unwrap().unwrap();
+            edit(&mut block);
+            store.rewrite_segment(segment(i), &[block]).unwrap();
+        };
+        tamper(1, &|b| b.data.seed = "FORGED".to_string());
+        tamper(3, &|b| b.previous_hash = "f".repeat(64));
+        std::fs::remove_file(
+            temp_dir
+                .path()
+                .join("segments")
+                .join(SegmentStore::segment_file_name(segment(5))),
+        )
+        .unwrap();
+
+        let report = ledger
+            .verify_chain_parallel(&small_batches(), |_| {})
+            .unwrap();
+        let found: Vec<(i32, FailureKind)> =
+            report.failures.iter().map(|f| (f.index, f.kind)).collect();
+        assert_eq!(
+            found,
+            vec![
+                (1, FailureKind::HashMismatch),
+                (3, FailureKind::HashMismatch),
+                (3, FailureKind::BrokenLink),
+                (5, FailureKind::MissingBlock),
+            ]
+        );
+        assert!(report.resume_point.is_none());
+    }
+
+    #[test]
+    fn test_resume_from_checkpoint() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        for i in 0..4 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+        ledger.create_checkpoint().unwrap();
+        let first = ledger
+            .verify_chain_parallel(&VerifyOptions::default(), |_| {})
+            .unwrap();
+        let point = first.resume_point.unwrap();
+
+        for i in 4..7 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+        ledger.create_checkpoint().unwrap();
+        ledger.append_block(&tic(7), &json!({"id": 7})).unwrap();
+        ledger.prune_payloads(6, false).unwrap();
+
+        let options = VerifyOptions {
+            resume_from: Some(point.clone()),
+            ..small_batches()
+        };
+        let resumed = ledger.verify_chain_parallel(&options, |_| {}).unwrap();
+        assert!(resumed.is_ok(), "{:?}", resumed.failures);
+        assert_eq!(resumed.start_index, 4);
+        assert_eq!(resumed.blocks_checked, 6);
+        assert_eq!(resumed.resume_point.unwrap().checkpoint_index, 8);
+
+        // A resume point that does not match the checkpoint is refused
+        let mut forged = point;
+        forged.hashes.push(&"0".repeat(64));
+        let options = VerifyOptions {
+            resume_from: Some(forged),
+            ..VerifyOptions::default()
+        };
+        assert!(ledger.verify_chain_parallel(&options, |_| {}).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_verifies_pinned_state() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut ledger = MEFLedger::new(temp_dir.path()).unwrap();
+        for i in 0..5 {
+            ledger.append_block(&tic(i), &json!({"id": i})).unwrap();
+        }
+        ledger.create_checkpoint().unwrap();
+        ledger.prune_payloads(2, false).unwrap();
+
+        let snapshot = LedgerSnapshot::open(temp_dir.path()).unwrap();
+        // Later writes do not change what the snapshot verifies
+        ledger.append_block(&tic(5), &json!({"id": 5})).unwrap();
+        ledger.prune_payloads(4, false).unwrap();
+
+        let report = snapshot
+            .verify_chain_parallel(&small_batches(), |_| {})
+            .unwrap();
+        assert!(report.is_ok(), "{:?}", report.failures);
+        assert_eq!(report.blocks_checked, 6);
+        assert_eq!(report.end_index, 5);
+    }
+}
@@ -7,11 +7,14 @@ use chrono::Utc;
 use log::debug;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant};
 
+use crate::proof_registry::{CollectionState as ProofCollectionState, ProofRegistry};
 use crate::providers::{get_provider, get_providers, IndexProvider};
 
 /// Default vector database path
@@ -115,6 +118,14 @@ impl CollectionState {
         }
     }
 
+    /// Version the membership proofs of this collection are keyed on
+    pub fn proof_version(&self) -> i64 {
+        self.indexes
+            .get("proof_version")
+            .and_then(|v| v.as_i64())
+            .unwrap_or(0)
+    }
+
     pub fn to_dict(&self) -> HashMap<String, Value> {
         let mut dict = HashMap::new();
         dict.insert(
@@ -149,18 +160,63 @@ impl Default for CollectionState {
     }
 }
 
+/// Receives notifications about collection changes, e.g. to export metrics
+pub trait IndexObserver: Send + Sync {
+    /// Called after vectors were upserted into or deleted from a collection
+    fn collection_changed(&self, collection: &str, vectors: usize);
+
+    /// Called after the index of a collection was rebuilt
+    fn index_built(&self, collection: &str, vectors: usize, duration: std::time::Duration);
+}
+
+/// Provider instance shared between concurrent searches of a collection
+type SharedProvider = Arc<Mutex<Box<dyn IndexProvider>>>;
+
+/// Provider build detached from the [`IndexManager`]
+///
+/// Created from a snapshot of a collection by [`IndexManager::prepare_build`],
+/// run without access to the manager and swapped in by
+/// [`IndexManager::install_build`]. Searches keep using the previous provider
+/// until the new one is installed.
+pub struct IndexBuild {
+    collection: String,
+    provider_name: String,
+    vectors: HashMap<String, HashMap<String, Value>>,
+    provider: Box<dyn IndexProvider>,
+    duration: Duration,
+}
+
+impl IndexBuild {
+    /// Collection being indexed
+    pub fn collection(&self) -> &str {
+        &self.collection
+    }
+
+    /// Build the provider from the snapshot
+    pub fn run(&mut self) {
+        let start = Instant::now();
+        self.provider.build(&self.vectors);
+        self.duration = start.elapsed();
+    }
+}
+
 /// Manage persisted vector sets and related index metadata
+///
+/// Searches only need `&self`, so the manager can be shared behind a
+/// read-write lock; provider instances are locked individually while a query
+/// runs.
 pub struct IndexManager {
     pub base_path: PathBuf,
     pub collections: HashMap<String, CollectionState>,
     pub collection_providers: HashMap<String, String>,
-    provider_instances: HashMap<String, Box<dyn IndexProvider>>,
+    provider_instances: RwLock<HashMap<String, SharedProvider>>,
     #[allow(dead_code)]
     ephemeral_provider_cache: HashMap<String, Box<dyn IndexProvider>>,
     #[allow(dead_code)]
     ephemeral_cache_limit: usize,
-    last_search_plan: HashMap<String, Value>,
+    last_search_plan: Mutex<HashMap<String, Value>>,
     index_status: HashMap<String, HashMap<String, Value>>,
+    observer: Option<Arc<dyn IndexObserver>>,
 }
 
 // Volatile key names for metadata canonicalization
@@ -184,6 +240,9 @@ const VOLATILE_KEY_NAMES: &[&str] = &[
 
 const VOLATILE_KEY_SUFFIXES: &[&str] = &["_ts", "_timestamp"];
 
+/// Index statuses written by [`IndexManager::flush`], relative to the base path
+const MANIFEST_PATH: &str = "manifest/indexes.json";
+
 impl IndexManager {
     /// Create a new IndexManager
     pub fn new(base_path: Option<PathBuf>) -> Result<Self> {
@@ -199,17 +258,28 @@ impl IndexManager {
             base_path,
             collections: HashMap::new(),
             collection_providers: HashMap::new(),
-            provider_instances: HashMap::new(),
+            provider_instances: RwLock::new(HashMap::new()),
             ephemeral_provider_cache: HashMap::new(),
             ephemeral_cache_limit,
-            last_search_plan: HashMap::new(),
+            last_search_plan: Mutex::new(HashMap::new()),
             index_status: HashMap::new(),
+            observer: None,
         };
 
         manager.load_existing_state()?;
+        manager.load_manifest();
         Ok(manager)
     }
 
+    /// Notify `observer` about collection changes, starting with the
+    /// current size of every loaded collection
+    pub fn set_observer(&mut self, observer: Arc<dyn IndexObserver>) {
+        for (name, state) in &self.collections {
+            observer.collection_changed(name, state.vectors.len());
+        }
+        self.observer = Some(observer);
+    }
+
     /// Insert or update vector records for a collection
     pub fn upsert_vectors(
         &mut self,
@@ -305,12 +375,16 @@ impl IndexManager {
         self.index_status.remove(collection);
 
         // Update provider after persisting
-        if let Ok(provider) = self.ensure_provider(collection) {
-            for (id, payload) in &updates {
-                provider.upsert(id, payload);
-            }
+        let provider = self.ensure_provider(collection);
+        let mut provider = lock(&provider);
+        for (id, payload) in &updates {
+            provider.upsert(id, payload);
         }
+        drop(provider);
 
+        if let Some(observer) = &self.observer {
+            observer.collection_changed(collection, result.vectors.len());
+        }
         Ok(result)
     }
 
@@ -356,10 +430,15 @@ impl IndexManager {
             self.persist_collection(&collection_str, &result)?;
 
             // Update provider after persisting
-            if let Ok(provider) = self.ensure_provider(collection) {
-                for vector_id in vector_ids {
-                    provider.delete(vector_id);
-                }
+            let provider = self.ensure_provider(collection);
+            let mut provider = lock(&provider);
+            for vector_id in vector_ids {
+                provider.delete(vector_id);
+            }
+            drop(provider);
+
+            if let Some(observer) = &self.observer {
+                observer.collection_changed(collection, result.vectors.len());
             }
 
             return Ok(result);
@@ -376,9 +455,35 @@ impl IndexManager {
             .clone()
     }
 
+    /// Rebuild membership proofs in `registry` for collections whose
+    /// `proof_version` changed since its last refresh
+    ///
+    /// Versions are compared before anything is cloned, so only the vectors
+    /// of stale collections are copied out from under the index lock.
+    pub fn refresh_proofs(&self, registry: &ProofRegistry) {
+        let known = registry.collection_versions();
+        let active: HashSet<String> = self.collections.keys().cloned().collect();
+        let stale: HashMap<String, ProofCollectionState> = self
+            .collections
+            .iter()
+            .filter(|(name, state)| known.get(*name) != Some(&state.proof_version()))
+            .map(|(name, state)| {
+                let state = ProofCollectionState {
+                    vectors: state.vectors.clone(),
+                    indexes: state.indexes.clone(),
+                };
+                (name.clone(), state)
+            })
+            .collect();
+        if stale.is_empty() && known.len() == active.len() {
+            return;
+        }
+        registry.refresh_collections(&active, &stale);
+    }
+
     /// Run a similarity search without mutating collection state
     pub fn search_vectors(
-        &mut self,
+        &self,
         collection: &str,
         query: &[f64],
         top_k: usize,
@@ -386,18 +491,18 @@ impl IndexManager {
         mode: Option<&str>,
         ef_search: Option<i64>,
     ) -> Result<Vec<HashMap<String, Value>>> {
-        let state = self.collections.get(collection);
-        if state.is_none() || state.unwrap().vectors.is_empty() {
-            debug!(
-                "search requested for empty collection; collection={} has_state={}",
-                collection,
-                state.is_some()
-            );
-            return Ok(Vec::new());
-        }
+        let state = match self.collections.get(collection) {
+            Some(state) if !state.vectors.is_empty() => state,
+            state => {
+                debug!(
+                    "search requested for empty collection; collection={} has_state={}",
+                    collection,
+                    state.is_some()
+                );
+                return Ok(Vec::new());
+            }
+        };
 
-        // Clone state to avoid borrow issues
-        let state = state.unwrap().clone();
         let _use_exact = mode.map(|m| m.to_lowercase() == "exact").unwrap_or(false);
 
         // Exact search implementation would go here (omitted for brevity)
@@ -411,27 +516,23 @@ impl IndexManager {
             extra_params.insert("ef_search".to_string(), Value::from(ef));
         }
 
-        let start = std::time::Instant::now();
+        let start = Instant::now();
 
-        let results = if use_ephemeral {
+        let (results, plan) = if use_ephemeral {
             let mut provider = self.get_ephemeral_provider(collection, &provider_name_str)?;
-            provider.search(query, &state.vectors, top_k, &extra_params)
+            let results = provider.search(query, &state.vectors, top_k, &extra_params);
+            // Ephemeral provider doesn't persist plan
+            (results, HashMap::new())
         } else {
-            let provider = self.ensure_provider(collection)?;
-            provider.search(query, &state.vectors, top_k, &extra_params)
+            let provider = self.provider(collection);
+            let mut provider = lock(&provider);
+            let results = provider.search(query, &state.vectors, top_k, &extra_params);
+            (results, provider.get_last_plan().unwrap_or_default())
         };
 
         let _total_ms = start.elapsed().as_secs_f64() * 1000.0;
 
-        let plan = if use_ephemeral {
-            HashMap::new() // Ephemeral provider doesn't persist plan
-        } else {
-            self.provider_instances
-                .get(collection)
-                .and_then(|p| p.get_last_plan())
-                .unwrap_or_default()
-        };
-        self.last_search_plan = plan;
+        *lock(&self.last_search_plan) = plan;
 
         let ranked: Vec<HashMap<String, Value>> = results
             .iter()
@@ -457,28 +558,92 @@ impl IndexManager {
 
     /// Get last search plan
     pub fn last_search_plan(&self) -> HashMap<String, Value> {
-        self.last_search_plan.clone()
+        lock(&self.last_search_plan).clone()
     }
 
     /// Build index for a collection
+    ///
+    /// Shorthand for [`prepare_build`](Self::prepare_build), [`IndexBuild::run`]
+    /// and [`install_build`](Self::install_build) while holding `&mut self`.
     pub fn build_index(&mut self, collection: &str) -> Result<HashMap<String, Value>> {
+        let mut build = self.prepare_build(collection)?;
+        build.run();
+        self.install_build(build)
+    }
+
+    /// Snapshot a collection for an index build
+    ///
+    /// # Arguments
+    ///
+    /// * `collection` - Collection to index
+    pub fn prepare_build(&self, collection: &str) -> Result<IndexBuild> {
         let state = self
             .collections
             .get(collection)
-            .ok_or_else(|| anyhow::anyhow!("Collection not found: {}", collection))?
-            .clone();
+            .ok_or_else(|| anyhow::anyhow!("Collection not found: {}", collection))?;
+        let provider_name = self.provider_name(collection);
+
+        Ok(IndexBuild {
+            collection: collection.to_string(),
+            provider: get_provider(Some(&provider_name)),
+            provider_name,
+            vectors: state.vectors.clone(),
+            duration: Duration::ZERO,
+        })
+    }
 
-        let start = std::time::Instant::now();
-        if let Ok(provider) = self.ensure_provider(collection) {
-            provider.build(&state.vectors);
+    /// Swap in a provider built by [`IndexBuild::run`]
+    ///
+    /// Vectors upserted or deleted since [`prepare_build`](Self::prepare_build)
+    /// are applied to the new provider before it replaces the current one.
+    /// Fails if the provider of the collection was changed in the meantime.
+    ///
+    /// # Arguments
+    ///
+    /// * `build` - Finished build
+    pub fn install_build(&mut self, build: IndexBuild) -> Result<HashMap<String, Value>> {
+        let IndexBuild {
+            collection,
+            provider_name,
+            vectors,
+            mut provider,
+            duration,
+        } = build;
+        let collection = collection.as_str();
+
+        if self.provider_name(collection) != provider_name {
+            return Err(anyhow::anyhow!(
+                "Provider of collection {} changed during index build",
+                collection
+            ));
         }
-        let duration_ms = start.elapsed().as_secs_f64() * 1000.0;
-
-        let provider_name = self
-            .collection_providers
+        let state = self
+            .collections
             .get(collection)
-            .cloned()
-            .unwrap_or_else(|| "hnsw".to_string());
+            .ok_or_else(|| anyhow::anyhow!("Collection not found: {}", collection))?;
+
+        // Catch up with writes that happened while the build was running
+        for (id, payload) in &state.vectors {
+            if vectors.get(id) != Some(payload) {
+                provider.upsert(id, payload);
+            }
+        }
+        for id in vectors.keys() {
+            if !state.vectors.contains_key(id) {
+                provider.delete(id);
+            }
+        }
+        self.provider_instances
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(collection.to_string(), Arc::new(Mutex::new(provider)));
+        self.collection_providers
+            .insert(collection.to_string(), provider_name.clone());
+
+        let duration_ms = duration.as_secs_f64() * 1000.0;
+        if let Some(observer) = &self.observer {
+            observer.index_built(collection, state.vectors.len(), duration);
+        }
 
         let mut params = HashMap::new();
         params.insert(
@@ -605,7 +770,10 @@ impl IndexManager {
             .insert(collection.to_string(), state.clone());
         self.collection_providers
             .insert(collection.to_string(), provider_name.to_string());
-        self.provider_instances.remove(collection);
+        self.provider_instances
+            .get_mut()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(collection);
 
         let mut status = HashMap::new();
         status.insert("collection".to_string(), Value::from(collection));
@@ -628,6 +796,41 @@ impl IndexManager {
         Ok(status)
     }
 
+    /// Write every collection and the index manifest to disk
+    ///
+    /// Collections are also written on each change; flushing rewrites them
+    /// and records the index status of every collection, so the last build
+    /// is reported again after a restart.
+    pub fn flush(&self) -> Result<()> {
+        for (collection, state) in &self.collections {
+            self.persist_collection(collection, state)?;
+        }
+        let path = self.base_path.join(MANIFEST_PATH);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).context("Failed to create manifest directory")?;
+        }
+        write_atomic(&path, &serde_json::to_vec_pretty(&self.index_status)?)
+            .context("Failed to write index manifest")
+    }
+
+    /// Collection files in the base directory that could not be loaded
+    pub fn unloaded_collections(&self) -> Result<Vec<String>> {
+        let mut unloaded = Vec::new();
+        for entry in fs::read_dir(&self.base_path).context("Failed to read base directory")? {
+            let path = entry?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            if let Some(collection) = path.file_stem().and_then(|s| s.to_str()) {
+                if !self.collections.contains_key(collection) {
+                    unloaded.push(collection.to_string());
+                }
+            }
+        }
+        unloaded.sort();
+        Ok(unloaded)
+    }
+
     // Internal helpers
 
     fn collection_path(&self, collection: &str) -> PathBuf {
@@ -638,10 +841,25 @@ impl IndexManager {
         let path = self.collection_path(collection);
         let data = state.to_dict();
         let json = serde_json::to_string_pretty(&data)?;
-        fs::write(&path, json).context(format!("Failed to write collection {}", collection))?;
+        write_atomic(&path, json.as_bytes())
+            .context(format!("Failed to write collection {}", collection))?;
         Ok(())
     }
 
+    /// Restore the index statuses recorded by the last flush
+    fn load_manifest(&mut self) {
+        let manifest = fs::read_to_string(self.base_path.join(MANIFEST_PATH))
+            .ok()
+            .and_then(|content| {
+                serde_json::from_str::<HashMap<String, HashMap<String, Value>>>(&content).ok()
+            })
+            .unwrap_or_default();
+        self.index_status = manifest
+            .into_iter()
+            .filter(|(collection, _)| self.collections.contains_key(collection))
+            .collect();
+    }
+
     fn load_existing_state(&mut self) -> Result<()> {
         let entries = fs::read_dir(&self.base_path).context("Failed to read base directory")?;
 
@@ -701,9 +919,9 @@ impl IndexManager {
         Ok(())
     }
 
-    fn ensure_provider(&mut self, collection: &str) -> Result<&mut Box<dyn IndexProvider>> {
-        let provider_name = self
-            .collection_providers
+    /// Name of the provider configured for a collection
+    fn provider_name(&self, collection: &str) -> String {
+        self.collection_providers
             .get(collection)
             .cloned()
             .or_else(|| {
@@ -717,23 +935,43 @@ impl IndexManager {
                 let providers = get_providers();
                 providers.keys().next().cloned()
             })
-            .unwrap_or_else(|| "hnsw".to_string());
+            .unwrap_or_else(|| "hnsw".to_string())
+    }
 
-        if !self.provider_instances.contains_key(collection) {
-            let mut provider = get_provider(Some(&provider_name));
-            let state = self.collections.entry(collection.to_string()).or_default();
+    /// Provider instance of a collection, built on first use
+    fn provider(&self, collection: &str) -> SharedProvider {
+        if let Some(provider) = self
+            .provider_instances
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(collection)
+        {
+            return provider.clone();
+        }
+
+        // Build outside the instance map so other collections stay searchable
+        let mut provider = get_provider(Some(&self.provider_name(collection)));
+        if let Some(state) = self.collections.get(collection) {
             provider.build(&state.vectors);
-            self.provider_instances
-                .insert(collection.to_string(), provider);
-            self.collection_providers
-                .insert(collection.to_string(), provider_name);
         }
+        self.provider_instances
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry(collection.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(provider)))
+            .clone()
+    }
 
-        Ok(self.provider_instances.get_mut(collection).unwrap())
+    fn ensure_provider(&mut self, collection: &str) -> SharedProvider {
+        let provider_name = self.provider_name(collection);
+        self.collections.entry(collection.to_string()).or_default();
+        self.collection_providers
+            .insert(collection.to_string(), provider_name);
+        self.provider(collection)
     }
 
     fn get_ephemeral_provider(
-        &mut self,
+        &self,
         collection: &str,
         provider_name: &str,
     ) -> Result<Box<dyn IndexProvider>> {
@@ -816,6 +1054,18 @@ impl IndexManager {
     }
 }
 
+/// Write a file through a temporary file so readers never see a partial write
+fn write_atomic(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+/// Lock a mutex, recovering the data of a poisoned lock
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -934,4 +1184,123 @@ mod tests {
         assert!(providers.contains_key("hnsw"));
         assert!(providers.contains_key("ivf_pq"));
     }
+
+    #[test]
+    fn test_refresh_proofs_tracks_upserts() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = IndexManager::new(Some(temp_dir.path().to_path_buf())).unwrap();
+        let registry = ProofRegistry::new(None, Some("test-secret".to_string()));
+
+        let records = vec![
+            VectorRecord::new("a".to_string(), vec![1.0, 0.0], HashMap::new(), Some(1)),
+            VectorRecord::new("b".to_string(), vec![0.0, 1.0], HashMap::new(), Some(1)),
+        ];
+        manager
+            .upsert_vectors("proofs", records, None, None)
+            .unwrap();
+        manager.refresh_proofs(&registry);
+
+        let proof = registry.get_membership_proof("proofs", "a").unwrap();
+        assert!(proof.verify(None, Some(&registry.commit_root())));
+        let root = registry.commit_root();
+
+        let record = VectorRecord::new("c".to_string(), vec![1.0, 1.0], HashMap::new(), Some(2));
+        manager
+            .upsert_vectors("proofs", vec![record], None, None)
+            .unwrap();
+        manager.refresh_proofs(&registry);
+
+        assert_ne!(registry.commit_root(), root);
+        assert!(registry.get_membership_proof("proofs", "c").is_some());
+    }
+
+    #[test]
+    fn test_refresh_proofs_skips_unchanged_collections() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = IndexManager::new(Some(temp_dir.path().to_path_buf())).unwrap();
+        let registry = ProofRegistry::new(None, Some("test-secret".to_string()));
+
+        for collection in ["left", "right"] {
+            let record =
+                VectorRecord::new("a".to_string(), vec![1.0, 0.0], HashMap::new(), Some(1));
+            manager
+                .upsert_vectors(collection, vec![record], None, None)
+                .unwrap();
+        }
+        manager.refresh_proofs(&registry);
+        let root = registry.commit_root();
+
+        // Unchanged versions leave the registry untouched
+        manager.refresh_proofs(&registry);
+        assert_eq!(registry.commit_root(), root);
+
+        let record = VectorRecord::new("b".to_string(), vec![0.0, 1.0], HashMap::new(), Some(2));
+        manager
+            .upsert_vectors("right", vec![record], None, None)
+            .unwrap();
+        manager.refresh_proofs(&registry);
+
+        assert_ne!(registry.commit_root(), root);
+        let versions = registry.collection_versions();
+        assert_eq!(versions.get("left"), Some(&1));
+        assert_eq!(versions.get("right"), Some(&2));
+        let proof = registry.get_membership_proof("left", "a").unwrap();
+        assert!(proof.verify(None, Some(&registry.commit_root())));
+        assert!(registry.get_membership_proof("right", "b").is_some());
+    }
+
+    #[test]
+    fn test_install_build_catches_up_with_concurrent_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = IndexManager::new(Some(temp_dir.path().to_path_buf())).unwrap();
+
+        let records = vec![
+            VectorRecord::new("a".to_string(), vec![1.0, 0.0], HashMap::new(), Some(1)),
+            VectorRecord::new("b".to_string(), vec![0.0, 1.0], HashMap::new(), Some(1)),
+        ];
+        manager.upsert_vectors("docs", records, None, None).unwrap();
+
+        let mut build = manager.prepare_build("docs").unwrap();
+        assert_eq!(build.collection(), "docs");
+
+        // Writes land while the build runs on its snapshot
+        let record = VectorRecord::new("c".to_string(), vec![0.6, 0.8], HashMap::new(), Some(2));
+        manager
+            .upsert_vectors("docs", vec![record], None, None)
+            .unwrap();
+        manager
+            .delete_vectors("docs", &["a".to_string()], Some(2))
+            .unwrap();
+        build.run();
+
+        let status = manager.install_build(build).unwrap();
+        assert_eq!(status["ready"], Value::from(true));
+        assert_eq!(status["points_indexed"], Value::from(2));
+
+        let results = manager
+            .search_vectors("docs", &[0.6, 0.8], 3, None, None, None)
+            .unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r["id"].as_str().unwrap()).collect();
+        assert_eq!(ids[0], "c");
+        assert!(!ids.contains(&"a"));
+    }
+
+    #[test]
+    fn test_flush_restores_index_status_and_reports_unloaded_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = IndexManager::new(Some(temp_dir.path().to_path_buf())).unwrap();
+        let record = VectorRecord::new("a".to_string(), vec![1.0, 0.0], HashMap::new(), Some(1));
+        manager
+            .upsert_vectors("docs", vec![record], None, None)
+            .unwrap();
+        manager.build_index("docs").unwrap();
+        manager.flush().unwrap();
+
+        fs::write(temp_dir.path().join("broken.json"), "{not json").unwrap();
+        let reopened = IndexManager::new(Some(temp_dir.path().to_path_buf())).unwrap();
+        let status = reopened.get_index_status("docs");
+        assert_eq!(status["ready"], Value::from(true));
+        assert_eq!(status["points_indexed"], Value::from(1));
+        assert_eq!(reopened.unloaded_collections().unwrap(), vec!["broken"]);
+    }
 }
@@ -13,7 +13,9 @@ mod manifest_store;
 mod proof_registry;
 mod providers;
 
-pub use index_manager::{CollectionState as IndexCollectionState, IndexManager, VectorRecord};
+pub use index_manager::{
+    CollectionState as IndexCollectionState, IndexBuild, IndexManager, IndexObserver, VectorRecord,
+};
 pub use manifest_store::{
     CollectionState as ManifestCollectionState, Manifest, ManifestStore, PersistenceConfig,
 };
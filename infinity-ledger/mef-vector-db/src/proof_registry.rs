@@ -303,14 +303,31 @@ impl ProofRegistry {
         state.signature.clone()
     }
 
+    /// Get the `proof_version` each collection's root was last built from
+    pub fn collection_versions(&self) -> HashMap<String, i64> {
+        let state = self.lock.lock().unwrap();
+        state.collection_versions.clone()
+    }
+
     /// Refresh proofs from collection states
     pub fn refresh_from_collections(&self, collections: &HashMap<String, CollectionState>) {
+        let active_collections: HashSet<String> = collections.keys().cloned().collect();
+        self.refresh_collections(&active_collections, collections);
+    }
+
+    /// Refresh proofs when only the changed collections are at hand
+    ///
+    /// Collections outside `active_collections` are dropped, those in
+    /// `collections` are rebuilt if their version moved, and every other
+    /// active collection keeps its current root.
+    pub fn refresh_collections(
+        &self,
+        active_collections: &HashSet<String>,
+        collections: &HashMap<String, CollectionState>,
+    ) {
         let mut state = self.lock.lock().unwrap();
-        let _previous_root = state.commit_root.clone();
         let mut changed = false;
 
-        let active_collections: HashSet<_> = collections.keys().cloned().collect();
-
         // Remove inactive collections
         state
             .collection_roots